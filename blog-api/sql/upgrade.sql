@@ -0,0 +1,152 @@
+-- ----------------------------
+-- 已有数据库升级脚本，按顺序执行新增部分
+-- ----------------------------
+
+-- ----------------------------
+-- 文章状态：草稿、定时发布、已发布、已归档
+-- ----------------------------
+ALTER TABLE `blog`
+  ADD COLUMN `status` varchar(20) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL DEFAULT 'draft' COMMENT '文章状态 draft/scheduled/published/archived' AFTER `user_id`,
+  ADD COLUMN `publish_at` datetime NULL DEFAULT NULL COMMENT '定时发布时间' AFTER `status`,
+  ADD INDEX `status_publish_at`(`status`, `publish_at`) USING BTREE;
+UPDATE `blog` SET `status` = 'published' WHERE `is_published` = 1;
+INSERT INTO `schedule_job` (`bean_name`, `method_name`, `params`, `cron`, `status`, `remark`, `create_time`)
+VALUES ('blogPublishScheduleTask', 'publishScheduledBlogs', '', '0 * * * * ?', 1, '每分钟发布已到发布时间的定时文章', NOW());
+
+-- ----------------------------
+-- 文章历史版本
+-- ----------------------------
+CREATE TABLE IF NOT EXISTS `blog_revision`  (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `blog_id` bigint NOT NULL COMMENT '文章id',
+  `title` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT '文章标题',
+  `description` longtext CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT '描述',
+  `content` longtext CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT '文章正文',
+  `category_id` bigint NOT NULL COMMENT '文章分类',
+  `tag_ids` varchar(1000) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL DEFAULT '' COMMENT '文章标签id，逗号分隔',
+  `create_time` datetime NOT NULL COMMENT '创建时间',
+  PRIMARY KEY (`id`) USING BTREE,
+  INDEX `blog_id`(`blog_id`) USING BTREE
+) ENGINE = InnoDB CHARACTER SET = utf8mb4 COLLATE = utf8mb4_general_ci ROW_FORMAT = DYNAMIC;
+
+-- ----------------------------
+-- 异常日志指纹，用于按异常分组统计
+-- ----------------------------
+ALTER TABLE `exception_log`
+  ADD COLUMN `fingerprint` varchar(16) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL DEFAULT '' COMMENT '异常指纹，相同接口的同类异常指纹相同' AFTER `user_agent`,
+  ADD INDEX `fingerprint`(`fingerprint`) USING BTREE;
+
+-- ----------------------------
+-- 管理员两步验证(TOTP)
+-- ----------------------------
+ALTER TABLE `user`
+  ADD COLUMN `totp_secret` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NULL DEFAULT NULL COMMENT '两步验证密钥(Base32)' AFTER `role`,
+  ADD COLUMN `totp_enabled` tinyint(1) NOT NULL DEFAULT 0 COMMENT '是否已启用两步验证' AFTER `totp_secret`,
+  ADD COLUMN `totp_recovery_codes` text CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NULL COMMENT '两步验证恢复码哈希，每行一个' AFTER `totp_enabled`;
+
+-- ----------------------------
+-- 后台账号角色与状态，角色：ROLE_admin 管理员 / ROLE_editor 编辑 / ROLE_moderator 评论审核 / ROLE_viewer 只读
+-- ----------------------------
+ALTER TABLE `user`
+  ADD COLUMN `status` tinyint(1) NOT NULL DEFAULT 1 COMMENT '账号状态(0禁用 1正常)' AFTER `totp_recovery_codes`,
+  ADD UNIQUE INDEX `username`(`username`) USING BTREE;
+
+-- ----------------------------
+-- 后台API token
+-- ----------------------------
+CREATE TABLE IF NOT EXISTS `api_token`  (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `user_id` bigint NOT NULL COMMENT '所属账号id',
+  `name` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT '名称',
+  `token_hash` char(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT 'token的SHA-256',
+  `token_prefix` varchar(16) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT 'token前缀，用于识别',
+  `scopes` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT '权限范围，逗号分隔',
+  `expire_time` datetime NULL DEFAULT NULL COMMENT '过期时间，为空时永不过期',
+  `last_used_time` datetime NULL DEFAULT NULL COMMENT '最后使用时间',
+  `create_time` datetime NOT NULL COMMENT '创建时间',
+  PRIMARY KEY (`id`) USING BTREE,
+  UNIQUE INDEX `token_hash`(`token_hash`) USING BTREE,
+  INDEX `user_id`(`user_id`) USING BTREE
+) ENGINE = InnoDB CHARACTER SET = utf8mb4 COLLATE = utf8mb4_general_ci ROW_FORMAT = DYNAMIC;
+
+-- ----------------------------
+-- 文章访问密码改为哈希存储，已有的明文密码在首次校验通过后自动升级
+-- ----------------------------
+ALTER TABLE `blog`
+  MODIFY COLUMN `password` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NULL DEFAULT NULL COMMENT '密码保护(Argon2哈希)';
+
+-- ----------------------------
+-- 评论保存Markdown原文与渲染后的安全HTML，历史评论在读取时渲染
+-- ----------------------------
+ALTER TABLE `comment`
+  ADD COLUMN `content_html` text CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NULL COMMENT '评论内容渲染后的安全HTML' AFTER `content`;
+
+-- ----------------------------
+-- 退订邮件通知的邮箱
+-- ----------------------------
+CREATE TABLE IF NOT EXISTS `mail_unsubscribe`  (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `email` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT '退订的邮箱',
+  `create_time` datetime NOT NULL COMMENT '退订时间',
+  PRIMARY KEY (`id`) USING BTREE,
+  UNIQUE INDEX `email`(`email`) USING BTREE
+) ENGINE = InnoDB CHARACTER SET = utf8mb4 COLLATE = utf8mb4_general_ci ROW_FORMAT = DYNAMIC;
+
+-- ----------------------------
+-- 评论黑名单，被屏蔽的IP或邮箱无法提交评论
+-- ----------------------------
+CREATE TABLE IF NOT EXISTS `comment_block`  (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `block_type` varchar(10) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT '类型 ip/email',
+  `value` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT '屏蔽的IP或邮箱',
+  `reason` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NULL DEFAULT NULL COMMENT '屏蔽原因',
+  `create_time` datetime NOT NULL COMMENT '创建时间',
+  PRIMARY KEY (`id`) USING BTREE,
+  UNIQUE INDEX `block_type_value`(`block_type`, `value`) USING BTREE
+) ENGINE = InnoDB CHARACTER SET = utf8mb4 COLLATE = utf8mb4_general_ci ROW_FORMAT = DYNAMIC;
+
+-- ----------------------------
+-- 文章slug，Markdown导入时按slug新增或更新文章
+-- ----------------------------
+ALTER TABLE `blog`
+  ADD COLUMN `slug` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NULL DEFAULT NULL COMMENT 'Markdown导入导出使用的唯一标识' AFTER `publish_at`,
+  ADD UNIQUE INDEX `slug`(`slug`) USING BTREE;
+
+-- ----------------------------
+-- 媒体库，上传的文件按内容哈希去重
+-- ----------------------------
+CREATE TABLE IF NOT EXISTS `media`  (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `file_name` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT '原始文件名',
+  `file_key` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT '存储路径',
+  `thumbnail_key` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NULL DEFAULT NULL COMMENT '缩略图存储路径',
+  `webp_key` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NULL DEFAULT NULL COMMENT 'WebP版本存储路径',
+  `storage` varchar(20) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT '存储方式 local/s3',
+  `mime_type` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT '文件类型',
+  `size` bigint NOT NULL COMMENT '文件大小(字节)',
+  `width` int NULL DEFAULT NULL COMMENT '图片宽度',
+  `height` int NULL DEFAULT NULL COMMENT '图片高度',
+  `hash` char(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT '文件内容SHA-256',
+  `user_id` bigint NOT NULL COMMENT '上传者',
+  `create_time` datetime NOT NULL COMMENT '上传时间',
+  PRIMARY KEY (`id`) USING BTREE,
+  UNIQUE INDEX `hash`(`hash`) USING BTREE
+) ENGINE = InnoDB CHARACTER SET = utf8mb4 COLLATE = utf8mb4_general_ci ROW_FORMAT = DYNAMIC;
+
+-- ----------------------------
+-- 访问记录按完整日期(年-月-日)保存，补录历史日期时按日期更新
+-- 旧数据只有"月-日"，晚于今天的日期视为去年
+-- ----------------------------
+UPDATE `visit_record`
+SET `date` = CONCAT(IF(`date` > DATE_FORMAT(CURDATE(), '%m-%d'), YEAR(CURDATE()) - 1, YEAR(CURDATE())), '-', `date`)
+WHERE `date` REGEXP '^[0-9]{2}-[0-9]{2}$';
+
+ALTER TABLE `visit_record`
+  MODIFY COLUMN `date` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT '日期\"2024-02-23\"',
+  ADD INDEX `date`(`date`) USING BTREE;
+
+-- ----------------------------
+-- 定时清理任务执行日志，params 为保留天数
+-- ----------------------------
+INSERT INTO `schedule_job` (`bean_name`, `method_name`, `params`, `cron`, `status`, `remark`, `create_time`)
+VALUES ('scheduleJobLogTask', 'cleanJobLog', '30', '0 0 2 * * ?', 1, '每天凌晨两点，清理30天前的任务执行日志', NOW());
@@ -0,0 +1,83 @@
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+
+use super::app_state;
+use crate::constant::RoleConstant;
+use crate::entity::user;
+use crate::service::BlogMarkdownService;
+
+const USAGE: &str = "用法:
+  blog_api import <目录或zip> [--user <用户名>]  导入Markdown文章，默认作者为第一个管理员
+  blog_api export <zip文件>                       导出全部文章为Markdown压缩包";
+
+/**
+ * 命令行子命令，不带参数时启动服务
+ */
+pub struct AppCommand;
+
+impl AppCommand {
+    pub fn is_command(args: &[String]) -> bool {
+        matches!(
+            args.first().map(String::as_str),
+            Some("import" | "export" | "help")
+        )
+    }
+
+    pub async fn run(args: &[String]) -> std::io::Result<()> {
+        match args {
+            [command, path, rest @ ..] if command == "import" => Self::import(path, rest).await,
+            [command, path] if command == "export" => Self::export(path).await,
+            _ => {
+                println!("{}", USAGE);
+                Ok(())
+            }
+        }
+    }
+
+    async fn import(path: &str, options: &[String]) -> std::io::Result<()> {
+        let db = app_state::get_connection().await;
+        let username = match options {
+            [] => None,
+            [option, username] if option == "--user" => Some(username),
+            _ => return Err(Error::new(ErrorKind::InvalidInput, USAGE)),
+        };
+        let mut select = user::Entity::find().order_by_asc(user::Column::Id);
+        select = match username {
+            Some(username) => select.filter(user::Column::Username.eq(username)),
+            None => select.filter(user::Column::Role.eq(RoleConstant::ADMIN)),
+        };
+        let author = select
+            .one(&db)
+            .await
+            .map_err(Error::other)?
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, "没有检索到该用户"))?;
+
+        let files = BlogMarkdownService::read_path(Path::new(path))
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e.to_string()))?;
+        let report = BlogMarkdownService::import_files(files, author.id, None, &db)
+            .await
+            .map_err(|e| Error::other(e.to_string()))?;
+        println!(
+            "导入完成, 新增{}篇, 更新{}篇, 失败{}篇",
+            report.created,
+            report.updated,
+            report.failures.len()
+        );
+        for failure in report.failures.iter() {
+            println!("  {}: {}", failure.file, failure.reason);
+        }
+        Ok(())
+    }
+
+    async fn export(path: &str) -> std::io::Result<()> {
+        let db = app_state::get_connection().await;
+        let bytes = BlogMarkdownService::export_zip(None, &db)
+            .await
+            .map_err(|e| Error::other(e.to_string()))?;
+        std::fs::write(path, bytes)?;
+        println!("导出完成: {}", path);
+        Ok(())
+    }
+}
@@ -16,6 +16,9 @@ pub struct AppConfig {
     mysql: MysqlConfig, //Mysql链接
     redis: RedisConfig, //Redis
     log: Option<LogConfig>,
+    comment: Option<CommentConfig>, //评论过滤(可选)
+    mail: Option<MailConfig>,       //邮件通知(可选)，未配置时不发送邮件
+    media: Option<MediaConfig>,     //媒体库(可选)，未配置时使用默认值
 }
 /**
  * Redis 连接信息结构体
@@ -45,10 +48,125 @@ pub struct MysqlConfig {
  */
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct ServerConfig {
-    pub(crate) port: u16,             //端口
-    pub(crate) host: String,          //IP地址
-    pub(crate) front_adderss: String, //前端页面地址
-    pub(crate) token_expires: i64,    //token 过期时间
+    pub(crate) port: u16,                    //端口
+    pub(crate) host: String,                 //IP地址
+    pub(crate) front_adderss: String,        //前端页面地址
+    pub(crate) token_expires: i64,           //token 过期时间
+    pub(crate) robots: Option<String>,       //robots.txt 规则(可选)
+    pub(crate) revision_retain: Option<u64>, //每篇文章保留的历史版本数量(可选)
+}
+/**
+ * 评论过滤配置，未配置的项使用默认值
+ */
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct CommentConfig {
+    pub(crate) moderation: bool,              //新评论是否全部需要审核
+    pub(crate) rate_window: u64,              //频率限制统计窗口(秒)
+    pub(crate) ip_limit: i64,                 //同一IP在窗口内最多评论次数
+    pub(crate) visitor_limit: i64,            //同一访客在窗口内最多评论次数
+    pub(crate) moderate_links: usize,         //链接数达到该值时需要审核
+    pub(crate) max_links: usize,              //链接数超过该值时拒绝
+    pub(crate) blocked_words: Vec<String>,    //屏蔽关键词(不区分大小写)
+    pub(crate) blocked_patterns: Vec<String>, //屏蔽正则
+    pub(crate) sort: String,                  //默认排序 oldest / newest / replies
+    pub(crate) max_depth: usize,              //最大嵌套层数，更深的回复平铺到该层
+}
+
+impl Default for CommentConfig {
+    fn default() -> Self {
+        Self {
+            moderation: false,
+            rate_window: 10 * 60,
+            ip_limit: 10,
+            visitor_limit: 5,
+            moderate_links: 2,
+            max_links: 5,
+            blocked_words: vec![],
+            blocked_patterns: vec![],
+            sort: "oldest".to_string(),
+            max_depth: 1,
+        }
+    }
+}
+/**
+ * 邮件配置，transport 可选 smtp(SMTP发送) / file(写入目录) / log(只记录日志)
+ */
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct MailConfig {
+    pub(crate) transport: String,    //发送方式
+    pub(crate) host: String,         //SMTP服务器
+    pub(crate) port: u16,            //SMTP端口
+    pub(crate) username: String,     //SMTP用户名
+    pub(crate) password: String,     //SMTP密码
+    pub(crate) security: String,     //加密方式 tls / starttls / none
+    pub(crate) from: String,         //发件人，如 DreamBlog <noreply@example.com>
+    pub(crate) admin_email: String,  //博主邮箱，接收新评论与待审核评论通知
+    pub(crate) file_dir: String,     //file方式的输出目录
+    pub(crate) template_dir: String, //邮件模板目录，模板不存在时使用内置模板
+    pub(crate) api_address: String,  //后端地址，用于生成退订链接
+    pub(crate) secret: String,       //退订链接签名密钥
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        Self {
+            transport: "log".to_string(),
+            host: String::new(),
+            port: 465,
+            username: String::new(),
+            password: String::new(),
+            security: "tls".to_string(),
+            from: String::new(),
+            admin_email: String::new(),
+            file_dir: "./mail".to_string(),
+            template_dir: "./config/mail".to_string(),
+            api_address: String::new(),
+            secret: String::new(),
+        }
+    }
+}
+/**
+ * 媒体库配置，storage 可选 local(本地目录) / s3(S3兼容存储，需启用 s3 feature)
+ */
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct MediaConfig {
+    pub(crate) storage: String,            //存储方式
+    pub(crate) local_dir: String,          //local方式的存储目录，通过 /upload 访问
+    pub(crate) public_url: String,         //文件访问地址前缀，可设置为CDN地址
+    pub(crate) max_size: usize,            //单个文件最大字节数
+    pub(crate) allowed_types: Vec<String>, //允许上传的文件类型，按文件内容识别
+    pub(crate) thumbnail_size: u32,        //缩略图最大宽高(像素)
+    pub(crate) s3_endpoint: String,        //S3服务地址，如 https://s3.us-east-1.amazonaws.com
+    pub(crate) s3_region: String,          //S3区域
+    pub(crate) s3_bucket: String,          //S3存储桶
+    pub(crate) s3_access_key: String,      //S3 Access Key
+    pub(crate) s3_secret_key: String,      //S3 Secret Key
+}
+
+impl Default for MediaConfig {
+    fn default() -> Self {
+        Self {
+            storage: "local".to_string(),
+            local_dir: "./upload".to_string(),
+            public_url: "/upload".to_string(),
+            max_size: 10 * 1024 * 1024,
+            allowed_types: vec![
+                "image/jpeg".to_string(),
+                "image/png".to_string(),
+                "image/gif".to_string(),
+                "image/webp".to_string(),
+            ],
+            thumbnail_size: 400,
+            s3_endpoint: String::new(),
+            s3_region: "us-east-1".to_string(),
+            s3_bucket: String::new(),
+            s3_access_key: String::new(),
+            s3_secret_key: String::new(),
+        }
+    }
 }
 pub static CONFIG: LazyLock<AppConfig> = LazyLock::new(|| {
     let args: Vec<String> = env::args().collect();
@@ -108,6 +226,18 @@ impl AppConfig {
         self.server.clone()
     }
 
+    pub fn get_comment_config(&self) -> CommentConfig {
+        self.comment.clone().unwrap_or_default()
+    }
+
+    pub fn get_mail_config(&self) -> Option<MailConfig> {
+        self.mail.clone()
+    }
+
+    pub fn get_media_config(&self) -> MediaConfig {
+        self.media.clone().unwrap_or_default()
+    }
+
     fn build_config(path: String) -> Result<AppConfig, DataBaseError> {
         let yaml_str = match fs::read_to_string(path.clone()) {
             Ok(str) => str,
@@ -9,11 +9,15 @@ use super::RedisClient;
 use crate::controller::{
     about_controller,
     admin::{self, tag_controller},
-    archive_controller, blog_controller, comment_controller, friend_controller, index_controller,
-    moment_controller, user_controller,
+    archive_controller, blog_controller, comment_controller, feed_controller, friend_controller,
+    index_controller, media_controller, moment_controller, sitemap_controller, user_controller,
+};
+use crate::middleware::{AppClaims, ExceptionLogger, OperationLogger, VisiLog};
+use crate::schedule::Scheduler;
+use crate::service::SearchService;
+use actix_jwt_session::{
+    Duration, Extractors, JwtTtl, RefreshTtl, UseJwt, JWT_HEADER_NAME, REFRESH_HEADER_NAME,
 };
-use crate::middleware::{AppClaims, VisiLog};
-use actix_jwt_session::{Duration, Extractors, JwtTtl, RefreshTtl, UseJwt, JWT_HEADER_NAME};
 //use actix_web::middleware::Logger;
 use actix_web::web::Data;
 use actix_web::{web, App, HttpServer};
@@ -40,6 +44,12 @@ impl AppServer {
             //,
             // CONFIG.clone(),
         );
+        //启动定时任务调度器
+        Scheduler::start(app_state.get_mysql_pool().clone()).await;
+        //构建全文检索索引
+        if let Err(e) = SearchService::rebuild_index(app_state.get_mysql_pool()).await {
+            log::error!("构建全文检索索引失败:{}", e);
+        }
         let redis_pool = RedisClient::get_redis_pool().await;
         let app_data = Data::new(app_state.clone());
         HttpServer::new(move || {
@@ -49,15 +59,22 @@ impl AppServer {
                 .app_data(Data::new(refresh_ttl))
                 .app_data(app_data.clone())
                 .use_jwt::<AppClaims>(
-                    Extractors::default().with_jwt_header(JWT_HEADER_NAME),
+                    Extractors::default()
+                        .with_jwt_header(JWT_HEADER_NAME)
+                        .with_refresh_header(REFRESH_HEADER_NAME),
                     Some(redis_pool.clone()),
                 )
                 .wrap(VisiLog::default())
+                .wrap(ExceptionLogger)
                 //.wrap(Logger::default())
                 //.wrap(ErrorHandler::default())
                 .configure(Self::view_router)
                 //admin
-                .service(web::scope("/admin/").configure(Self::admin_router))
+                .service(
+                    web::scope("/admin/")
+                        .wrap(OperationLogger)
+                        .configure(Self::admin_router),
+                )
                 .default_service(web::to(index_controller::default))
         })
         .bind_auto_h2c(format!("{}:{}", server_config.host, server_config.port))?
@@ -81,9 +98,18 @@ impl AppServer {
             .service(comment_controller::get_comments)
             .service(blog_controller::check_blog_password)
             .service(user_controller::login)
+            .service(user_controller::login_totp)
             .service(blog_controller::search_blog)
             .service(moment_controller::moment_like)
-            .service(comment_controller::save_comment);
+            .service(comment_controller::save_comment)
+            .service(comment_controller::unsubscribe_page)
+            .service(comment_controller::unsubscribe)
+            .service(feed_controller::blog_feed)
+            .service(feed_controller::moment_feed)
+            .service(sitemap_controller::sitemap)
+            .service(sitemap_controller::sitemap_page)
+            .service(sitemap_controller::robots)
+            .service(media_controller::media_file);
     }
 
     /**
@@ -91,6 +117,18 @@ impl AppServer {
      */
     fn admin_router(cfg: &mut web::ServiceConfig) {
         cfg.service(user_controller::login)
+            .service(user_controller::login_totp)
+            .service(user_controller::refresh)
+            .service(user_controller::logout)
+            .service(admin::session_controller::get_sessions)
+            .service(admin::session_controller::revoke_session)
+            .service(admin::user_controller::get_users)
+            .service(admin::user_controller::create_user)
+            .service(admin::user_controller::update_user_role)
+            .service(admin::user_controller::update_user_status)
+            .service(admin::api_token_controller::get_api_tokens)
+            .service(admin::api_token_controller::create_api_token)
+            .service(admin::api_token_controller::delete_api_token)
             .service(admin::dashboard_controller::dashboard) //.default_service(web::to(adminIndexController::default)),
             .service(admin::blog_controller::blogs)
             .service(admin::blog_controller::visibility)
@@ -101,6 +139,15 @@ impl AppServer {
             .service(admin::blog_controller::update_blog)
             .service(admin::blog_controller::create_blog)
             .service(admin::blog_controller::delete_blog)
+            .service(admin::blog_controller::rebuild_search_index)
+            .service(admin::blog_controller::import_markdown)
+            .service(admin::blog_controller::export_markdown)
+            .service(admin::blog_controller::revisions)
+            .service(admin::blog_controller::revision_diff)
+            .service(admin::blog_controller::restore_revision)
+            .service(admin::media_controller::find_media)
+            .service(admin::media_controller::upload_media)
+            .service(admin::media_controller::delete_media)
             .service(admin::moment_controller::moments)
             .service(admin::moment_controller::moment_published)
             .service(admin::moment_controller::delete_moment)
@@ -117,7 +164,17 @@ impl AppServer {
             .service(admin::comment_controller::find_blog_id_and_title)
             .service(admin::comment_controller::delete_comment)
             .service(admin::comment_controller::update_comment)
+            .service(admin::comment_controller::find_pending_comments)
+            .service(admin::comment_controller::update_comments_published)
+            .service(admin::comment_controller::delete_comments)
+            .service(admin::comment_controller::find_comment_blocks)
+            .service(admin::comment_controller::create_comment_block)
+            .service(admin::comment_controller::delete_comment_block)
             .service(admin::account_controller::change_account)
+            .service(admin::account_controller::totp_setup)
+            .service(admin::account_controller::totp_enable)
+            .service(admin::account_controller::totp_disable)
+            .service(admin::account_controller::totp_recovery_codes)
             .service(admin::friend_controller::get_friend_info)
             .service(admin::friend_controller::update_friend)
             .service(admin::friend_controller::delete_friend_by_id)
@@ -126,11 +183,23 @@ impl AppServer {
             .service(admin::schedule_controller::update_job_status)
             .service(admin::schedule_controller::delete_job_by_id)
             .service(admin::schedule_controller::add_job)
+            .service(admin::schedule_controller::edit_job)
+            .service(admin::schedule_controller::run_job_once)
+            .service(admin::schedule_controller::get_job_log_list)
+            .service(admin::schedule_controller::delete_job_log_by_log_id)
             .service(admin::site_setting_controller::get_site_setting_data)
             .service(admin::site_setting_controller::update_site_settings)
             .service(admin::visit_log_controller::get_visit_log_list)
             .service(admin::visit_log_controller::delete_visit_log_by_id)
+            .service(admin::login_log_controller::get_login_log_list)
+            .service(admin::login_log_controller::delete_login_log_by_id)
+            .service(admin::operation_log_controller::get_operation_log_list)
+            .service(admin::operation_log_controller::delete_operation_log_by_id)
+            .service(admin::exception_log_controller::get_exception_log_groups)
+            .service(admin::exception_log_controller::get_exception_logs_by_fingerprint)
+            .service(admin::exception_log_controller::delete_exception_logs)
             .service(admin::visitor_controller::get_visitor_list)
-            .service(admin::visitor_controller::delete_visitor);
+            .service(admin::visitor_controller::delete_visitor)
+            .service(admin::visitor_controller::sync_visit_info);
     }
 }
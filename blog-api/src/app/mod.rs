@@ -1,9 +1,11 @@
+mod app_command;
 mod app_state;
 mod app_server;
 mod app_config;
 mod redis_client;
 
+pub use app_command::AppCommand;
 pub use app_server::AppServer;
 pub use app_state::AppState;
-pub use app_config::CONFIG;
+pub use app_config::{CommentConfig, MailConfig, MediaConfig, CONFIG};
 pub use redis_client::RedisClient;
\ No newline at end of file
@@ -1,8 +1,13 @@
 //配置项
-use blog_api::AppServer;
+use blog_api::{AppCommand, AppServer};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
+    //1. 命令行子命令 import / export
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if AppCommand::is_command(&args) {
+        return AppCommand::run(&args).await;
+    }
     //2. Service run
     AppServer::run().await
 }
@@ -0,0 +1,257 @@
+use atom_syndication::{
+    CategoryBuilder as AtomCategoryBuilder, ContentBuilder, EntryBuilder, FeedBuilder,
+    FixedDateTime, LinkBuilder, PersonBuilder, Text,
+};
+use chrono::{DateTime, Local};
+use rss::{CategoryBuilder, ChannelBuilder, GuidBuilder, ItemBuilder};
+use serde_json::json;
+
+/**
+ * 订阅源格式
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    Json,
+}
+
+impl FeedFormat {
+    /**
+     * 根据请求路径后缀判断格式：feed.xml / atom.xml / feed.json
+     */
+    pub fn from_path(path: &str) -> Self {
+        if path.ends_with("atom.xml") {
+            Self::Atom
+        } else if path.ends_with(".json") {
+            Self::Json
+        } else {
+            Self::Rss
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Rss => "application/rss+xml; charset=utf-8",
+            Self::Atom => "application/atom+xml; charset=utf-8",
+            Self::Json => "application/feed+json; charset=utf-8",
+        }
+    }
+}
+
+/**
+ * 订阅条目，summary 与 content 均为HTML
+ */
+#[derive(Debug, Clone)]
+pub struct FeedItem {
+    pub title: String,
+    pub link: String,
+    pub summary: Option<String>,
+    pub content: String,
+    pub categories: Vec<String>,
+    pub published: DateTime<Local>,
+    pub updated: DateTime<Local>,
+}
+
+/**
+ * 订阅源，可输出为 RSS 2.0、Atom 1.0 与 JSON Feed 1.1
+ */
+#[derive(Debug, Clone)]
+pub struct Feed {
+    pub title: String,
+    pub description: String,
+    pub author: String,
+    //站点页面地址
+    pub link: String,
+    //订阅源自身地址
+    pub feed_url: String,
+    pub items: Vec<FeedItem>,
+}
+
+impl Feed {
+    /**
+     * 最后修改时间：条目中最新的更新时间
+     */
+    pub fn last_modified(&self) -> Option<DateTime<Local>> {
+        self.items.iter().map(|item| item.updated).max()
+    }
+
+    pub fn render(&self, format: FeedFormat) -> String {
+        match format {
+            FeedFormat::Rss => self.to_rss(),
+            FeedFormat::Atom => self.to_atom(),
+            FeedFormat::Json => self.to_json(),
+        }
+    }
+
+    fn to_rss(&self) -> String {
+        let items: Vec<rss::Item> = self
+            .items
+            .iter()
+            .map(|item| {
+                ItemBuilder::default()
+                    .title(Some(item.title.clone()))
+                    .link(Some(item.link.clone()))
+                    .guid(Some(
+                        GuidBuilder::default()
+                            .value(item.link.clone())
+                            .permalink(true)
+                            .build(),
+                    ))
+                    .description(item.summary.clone())
+                    .content(Some(item.content.clone()))
+                    .author(Some(self.author.clone()))
+                    .categories(
+                        item.categories
+                            .iter()
+                            .map(|name| CategoryBuilder::default().name(name.clone()).build())
+                            .collect::<Vec<_>>(),
+                    )
+                    .pub_date(Some(item.published.to_rfc2822()))
+                    .build()
+            })
+            .collect();
+        ChannelBuilder::default()
+            .title(self.title.clone())
+            .link(self.link.clone())
+            .description(self.description.clone())
+            .language(Some("zh-CN".to_string()))
+            .last_build_date(self.last_modified().map(|time| time.to_rfc2822()))
+            .items(items)
+            .build()
+            .to_string()
+    }
+
+    fn to_atom(&self) -> String {
+        let entries: Vec<atom_syndication::Entry> = self
+            .items
+            .iter()
+            .map(|item| {
+                EntryBuilder::default()
+                    .title(item.title.clone())
+                    .id(item.link.clone())
+                    .link(
+                        LinkBuilder::default()
+                            .href(item.link.clone())
+                            .rel("alternate")
+                            .build(),
+                    )
+                    .published(Some(FixedDateTime::from(item.published)))
+                    .updated(FixedDateTime::from(item.updated))
+                    .summary(item.summary.clone().map(Text::html))
+                    .content(Some(
+                        ContentBuilder::default()
+                            .value(Some(item.content.clone()))
+                            .content_type(Some("html".to_string()))
+                            .build(),
+                    ))
+                    .categories(
+                        item.categories
+                            .iter()
+                            .map(|name| AtomCategoryBuilder::default().term(name.clone()).build())
+                            .collect::<Vec<_>>(),
+                    )
+                    .build()
+            })
+            .collect();
+        FeedBuilder::default()
+            .title(self.title.clone())
+            .id(self.feed_url.clone())
+            .subtitle(Some(Text::plain(self.description.clone())))
+            .updated(FixedDateTime::from(
+                self.last_modified().unwrap_or_else(Local::now),
+            ))
+            .author(PersonBuilder::default().name(self.author.clone()).build())
+            .link(
+                LinkBuilder::default()
+                    .href(self.link.clone())
+                    .rel("alternate")
+                    .build(),
+            )
+            .link(
+                LinkBuilder::default()
+                    .href(self.feed_url.clone())
+                    .rel("self")
+                    .build(),
+            )
+            .entries(entries)
+            .build()
+            .to_string()
+    }
+
+    fn to_json(&self) -> String {
+        let items: Vec<serde_json::Value> = self
+            .items
+            .iter()
+            .map(|item| {
+                json!({
+                    "id": item.link,
+                    "url": item.link,
+                    "title": item.title,
+                    "content_html": item.content,
+                    "summary": item.summary,
+                    "date_published": item.published.to_rfc3339(),
+                    "date_modified": item.updated.to_rfc3339(),
+                    "tags": item.categories,
+                })
+            })
+            .collect();
+        json!({
+            "version": "https://jsonfeed.org/version/1.1",
+            "title": self.title,
+            "home_page_url": self.link,
+            "feed_url": self.feed_url,
+            "description": self.description,
+            "language": "zh-CN",
+            "authors": [{ "name": self.author }],
+            "items": items,
+        })
+        .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Feed, FeedFormat, FeedItem};
+    use chrono::{Local, TimeZone};
+
+    fn feed() -> Feed {
+        Feed {
+            title: "Zero Blog".to_string(),
+            description: "Zero Blog".to_string(),
+            author: "路人".to_string(),
+            link: "http://localhost:8080".to_string(),
+            feed_url: "http://localhost:8089/feed.xml".to_string(),
+            items: vec![FeedItem {
+                title: "Rust & Actix".to_string(),
+                link: "http://localhost:8080/blog/1".to_string(),
+                summary: Some("<p>摘要</p>".to_string()),
+                content: "<p>正文</p>".to_string(),
+                categories: vec!["后端".to_string(), "Rust".to_string()],
+                published: Local.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap(),
+                updated: Local.with_ymd_and_hms(2024, 5, 2, 12, 0, 0).unwrap(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_render_feed() {
+        let feed = feed();
+        let rss = feed.render(FeedFormat::Rss);
+        assert!(rss.contains("<title>Rust &amp; Actix</title>"));
+        assert!(rss.contains("<category>后端</category>"));
+        let atom = feed.render(FeedFormat::Atom);
+        assert!(atom.contains("rel=\"self\""));
+        assert!(atom.contains("2024-05-02T12:00:00"));
+        let json: serde_json::Value = serde_json::from_str(&feed.render(FeedFormat::Json)).unwrap();
+        assert_eq!(json["items"][0]["content_html"], "<p>正文</p>");
+        assert_eq!(json["authors"][0]["name"], "路人");
+    }
+
+    #[test]
+    fn test_feed_format() {
+        assert_eq!(FeedFormat::from_path("/feed.xml"), FeedFormat::Rss);
+        assert_eq!(FeedFormat::from_path("/moments/atom.xml"), FeedFormat::Atom);
+        assert_eq!(FeedFormat::from_path("/feed.json"), FeedFormat::Json);
+    }
+}
@@ -0,0 +1,306 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+
+/// Hexo 摘要分隔符
+const MORE_SEPARATOR: &str = "<!-- more -->";
+/// 导出时的时间格式
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/**
+ * 带 YAML front matter 的 Markdown 文章，兼容 Hexo / Hugo / Jekyll 的常用字段
+ */
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MarkdownPost {
+    pub slug: String,
+    pub title: String,
+    pub date: Option<NaiveDateTime>,
+    pub updated: Option<NaiveDateTime>,
+    //文章只有一个分类，取第一个
+    pub categories: Vec<String>,
+    pub tags: Vec<String>,
+    pub description: String,
+    pub cover: String,
+    pub password: Option<String>,
+    //未指定时为None，导入时保留已有文章的状态
+    pub published: Option<bool>,
+    //文章状态 draft/scheduled/published/archived，优先于published
+    pub status: Option<String>,
+    pub publish_at: Option<NaiveDateTime>,
+    pub content: String,
+}
+
+/**
+ * 导出的 front matter，字段顺序即输出顺序
+ */
+#[derive(Serialize)]
+struct ExportFrontMatter<'a> {
+    title: &'a str,
+    slug: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    updated: Option<String>,
+    categories: &'a [String],
+    tags: &'a [String],
+    description: &'a str,
+    cover: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    published: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    publish_at: Option<String>,
+}
+
+impl MarkdownPost {
+    /**
+     * 解析 Markdown 文件，file_name 用于在缺少 slug / 标题时生成
+     */
+    pub fn parse(file_name: &str, text: &str) -> Result<Self, String> {
+        let text = text.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+        let (front_matter, body) = Self::split(&text)?;
+        let stem = Self::file_stem(file_name);
+
+        let mut body = body.trim_start_matches('\n').to_string();
+        let title = match Self::get_str(&front_matter, &["title"]) {
+            Some(title) => title,
+            //没有标题时使用正文一级标题，并从正文中移除
+            None => match body.strip_prefix("# ") {
+                Some(rest) => {
+                    let (title, rest) = rest.split_once('\n').unwrap_or((rest, ""));
+                    let title = title.trim().to_string();
+                    body = rest.trim_start_matches('\n').to_string();
+                    title
+                }
+                None => stem.clone(),
+            },
+        };
+        if title.trim().is_empty() {
+            return Err("文章标题不能为空".to_string());
+        }
+
+        let slug = Self::get_str(&front_matter, &["slug"])
+            .or_else(|| {
+                Self::get_str(&front_matter, &["permalink", "url"]).map(|permalink| {
+                    let permalink = permalink.trim_end_matches('/');
+                    let name = permalink.rsplit('/').next().unwrap_or_default();
+                    name.trim_end_matches(".html").to_string()
+                })
+            })
+            .unwrap_or(stem);
+        let slug = slug::slugify(slug);
+        if slug.is_empty() {
+            return Err("无法根据文件名生成slug，请在front matter中指定slug".to_string());
+        }
+
+        let description = match Self::get_str(&front_matter, &["description", "excerpt", "summary"])
+        {
+            Some(description) => description,
+            None => match body.split_once(MORE_SEPARATOR) {
+                Some((excerpt, _)) => excerpt.trim().to_string(),
+                None => String::new(),
+            },
+        };
+        //Jekyll 使用 published: false，Hugo 使用 draft: true
+        let published = match (
+            Self::get_bool(&front_matter, "published"),
+            Self::get_bool(&front_matter, "draft"),
+        ) {
+            (None, None) => None,
+            (published, draft) => Some(published.unwrap_or(true) && !draft.unwrap_or(false)),
+        };
+
+        Ok(Self {
+            slug,
+            title: title.trim().to_string(),
+            date: Self::get_str(&front_matter, &["date"]).and_then(|s| Self::parse_date(&s)),
+            updated: Self::get_str(&front_matter, &["updated", "lastmod", "last_modified_at"])
+                .and_then(|s| Self::parse_date(&s)),
+            categories: Self::get_list(&front_matter, &["categories", "category"]),
+            tags: Self::get_list(&front_matter, &["tags", "tag"]),
+            description,
+            cover: Self::get_str(
+                &front_matter,
+                &["cover", "image", "thumbnail", "banner", "featured_image"],
+            )
+            .unwrap_or_default(),
+            password: Self::get_str(&front_matter, &["password"]).filter(|s| !s.is_empty()),
+            published,
+            status: Self::get_str(&front_matter, &["status"]).map(|s| s.to_lowercase()),
+            publish_at: Self::get_str(&front_matter, &["publish_at", "publishAt"])
+                .and_then(|s| Self::parse_date(&s)),
+            content: body.trim_end().to_string(),
+        })
+    }
+
+    /**
+     * 生成带 front matter 的 Markdown 文本
+     */
+    pub fn to_markdown(&self) -> String {
+        let front_matter = ExportFrontMatter {
+            title: &self.title,
+            slug: &self.slug,
+            date: self.date.map(|date| date.format(DATE_FORMAT).to_string()),
+            updated: self
+                .updated
+                .map(|date| date.format(DATE_FORMAT).to_string()),
+            categories: &self.categories,
+            tags: &self.tags,
+            description: &self.description,
+            cover: &self.cover,
+            password: self.password.as_deref(),
+            published: self.published,
+            status: self.status.as_deref(),
+            publish_at: self
+                .publish_at
+                .map(|date| date.format(DATE_FORMAT).to_string()),
+        };
+        let yaml = serde_yaml::to_string(&front_matter).unwrap_or_default();
+        format!("---\n{}---\n\n{}\n", yaml, self.content)
+    }
+
+    /**
+     * 拆分 front matter 与正文，没有 front matter 时返回空表
+     */
+    fn split(text: &str) -> Result<(Mapping, &str), String> {
+        let Some(rest) = text.strip_prefix("---\n") else {
+            return Ok((Mapping::new(), text));
+        };
+        let mut offset = 0;
+        for line in rest.split_inclusive('\n') {
+            let end = line.trim_end();
+            if end == "---" || end == "..." {
+                let yaml = &rest[..offset];
+                let front_matter = match serde_yaml::from_str::<Value>(yaml) {
+                    Ok(Value::Mapping(mapping)) => mapping,
+                    Ok(Value::Null) => Mapping::new(),
+                    Ok(_) => return Err("front matter 格式不正确".to_string()),
+                    Err(e) => return Err(format!("front matter 解析失败:{}", e)),
+                };
+                return Ok((front_matter, &rest[offset + line.len()..]));
+            }
+            offset += line.len();
+        }
+        Err("front matter 缺少结束标记 ---".to_string())
+    }
+
+    fn file_stem(file_name: &str) -> String {
+        let name = file_name.rsplit(['/', '\\']).next().unwrap_or(file_name);
+        let stem = name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(name);
+        //Jekyll 文件名 2024-01-02-hello-world.md
+        match (stem.get(..10), stem.get(11..)) {
+            (Some(prefix), Some(rest))
+                if stem.as_bytes()[10] == b'-'
+                    && NaiveDate::parse_from_str(prefix, "%Y-%m-%d").is_ok() =>
+            {
+                rest.to_string()
+            }
+            _ => stem.to_string(),
+        }
+    }
+
+    fn get_str(front_matter: &Mapping, keys: &[&str]) -> Option<String> {
+        keys.iter()
+            .find_map(|key| match front_matter.get(*key)? {
+                Value::String(s) => Some(s.trim().to_string()),
+                Value::Number(n) => Some(n.to_string()),
+                Value::Bool(b) => Some(b.to_string()),
+                _ => None,
+            })
+            .filter(|s| !s.is_empty())
+    }
+
+    fn get_bool(front_matter: &Mapping, key: &str) -> Option<bool> {
+        match front_matter.get(key)? {
+            Value::Bool(b) => Some(*b),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /**
+     * 字符串或列表，Hexo 的多级分类 [[父, 子]] 会被展开
+     */
+    fn get_list(front_matter: &Mapping, keys: &[&str]) -> Vec<String> {
+        fn flatten(value: &Value, list: &mut Vec<String>) {
+            match value {
+                Value::String(s) if !s.trim().is_empty() => list.push(s.trim().to_string()),
+                Value::Number(n) => list.push(n.to_string()),
+                Value::Sequence(values) => values.iter().for_each(|value| flatten(value, list)),
+                _ => {}
+            }
+        }
+        let mut list = vec![];
+        if let Some(value) = keys.iter().find_map(|key| front_matter.get(*key)) {
+            flatten(value, &mut list);
+        }
+        list.dedup();
+        list
+    }
+
+    fn parse_date(value: &str) -> Option<NaiveDateTime> {
+        if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+            return Some(date.with_timezone(&Local).naive_local());
+        }
+        ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+            .or_else(|| {
+                NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MarkdownPost;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_front_matter() {
+        let hexo = "---\ntitle: 你好 Rust\ndate: 2024-01-02 10:20:30\ncategories:\n  - [后端, Rust]\ntags: [rust, actix]\ncover: /img/a.png\n---\n摘要部分\n<!-- more -->\n正文\r\n";
+        let post = MarkdownPost::parse("posts/hello-rust.md", hexo).unwrap();
+        assert_eq!(post.slug, "hello-rust");
+        assert_eq!(post.title, "你好 Rust");
+        assert_eq!(
+            post.date,
+            NaiveDate::from_ymd_opt(2024, 1, 2)
+                .unwrap()
+                .and_hms_opt(10, 20, 30)
+        );
+        assert_eq!(post.categories, vec!["后端", "Rust"]);
+        assert_eq!(post.tags, vec!["rust", "actix"]);
+        assert_eq!(post.description, "摘要部分");
+        assert_eq!(post.published, None);
+
+        let hugo = "---\ntitle: \"Draft\"\ndraft: true\nslug: my-post\ntags: go\n---\nbody";
+        let post = MarkdownPost::parse("content/index.md", hugo).unwrap();
+        assert_eq!(post.slug, "my-post");
+        assert_eq!(post.tags, vec!["go"]);
+        assert_eq!(post.published, Some(false));
+
+        let jekyll = "# Jekyll 标题\n\n正文";
+        let post = MarkdownPost::parse("_posts/2023-05-06-jekyll-post.markdown", jekyll).unwrap();
+        assert_eq!(post.slug, "jekyll-post");
+        assert_eq!(post.title, "Jekyll 标题");
+        assert_eq!(post.content, "正文");
+
+        assert!(MarkdownPost::parse("a.md", "---\ntitle: [\n---\n").is_err());
+        assert!(MarkdownPost::parse("a.md", "---\ntitle: a\n").is_err());
+
+        let mut exported = MarkdownPost::parse("posts/hello-rust.md", hexo).unwrap();
+        exported.password = Some("$argon2id$hash".to_string());
+        exported.published = Some(false);
+        exported.status = Some("scheduled".to_string());
+        exported.publish_at = NaiveDate::from_ymd_opt(2030, 1, 1)
+            .unwrap()
+            .and_hms_opt(8, 0, 0);
+        let parsed = MarkdownPost::parse("x.md", &exported.to_markdown()).unwrap();
+        assert_eq!(parsed, exported);
+    }
+}
@@ -6,6 +6,7 @@ use std::path::Path;
 use std::sync::LazyLock;
 
 use actix_web::dev::ServiceRequest;
+use actix_web::HttpRequest;
 
 use crate::common::ip_value::ToUIntIP;
 
@@ -88,6 +89,11 @@ impl IpRegion {
 
     /// 获取真实的客户端IP地址，考虑代理和转发的情况
     pub fn get_real_client_ip(req: &ServiceRequest) -> String {
+        Self::get_request_ip(req.request())
+    }
+
+    /// 同 get_real_client_ip，用于 handler 中的 HttpRequest
+    pub fn get_request_ip(req: &HttpRequest) -> String {
         // 按优先级尝试获取IP地址
         let headers = req.headers();
 
@@ -0,0 +1,120 @@
+use std::sync::LazyLock;
+
+use jieba_rs::Jieba;
+use tantivy::tokenizer::{Token, TokenStream, Tokenizer};
+
+/// jieba 分词器(加载默认词典)
+static JIEBA: LazyLock<Jieba> = LazyLock::new(Jieba::new);
+
+/**
+ * 基于 jieba 的中文分词器，供全文检索索引使用
+ */
+#[derive(Clone, Default)]
+pub struct JiebaTokenizer;
+
+pub struct JiebaTokenStream {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl JiebaTokenizer {
+    /**
+     * 索引分词：搜索引擎模式，长词会再切分出短词以提高召回
+     */
+    fn cut_for_index(text: &str) -> Vec<Token> {
+        let base = text.as_ptr() as usize;
+        JIEBA
+            .cut_for_search(text, true)
+            .into_iter()
+            .filter(|word| Self::is_word(word))
+            .enumerate()
+            .map(|(position, word)| {
+                //分词结果均为原文切片，通过指针计算字节偏移
+                let offset_from = word.as_ptr() as usize - base;
+                Token {
+                    offset_from,
+                    offset_to: offset_from + word.len(),
+                    position,
+                    text: word.to_string(),
+                    position_length: 1,
+                }
+            })
+            .collect()
+    }
+
+    /**
+     * 查询分词：精确模式，转小写并去重，忽略空白与标点
+     */
+    pub fn cut_for_query(text: &str) -> Vec<String> {
+        let mut words: Vec<String> = vec![];
+        for word in JIEBA.cut(text, true) {
+            if !Self::is_word(word) {
+                continue;
+            }
+            let word = word.to_lowercase();
+            if !words.contains(&word) {
+                words.push(word);
+            }
+        }
+        words
+    }
+
+    fn is_word(word: &str) -> bool {
+        word.chars().any(char::is_alphanumeric)
+    }
+}
+
+impl Tokenizer for JiebaTokenizer {
+    type TokenStream<'a> = JiebaTokenStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> JiebaTokenStream {
+        JiebaTokenStream {
+            tokens: Self::cut_for_index(text),
+            index: 0,
+        }
+    }
+}
+
+impl TokenStream for JiebaTokenStream {
+    fn advance(&mut self) -> bool {
+        if self.index < self.tokens.len() {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.index - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.index - 1]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JiebaTokenizer;
+    use tantivy::tokenizer::{TokenStream, Tokenizer};
+
+    #[test]
+    fn test_cut_for_query() {
+        let words = JiebaTokenizer::cut_for_query("Rust 异步编程 (tokio)*");
+        assert!(words.contains(&"rust".to_string()));
+        assert!(words.contains(&"tokio".to_string()));
+        assert!(words.iter().all(|w| !w.trim().is_empty() && w != "*"));
+    }
+
+    #[test]
+    fn test_token_offsets() {
+        let text = "我爱中文分词";
+        let mut tokenizer = JiebaTokenizer;
+        let mut stream = tokenizer.token_stream(text);
+        while stream.advance() {
+            let token = stream.token();
+            assert_eq!(&text[token.offset_from..token.offset_to], token.text);
+        }
+    }
+}
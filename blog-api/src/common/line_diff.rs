@@ -0,0 +1,159 @@
+use serde::Serialize;
+
+/// 参与LCS计算的最大行数乘积，超过后中间差异部分整体按删除+新增输出
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffTag {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/**
+ * 差异行，行号从1开始，删除行没有新行号，新增行没有旧行号
+ */
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiffLine {
+    pub tag: DiffTag,
+    #[serde(rename(serialize = "oldLine"))]
+    pub old_line: Option<usize>,
+    #[serde(rename(serialize = "newLine"))]
+    pub new_line: Option<usize>,
+    pub content: String,
+}
+
+pub struct LineDiff;
+
+impl LineDiff {
+    /**
+     * 按行比较文本，先去掉相同的首尾行，再对中间部分求最长公共子序列
+     */
+    pub fn diff(old: &str, new: &str) -> Vec<DiffLine> {
+        let old_lines: Vec<&str> = old.lines().collect();
+        let new_lines: Vec<&str> = new.lines().collect();
+        let prefix = old_lines
+            .iter()
+            .zip(new_lines.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let suffix = old_lines[prefix..]
+            .iter()
+            .rev()
+            .zip(new_lines[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let old_mid = &old_lines[prefix..old_lines.len() - suffix];
+        let new_mid = &new_lines[prefix..new_lines.len() - suffix];
+
+        let mut result = Vec::with_capacity(old_lines.len().max(new_lines.len()));
+        for (index, line) in old_lines[..prefix].iter().enumerate() {
+            result.push(Self::line(DiffTag::Equal, Some(index), Some(index), line));
+        }
+        Self::diff_middle(old_mid, new_mid, prefix, &mut result);
+        let old_start = old_lines.len() - suffix;
+        let new_start = new_lines.len() - suffix;
+        for (offset, line) in old_lines[old_start..].iter().enumerate() {
+            result.push(Self::line(
+                DiffTag::Equal,
+                Some(old_start + offset),
+                Some(new_start + offset),
+                line,
+            ));
+        }
+        result
+    }
+
+    fn diff_middle(old: &[&str], new: &[&str], offset: usize, result: &mut Vec<DiffLine>) {
+        let (n, m) = (old.len(), new.len());
+        if n.saturating_mul(m) > MAX_LCS_CELLS {
+            for (index, line) in old.iter().enumerate() {
+                result.push(Self::line(
+                    DiffTag::Delete,
+                    Some(offset + index),
+                    None,
+                    line,
+                ));
+            }
+            for (index, line) in new.iter().enumerate() {
+                result.push(Self::line(
+                    DiffTag::Insert,
+                    None,
+                    Some(offset + index),
+                    line,
+                ));
+            }
+            return;
+        }
+        //lcs[i][j]：old[i..] 与 new[j..] 的最长公共子序列长度
+        let mut lcs = vec![0u32; (n + 1) * (m + 1)];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i * (m + 1) + j] = if old[i] == new[j] {
+                    lcs[(i + 1) * (m + 1) + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * (m + 1) + j].max(lcs[i * (m + 1) + j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && old[i] == new[j] {
+                result.push(Self::line(
+                    DiffTag::Equal,
+                    Some(offset + i),
+                    Some(offset + j),
+                    old[i],
+                ));
+                i += 1;
+                j += 1;
+            } else if i < n && (j == m || lcs[(i + 1) * (m + 1) + j] >= lcs[i * (m + 1) + j + 1]) {
+                result.push(Self::line(DiffTag::Delete, Some(offset + i), None, old[i]));
+                i += 1;
+            } else {
+                result.push(Self::line(DiffTag::Insert, None, Some(offset + j), new[j]));
+                j += 1;
+            }
+        }
+    }
+
+    fn line(tag: DiffTag, old: Option<usize>, new: Option<usize>, content: &str) -> DiffLine {
+        DiffLine {
+            tag,
+            old_line: old.map(|index| index + 1),
+            new_line: new.map(|index| index + 1),
+            content: content.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DiffTag, LineDiff};
+
+    #[test]
+    fn test_line_diff() {
+        let diff = LineDiff::diff("a\nb\nc\nd", "a\nc\ne\nd");
+        let tags: Vec<(DiffTag, &str)> = diff
+            .iter()
+            .map(|line| (line.tag, line.content.as_str()))
+            .collect();
+        assert_eq!(
+            tags,
+            vec![
+                (DiffTag::Equal, "a"),
+                (DiffTag::Delete, "b"),
+                (DiffTag::Equal, "c"),
+                (DiffTag::Insert, "e"),
+                (DiffTag::Equal, "d"),
+            ]
+        );
+        assert_eq!((diff[2].old_line, diff[2].new_line), (Some(3), Some(2)));
+        assert_eq!((diff[4].old_line, diff[4].new_line), (Some(4), Some(4)));
+        assert!(LineDiff::diff("", "")
+            .iter()
+            .all(|line| line.tag == DiffTag::Equal));
+        assert_eq!(LineDiff::diff("", "x")[0].tag, DiffTag::Insert);
+    }
+}
@@ -0,0 +1,84 @@
+use std::fs;
+use std::path::Path;
+
+/**
+ * 邮件模板：第一行为邮件标题，其余为HTML正文
+ * {{name}} 替换为转义后的值，{{{name}}} 原样替换(只用于已经安全渲染的HTML)
+ */
+pub struct MailTemplate;
+
+impl MailTemplate {
+    /// 评论收到回复，发送给被回复的访客
+    pub const REPLY: &'static str = "reply";
+    /// 新评论，发送给博主
+    pub const COMMENT: &'static str = "comment";
+    /// 评论待审核，发送给博主
+    pub const MODERATION: &'static str = "moderation";
+
+    /**
+     * 优先读取模板目录中的 {name}.html，不存在时使用内置模板
+     */
+    pub fn load(template_dir: &str, name: &str) -> String {
+        let path = Path::new(template_dir).join(format!("{}.html", name));
+        match fs::read_to_string(&path) {
+            Ok(template) => template,
+            Err(_) => Self::builtin(name).to_string(),
+        }
+    }
+
+    /**
+     * 渲染模板，返回(标题, 正文)
+     */
+    pub fn render(template: &str, vars: &[(&str, &str)]) -> (String, String) {
+        let (subject, body) = template.split_once('\n').unwrap_or((template, ""));
+        let mut subject = subject.trim().to_string();
+        let mut body = body.to_string();
+        for (name, value) in vars {
+            subject = subject.replace(&format!("{{{{{}}}}}", name), value);
+            body = body
+                .replace(&format!("{{{{{{{}}}}}}}", name), value)
+                .replace(&format!("{{{{{}}}}}", name), &Self::escape(value));
+        }
+        (subject, body)
+    }
+
+    fn builtin(name: &str) -> &'static str {
+        match name {
+            Self::REPLY => include_str!("../../config/mail/reply.html"),
+            Self::COMMENT => include_str!("../../config/mail/comment.html"),
+            Self::MODERATION => include_str!("../../config/mail/moderation.html"),
+            _ => "",
+        }
+    }
+
+    fn escape(value: &str) -> String {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&#39;")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MailTemplate;
+
+    #[test]
+    fn test_mail_template() {
+        let (subject, body) = MailTemplate::render(
+            "{{name}}的评论\n<p>{{name}}</p>{{{content}}}",
+            &[("name", "<b>访客</b>"), ("content", "<p>安全HTML</p>")],
+        );
+        assert_eq!(subject, "<b>访客</b>的评论");
+        assert_eq!(body, "<p>&lt;b&gt;访客&lt;/b&gt;</p><p>安全HTML</p>");
+
+        let (subject, body) = MailTemplate::render(
+            &MailTemplate::load("./not-exist", MailTemplate::REPLY),
+            &[("title", "文章")],
+        );
+        assert_eq!(subject, "你在《文章》的评论收到了新回复");
+        assert!(body.contains("{{unsubscribe_url}}"));
+    }
+}
@@ -1,10 +1,14 @@
 use comrak::{
     adapters::{HeadingAdapter, HeadingMeta},
-    markdown_to_html_with_plugins,
-    nodes::Sourcepos,
-    Options, Plugins,
+    format_html, markdown_to_html_with_plugins,
+    nodes::{NodeValue, Sourcepos},
+    parse_document, Arena, Options, Plugins,
 };
 use std::io::{self, Write};
+
+/// 安全渲染允许的链接协议，不带协议的相对链接同样允许
+const SAFE_URL_SCHEMES: [&str; 3] = ["http:", "https:", "mailto:"];
+
 pub struct MarkdownParser;
 impl MarkdownParser {
     pub fn parser_html(markdown: String) -> String {
@@ -14,6 +18,84 @@ impl MarkdownParser {
         plugins.render.heading_adapter = Some(&adapter);
         markdown_to_html_with_plugins(markdown.as_str(), &options, &plugins)
     }
+
+    /**
+     * 渲染访客提交的内容(评论)：不输出原始HTML，只保留安全协议的链接，链接带 rel="nofollow ugc"
+     */
+    pub fn parser_comment_html(markdown: &str) -> String {
+        Self::render_safe(markdown, "nofollow ugc")
+    }
+
+    fn render_safe(markdown: &str, rel: &str) -> String {
+        let mut options = Options::default();
+        options.extension.autolink = true;
+        options.extension.strikethrough = true;
+        //原始HTML转义输出，而不是直接丢弃
+        options.render.unsafe_ = false;
+        options.render.escape = true;
+        let arena = Arena::new();
+        let root = parse_document(&arena, markdown, &options);
+        for node in root.descendants() {
+            if let NodeValue::Link(link) | NodeValue::Image(link) =
+                &mut node.data.borrow_mut().value
+            {
+                if !Self::is_safe_url(&link.url) {
+                    link.url.clear();
+                }
+            }
+        }
+        let mut html = vec![];
+        if let Err(e) = format_html(root, &options, &mut html) {
+            log::error!("Markdown渲染失败:{}", e);
+            return String::new();
+        }
+        let html = String::from_utf8_lossy(&html).into_owned();
+        //原始HTML已被转义，输出中的<a标签只可能来自Markdown链接
+        html.replace("<a href=", &format!("<a rel=\"{}\" href=", rel))
+    }
+
+    /**
+     * 链接协议是否安全：协议在白名单中，或者是不带协议的相对链接
+     */
+    fn is_safe_url(url: &str) -> bool {
+        let url = url.trim().to_lowercase();
+        let scheme_end = url.find(':');
+        let path_start = url.find(['/', '?', '#']);
+        match (scheme_end, path_start) {
+            (None, _) => true,
+            (Some(colon), Some(path)) if path < colon => true,
+            _ => SAFE_URL_SCHEMES
+                .iter()
+                .any(|scheme| url.starts_with(scheme)),
+        }
+    }
+
+    /**
+     * 提取Markdown中的纯文本(用于全文检索)
+     */
+    pub fn parser_text(markdown: &str) -> String {
+        let arena = Arena::new();
+        let root = parse_document(&arena, markdown, &Options::default());
+        let mut text = String::new();
+        for node in root.descendants() {
+            match &node.data.borrow().value {
+                NodeValue::Text(literal) => text.push_str(literal),
+                NodeValue::Code(code) => text.push_str(&code.literal),
+                NodeValue::CodeBlock(block) => {
+                    text.push_str(&block.literal);
+                    text.push(' ');
+                }
+                NodeValue::SoftBreak | NodeValue::LineBreak => text.push(' '),
+                NodeValue::Paragraph | NodeValue::Heading(_) | NodeValue::Item(_) => {
+                    if !text.is_empty() && !text.ends_with(' ') {
+                        text.push(' ');
+                    }
+                }
+                _ => {}
+            }
+        }
+        text.trim().to_string()
+    }
 }
 
 #[cfg(test)]
@@ -49,6 +131,27 @@ mod tests {
         print_html("# Here is a [link](/)", &options, &plugins);
     }
 
+    #[test]
+    fn test_parser_text() {
+        let text = super::MarkdownParser::parser_text("# 标题\n\n一段**加粗**文字`code`\n\n- 列表");
+        assert_eq!(text, "标题 一段加粗文字code 列表");
+    }
+
+    #[test]
+    fn test_parser_comment_html() {
+        let html = super::MarkdownParser::parser_comment_html(
+            "<script>alert(1)</script>\n\n[a](javascript:alert(1)) [b](https://example.com) https://c.com",
+        );
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("javascript:"));
+        assert!(html.contains(r#"<a rel="nofollow ugc" href="https://example.com">b</a>"#));
+        assert!(html.contains(r#"<a rel="nofollow ugc" href="https://c.com">"#));
+        assert!(super::MarkdownParser::is_safe_url("/about#top"));
+        assert!(!super::MarkdownParser::is_safe_url(" JavaScript:alert(1)"));
+        assert!(!super::MarkdownParser::is_safe_url("data:text/html,x"));
+    }
+
     fn print_html(document: &str, options: &Options, plugins: &Plugins) {
         let html = markdown_to_html_with_plugins(document, options, plugins);
         println!("{}", html);
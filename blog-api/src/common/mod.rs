@@ -4,15 +4,27 @@
  * @LastEditors: lurendie
  * @LastEditTime: 2024-05-15 19:14:37
  */
+mod feed;
+mod front_matter;
 mod ip_region;
 mod ip_value;
+mod jieba_tokenizer;
+mod line_diff;
+mod mail_template;
 mod markdown;
 mod pagination;
 pub mod param_utils;
+mod totp;
 mod type_value;
 mod user_agent;
+pub use feed::{Feed, FeedFormat, FeedItem};
+pub use front_matter::MarkdownPost;
 pub use ip_region::IpRegion;
+pub use jieba_tokenizer::JiebaTokenizer;
+pub use line_diff::{DiffLine, LineDiff};
+pub use mail_template::MailTemplate;
 pub use markdown::MarkdownParser;
 pub use param_utils::ParamUtils;
+pub use totp::Totp;
 pub use type_value::TypeValue;
 pub use user_agent::{UserAgent, UserAgentInfo};
@@ -0,0 +1,140 @@
+use hmac::{Hmac, Mac};
+use rand::{rngs::OsRng, RngCore};
+use sha1::Sha1;
+
+/// RFC 4648 Base32 字母表
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/**
+ * RFC 6238 基于时间的一次性密码(HMAC-SHA1，6位，30秒)
+ */
+pub struct Totp;
+
+impl Totp {
+    /// 动态码位数
+    pub const DIGITS: u32 = 6;
+    /// 时间步长(秒)
+    pub const PERIOD: u64 = 30;
+    /// 校验时前后允许偏差的时间步数
+    pub const SKEW: u64 = 1;
+
+    /**
+     * 生成随机密钥(160位)，Base32编码
+     */
+    pub fn generate_secret() -> String {
+        let mut secret = [0u8; 20];
+        OsRng.fill_bytes(&mut secret);
+        Self::base32_encode(&secret)
+    }
+
+    /**
+     * 认证器App使用的 otpauth URI，可直接生成二维码
+     */
+    pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+        format!(
+            "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+            urlencoding::encode(issuer),
+            urlencoding::encode(account),
+            secret,
+            urlencoding::encode(issuer),
+            Self::DIGITS,
+            Self::PERIOD
+        )
+    }
+
+    /**
+     * 校验动态码，通过时返回匹配的时间步，用于防止同一动态码重复使用
+     */
+    pub fn verify(secret: &str, code: &str, unix_time: u64) -> Option<u64> {
+        let key = Self::base32_decode(secret)?;
+        let code = code.trim();
+        if code.len() != Self::DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let current = unix_time / Self::PERIOD;
+        (current.saturating_sub(Self::SKEW)..=current + Self::SKEW)
+            .find(|step| Self::format_code(Self::hotp(&key, *step)) == code)
+    }
+
+    /**
+     * RFC 4226 HOTP：HMAC-SHA1 后动态截断
+     */
+    fn hotp(key: &[u8], counter: u64) -> u32 {
+        let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC可以接受任意长度的密钥");
+        mac.update(&counter.to_be_bytes());
+        let hash = mac.finalize().into_bytes();
+        let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+        u32::from_be_bytes([
+            hash[offset] & 0x7f,
+            hash[offset + 1],
+            hash[offset + 2],
+            hash[offset + 3],
+        ])
+    }
+
+    fn format_code(value: u32) -> String {
+        format!(
+            "{:0width$}",
+            value % 10u32.pow(Self::DIGITS),
+            width = Self::DIGITS as usize
+        )
+    }
+
+    fn base32_encode(data: &[u8]) -> String {
+        let mut result = String::with_capacity(data.len().div_ceil(5) * 8);
+        let (mut buffer, mut bits) = (0u32, 0u32);
+        for byte in data {
+            buffer = (buffer << 8) | *byte as u32;
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                result.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+            }
+        }
+        if bits > 0 {
+            result.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+        }
+        result
+    }
+
+    /**
+     * Base32解码，忽略大小写、空格与填充符
+     */
+    fn base32_decode(text: &str) -> Option<Vec<u8>> {
+        let mut result = Vec::with_capacity(text.len() * 5 / 8);
+        let (mut buffer, mut bits) = (0u32, 0u32);
+        for c in text.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+            let value = BASE32_ALPHABET
+                .iter()
+                .position(|a| *a as char == c.to_ascii_uppercase())?;
+            buffer = (buffer << 5) | value as u32;
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                result.push((buffer >> bits) as u8);
+            }
+        }
+        (!result.is_empty()).then_some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Totp;
+
+    #[test]
+    fn test_totp() {
+        //RFC 6238 附录B测试密钥 "12345678901234567890"
+        let secret = Totp::base32_encode(b"12345678901234567890");
+        assert_eq!(secret, "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+        assert_eq!(Totp::verify(&secret, "287082", 59), Some(1));
+        assert_eq!(Totp::verify(&secret, "081804", 1111111109), Some(37037036));
+        assert_eq!(Totp::verify(&secret, "287082", 89), Some(1));
+        assert_eq!(Totp::verify(&secret, "287082", 150), None);
+        assert_eq!(Totp::verify(&secret, "28708", 59), None);
+        assert_eq!(
+            Totp::base32_decode(&Totp::generate_secret()).unwrap().len(),
+            20
+        );
+    }
+}
@@ -7,6 +7,16 @@ impl BlogInfoConstant {
     pub(crate) const NEW_BLOG_PAGE_SIZE: usize = 3;
     //每页显示5条博客简介
     pub(crate) const PAGE_SIZE: u64 = 5;
+    //同一访客在该时间(秒)内重复浏览同一博客不计入浏览量
+    pub(crate) const VIEW_INTERVAL_SECONDS: u64 = 60 * 60;
+    //同一IP在统计窗口内文章密码错误该次数后暂停校验
+    pub(crate) const MAX_PASSWORD_FAILURES: i64 = 10;
+    //文章密码错误次数统计窗口(秒)
+    pub(crate) const PASSWORD_FAIL_WINDOW_SECONDS: u64 = 10 * 60;
+    //文章解锁token有效期(秒)
+    pub(crate) const UNLOCK_TOKEN_SECONDS: i64 = 2 * 60 * 60;
+    //前端携带文章解锁token的请求头
+    pub(crate) const UNLOCK_TOKEN_HEADER: &'static str = "Blog-Token";
 
     pub(crate) const _PRIVATE_BLOG_DESCRIPTION: &str = "此文章受密码保护！";
 }
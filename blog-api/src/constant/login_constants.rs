@@ -0,0 +1,26 @@
+pub struct LoginConstant;
+
+impl LoginConstant {
+    //同一用户名在同一IP上，统计窗口内失败该次数后锁定该用户名在该IP上的登录
+    pub(crate) const MAX_USERNAME_IP_FAILURES: i64 = 5;
+    //已存在的用户名在统计窗口内累计失败该次数后锁定(分布式猜测)，登录成功过的IP不受限制
+    pub(crate) const MAX_USERNAME_FAILURES: i64 = 50;
+    //同一IP在统计窗口内失败该次数后锁定
+    pub(crate) const MAX_IP_FAILURES: i64 = 20;
+    //登录失败次数统计窗口(秒)
+    pub(crate) const FAIL_WINDOW_SECONDS: u64 = 15 * 60;
+    //首次锁定时长(秒)，之后每次锁定时长翻倍
+    pub(crate) const BASE_LOCK_SECONDS: u64 = 60;
+    //最长锁定时长(秒)
+    pub(crate) const MAX_LOCK_SECONDS: u64 = 24 * 60 * 60;
+    //登录成功过的IP的保留时间(秒)
+    pub(crate) const TRUSTED_IP_SECONDS: u64 = 30 * 24 * 60 * 60;
+    //锁定次数的保留时间(秒)，过期后锁定时长重新从首次计算
+    pub(crate) const LOCK_LEVEL_SECONDS: u64 = 24 * 60 * 60;
+    //两步验证预登录token有效期(秒)
+    pub(crate) const PRE_AUTH_SECONDS: u64 = 5 * 60;
+    //认证器App中显示的发行方
+    pub(crate) const TOTP_ISSUER: &'static str = "DreamBlog";
+    //两步验证恢复码数量
+    pub(crate) const RECOVERY_CODE_COUNT: usize = 10;
+}
@@ -2,10 +2,22 @@
  * blog 配置
  */
 mod blog_info_constants;
+/**
+ * 登录限制配置
+ */
+mod login_constants;
+/**
+ * 操作日志配置
+ */
+mod operation_constants;
 /**
 Redis key配置
 */
 mod redis_key_constants;
+/**
+ * 后台角色配置
+ */
+mod role_constants;
 /**
 SiteSetting配置
 */
@@ -13,7 +25,10 @@ mod site_setting_constants;
 mod visit_constants;
 
 pub use blog_info_constants::BlogInfoConstant;
+pub use login_constants::LoginConstant;
+pub use operation_constants::OperationConstant;
 pub use redis_key_constants::RedisKeyConstant;
+pub use role_constants::RoleConstant;
 pub use site_setting_constants::SiteSettingConstant;
 pub use visit_constants::VisitBehavior;
 pub use visit_constants::VisitBehaviorType;
@@ -0,0 +1,120 @@
+/**
+ * 后台写操作的描述：(请求方式, 路由, 描述)，路由为去掉 /admin 前缀后的路由定义
+ */
+const OPERATION_DESCRIPTIONS: &[(&str, &str, &str)] = &[
+    ("POST", "/account", "修改账号密码"),
+    ("POST", "/account/totp/setup", "获取两步验证密钥"),
+    ("POST", "/account/totp/enable", "启用两步验证"),
+    ("POST", "/account/totp/disable", "关闭两步验证"),
+    ("POST", "/account/totp/recoveryCodes", "重新生成恢复码"),
+    ("POST", "/logout", "退出登录"),
+    ("DELETE", "/sessions", "注销登录会话"),
+    ("POST", "/user", "创建后台账号"),
+    ("PUT", "/user/{id}/role", "分配账号角色"),
+    ("PUT", "/user/{id}/status", "修改账号状态"),
+    ("POST", "/apiToken", "创建API token"),
+    ("DELETE", "/apiToken", "撤销API token"),
+    ("PUT", "/blog/{blog_id}/visibility", "更新文章可见性"),
+    ("PUT", "/blog/top", "更新文章置顶状态"),
+    ("PUT", "/blog/recommend", "更新文章推荐状态"),
+    ("PUT", "/blog", "更新文章"),
+    ("POST", "/blog", "发布文章"),
+    ("DELETE", "/blog", "删除文章"),
+    ("POST", "/blog/searchIndex", "重建全文检索索引"),
+    ("POST", "/blogs/import", "导入Markdown文章"),
+    (
+        "POST",
+        "/blog/{blog_id}/revisions/{revision_id}/restore",
+        "恢复文章历史版本",
+    ),
+    ("POST", "/media", "上传媒体文件"),
+    ("DELETE", "/media", "删除媒体文件"),
+    ("POST", "/moment", "发布动态"),
+    ("PUT", "/moment/published", "更新动态公开状态"),
+    ("PUT", "/moment", "更新动态"),
+    ("DELETE", "/moment", "删除动态"),
+    ("PUT", "/category", "新增或更新分类"),
+    ("DELETE", "/category", "删除分类"),
+    ("PUT", "/tag", "更新标签"),
+    ("POST", "/tag", "新增标签"),
+    ("DELETE", "/tag", "删除标签"),
+    ("PUT", "/comment", "更新评论"),
+    ("DELETE", "/comment", "删除评论"),
+    ("PUT", "/comments/published", "批量审核评论"),
+    ("DELETE", "/comments", "批量删除评论"),
+    ("POST", "/commentBlock", "屏蔽评论IP或邮箱"),
+    ("DELETE", "/commentBlock", "取消屏蔽评论IP或邮箱"),
+    ("PUT", "/friend/published", "更新友链公开状态"),
+    ("POST", "/friend", "新增友链"),
+    ("PUT", "/friend", "更新友链"),
+    ("DELETE", "/friend", "删除友链"),
+    ("PUT", "/friendInfo/commentEnabled", "更新友链页面评论开关"),
+    ("PUT", "/friendInfo/content", "更新友链页面信息"),
+    ("PUT", "/job/status", "更新定时任务状态"),
+    ("POST", "/job/run", "立即执行定时任务"),
+    ("DELETE", "/job", "删除定时任务"),
+    ("POST", "/job", "新增定时任务"),
+    ("PUT", "/job", "更新定时任务"),
+    ("DELETE", "/job/log", "删除任务日志"),
+    ("POST", "/siteSettings", "更新站点设置"),
+    ("DELETE", "/visitLog", "删除访问日志"),
+    ("DELETE", "/loginLog", "删除登录日志"),
+    ("DELETE", "/operationLogs", "删除操作日志"),
+    ("DELETE", "/exceptionLogs", "删除异常日志"),
+    ("DELETE", "/visitor", "删除访客"),
+    ("POST", "/visitor/sync", "同步访客信息"),
+];
+
+/**
+ * 写入操作日志前需要脱敏的参数名(忽略大小写，包含即脱敏)
+ */
+const SENSITIVE_PARAMS: [&str; 5] = ["password", "secret", "token", "credential", "code"];
+
+/**
+ * 不记录请求体的接口：请求体为上传的文件内容(如Markdown导入中的front matter含明文密码)
+ */
+const UNLOGGED_BODY_ROUTES: [&str; 1] = ["/blogs/import"];
+
+pub struct OperationConstant;
+
+impl OperationConstant {
+    /// 操作日志参数最大长度，与 operation_log.param 字段长度一致
+    pub const MAX_PARAM_LENGTH: usize = 2000;
+    /// 记录参数时最多读取的请求体长度，超出时只记录查询参数
+    pub const MAX_BODY_LENGTH: usize = 64 * 1024;
+    /// 脱敏后的参数值
+    pub const MASK: &'static str = "******";
+
+    /**
+     * 根据请求方式与匹配到的路由获取操作描述，未配置时为 "请求方式 路由"
+     */
+    pub fn description(method: &str, pattern: &str) -> String {
+        let route = pattern
+            .strip_prefix("/admin")
+            .map(|route| format!("/{}", route.trim_start_matches('/')))
+            .unwrap_or_else(|| pattern.to_string());
+        OPERATION_DESCRIPTIONS
+            .iter()
+            .find(|(m, r, _)| *m == method && *r == route)
+            .map(|(_, _, description)| description.to_string())
+            .unwrap_or_else(|| format!("{} {}", method, route))
+    }
+
+    /**
+     * 该请求路径的请求体是否可以记录
+     */
+    pub fn is_body_logged(path: &str) -> bool {
+        let path = path.trim_end_matches('/');
+        !UNLOGGED_BODY_ROUTES
+            .iter()
+            .any(|route| path.ends_with(route))
+    }
+
+    /**
+     * 参数名是否需要脱敏
+     */
+    pub fn is_sensitive(name: &str) -> bool {
+        let name = name.to_lowercase();
+        SENSITIVE_PARAMS.iter().any(|param| name.contains(param))
+    }
+}
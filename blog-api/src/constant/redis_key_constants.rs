@@ -38,10 +38,95 @@ impl RedisKeyConstant {
     //  * 博客归档key
     //  */
     pub const ARCHIVE_BLOG_MAP: &str = "archiveBlogMap";
-    // /**
-    //  * 博客访问量key
-    //  */
-    //pub const BLOG_VIEWS_MAP: &str = "blogViewsMap";
+    /**
+     * 站点地图key
+     * sitemapXml : {{"index","sitemap.xml"},{"1","sitemap-1.xml"}}
+     */
+    pub const SITEMAP_XML: &str = "sitemapXml";
+    /**
+     * 文章变更后需要失效的缓存
+     */
+    pub const BLOG_CACHE_KEYS: [&str; 6] = [
+        Self::HOME_BLOG_INFO_LIST,
+        Self::NEW_BLOG_LIST,
+        Self::RANDOM_BLOG_LIST,
+        Self::ARCHIVE_BLOG_MAP,
+        Self::TAG_CLOUD_LIST,
+        Self::SITEMAP_XML,
+    ];
+    /**
+     * 分类变更后需要失效的缓存(文章列表包含分类名)
+     */
+    pub const CATEGORY_CACHE_KEYS: [&str; 5] = [
+        Self::CATEGORY_NAME_LIST,
+        Self::HOME_BLOG_INFO_LIST,
+        Self::NEW_BLOG_LIST,
+        Self::RANDOM_BLOG_LIST,
+        Self::SITEMAP_XML,
+    ];
+    /**
+     * 标签变更后需要失效的缓存(文章列表包含标签)
+     */
+    pub const TAG_CACHE_KEYS: [&str; 5] = [
+        Self::TAG_CLOUD_LIST,
+        Self::HOME_BLOG_INFO_LIST,
+        Self::NEW_BLOG_LIST,
+        Self::RANDOM_BLOG_LIST,
+        Self::SITEMAP_XML,
+    ];
+    /**
+     * 浏览量同步到数据库后需要失效的缓存(缓存中的浏览量为数据库的值)
+     */
+    pub const BLOG_VIEWS_CACHE_KEYS: [&str; 3] = [
+        Self::HOME_BLOG_INFO_LIST,
+        Self::NEW_BLOG_LIST,
+        Self::RANDOM_BLOG_LIST,
+    ];
+    /**
+     * 博客访问量key(尚未同步到数据库的增量)
+     * blogViewsMap : {{"博客id","浏览量增量"}}
+     */
+    pub const BLOG_VIEWS_MAP: &str = "blogViewsMap";
+    /**
+     * 访客浏览博客去重key前缀 blogViewVisitor:博客id:访客标识
+     */
+    pub const BLOG_VIEW_VISITOR: &str = "blogViewVisitor";
+    /**
+     * 文章密码校验失败次数key前缀 blogPasswordFail:IP
+     */
+    pub const BLOG_PASSWORD_FAIL: &str = "blogPasswordFail";
+    /**
+     * 评论频率限制key前缀 commentRateLimit:ip:IP / commentRateLimit:visitor:访客标识
+     */
+    pub const COMMENT_RATE_LIMIT: &str = "commentRateLimit";
+    /**
+     * 登录失败次数key前缀 loginFailCount:user:用户名 / loginFailCount:userIp:用户名@IP / loginFailCount:ip:IP
+     */
+    pub const LOGIN_FAIL_COUNT: &str = "loginFailCount";
+    /**
+     * 登录锁定key前缀 loginLock:user:用户名 / loginLock:userIp:用户名@IP / loginLock:ip:IP，过期时间即剩余锁定时长
+     */
+    pub const LOGIN_LOCK: &str = "loginLock";
+    /**
+     * 登录锁定次数key前缀，用于递增锁定时长
+     */
+    pub const LOGIN_LOCK_LEVEL: &str = "loginLockLevel";
+    /**
+     * 登录成功过的IP loginTrustedIp:用户名:IP，从这些IP登录不受用户名锁定限制
+     */
+    pub const LOGIN_TRUSTED_IP: &str = "loginTrustedIp";
+    /**
+     * 两步验证预登录token key前缀 loginPreAuth:token，值为用户名
+     */
+    pub const LOGIN_PRE_AUTH: &str = "loginPreAuth";
+    /**
+     * 已使用的两步验证时间步 totpUsedStep:账号id:时间步，防止动态码重复使用
+     */
+    pub const TOTP_USED_STEP: &str = "totpUsedStep";
+    /**
+     * 登录会话Hash loginSession:账号id，字段为jwt_id，值为会话信息JSON
+     */
+    pub const LOGIN_SESSION: &str = "loginSession";
     // /**
     //  * 访客标识码key
     //  */
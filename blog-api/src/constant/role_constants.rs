@@ -0,0 +1,22 @@
+pub struct RoleConstant;
+
+impl RoleConstant {
+    //管理员，拥有全部权限
+    pub(crate) const ADMIN: &'static str = "ROLE_admin";
+    //编辑，只能管理自己的文章
+    pub(crate) const EDITOR: &'static str = "ROLE_editor";
+    //评论审核，只能管理评论
+    pub(crate) const MODERATOR: &'static str = "ROLE_moderator";
+    //只读，只能查看后台数据
+    pub(crate) const VIEWER: &'static str = "ROLE_viewer";
+    //可以登录后台的角色
+    pub(crate) const ROLES: [&'static str; 4] =
+        [Self::ADMIN, Self::EDITOR, Self::MODERATOR, Self::VIEWER];
+
+    /**
+     * 是否为可以登录后台的角色
+     */
+    pub fn is_valid(role: &str) -> bool {
+        Self::ROLES.contains(&role)
+    }
+}
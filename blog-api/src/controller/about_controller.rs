@@ -1,6 +1,6 @@
 use crate::service::AboutService;
-use crate::{app::AppState, error::WebErrorCode, model::ApiResponse};
-use actix_web::{get, web, Responder};
+use crate::{app::AppState, model::ApiResponse};
+use actix_web::{get, web, HttpResponse, Responder};
 use rbs::value;
 
 //关于我
@@ -10,8 +10,6 @@ pub(crate) async fn about(app: web::Data<AppState>) -> impl Responder {
         Ok(value_map) => {
             ApiResponse::success(Some(value!(value_map))).json()
         }
-        Err(e) => {
-            ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json()
-        }
+        Err(e) => HttpResponse::from_error(e),
     }
 }
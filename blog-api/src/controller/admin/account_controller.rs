@@ -1,11 +1,14 @@
 use crate::entity::user;
 use crate::middleware::AppClaims;
-use crate::service::UserService;
+use crate::model::User;
+use crate::service::{SessionService, TotpService, UserService};
 use crate::{app::AppState, model::ApiResponse};
-use actix_jwt_session::Authenticated;
-use actix_web::{routes, web, Responder};
+use actix_jwt_session::{Authenticated, SessionStorage};
+use actix_web::{routes, web, HttpResponse, Responder};
 use chrono::Utc;
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+use rbs::value::map::ValueMap;
+use rbs::{value, Value};
+use sea_orm::{ActiveModelTrait, EntityTrait, Set};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -23,33 +26,54 @@ pub struct AccountUpdate {
 pub async fn change_account(
     auth: Authenticated<AppClaims>,
     app: web::Data<AppState>,
+    store: web::Data<SessionStorage>,
     account_update: web::Json<AccountUpdate>,
 ) -> impl Responder {
     let db = app.get_mysql_pool();
-    let username = auth.subject.clone();
 
-    // 查询当前用户
-    let result = user::Entity::find()
-        .filter(user::Column::Username.eq(username))
+    // 按账号id查询当前用户，修改用户名后旧token仍然可用
+    let result = user::Entity::find_by_id(auth.account_id as i64)
         .one(db)
         .await;
 
     match result {
         Ok(Some(user_model)) => {
-            let mut active_user: user::ActiveModel = user_model.into();
+            let mut active_user: user::ActiveModel = user_model.clone().into();
             let now = Utc::now().naive_utc();
 
-            // 更新字段
+            // 先完成所有校验再写入，校验失败时不修改任何数据
             if let Some(username) = &account_update.username {
                 // 检查用户名是否已被其他用户使用
                 if let Ok(existing_user) = UserService::get_by_username(username, db).await {
-                    if existing_user.get_username() == *username {
+                    if existing_user.get_id() != user_model.id {
                         return ApiResponse::<String>::error("用户名已被使用".to_string()).json();
                     }
                 }
                 active_user.username = Set(username.clone());
             }
 
+            // 修改密码需要校验旧密码，新密码哈希后与其它字段一起写入
+            let password_changed = match &account_update.password {
+                Some(password) => {
+                    let old_password = match &account_update.old_password {
+                        Some(old_password) => old_password,
+                        None => {
+                            return ApiResponse::<String>::error("请输入原密码".to_string()).json()
+                        }
+                    };
+                    let current = User::from(user_model.clone());
+                    match UserService::check_password_change(&current, old_password, password) {
+                        Ok(hash) => active_user.password = Set(hash),
+                        Err(e) => {
+                            return ApiResponse::<String>::error(format!("密码修改失败: {}", e))
+                                .json()
+                        }
+                    }
+                    true
+                }
+                None => false,
+            };
+
             if let Some(nickname) = &account_update.nickname {
                 active_user.nickname = Set(nickname.clone());
             }
@@ -64,15 +88,127 @@ pub async fn change_account(
 
             active_user.update_time = Set(now);
 
-            match active_user.update(db).await {
-                Ok(_) => {
-                    ApiResponse::<String>::success_with_msg("用户信息更新成功".to_string(), None)
-                        .json()
+            if let Err(e) = active_user.update(db).await {
+                return ApiResponse::<String>::error(format!("用户信息更新失败: {}", e)).json();
+            }
+            //密码已修改，注销该账号的其它登录会话
+            if password_changed {
+                match SessionService::revoke_other_sessions(
+                    auth.account_id as i64,
+                    auth.jwt_id,
+                    &store,
+                )
+                .await
+                {
+                    Ok(count) => {
+                        log::info!("用户:{}修改密码，注销其它会话{}个", auth.subject, count)
+                    }
+                    Err(e) => log::error!("用户:{}修改密码后注销其它会话失败:{}", auth.subject, e),
                 }
-                Err(e) => ApiResponse::<String>::error(format!("用户信息更新失败: {}", e)).json(),
             }
+            ApiResponse::<String>::success_with_msg("用户信息更新成功".to_string(), None).json()
         }
         Ok(None) => ApiResponse::<String>::error("用户不存在".to_string()).json(),
         Err(e) => ApiResponse::<String>::error(format!("查询用户失败: {}", e)).json(),
     }
 }
+
+#[derive(Debug, Deserialize)]
+pub struct TotpCode {
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TotpDisable {
+    pub password: String,
+    pub code: String,
+}
+
+/**
+ * 获取两步验证密钥与otpauth URI，需调用 enable 确认后才会启用
+ */
+#[routes]
+#[post("/account/totp/setup")]
+pub async fn totp_setup(
+    auth: Authenticated<AppClaims>,
+    app: web::Data<AppState>,
+) -> impl Responder {
+    match TotpService::setup(auth.account_id as i64, app.get_mysql_pool()).await {
+        Ok((secret, uri)) => {
+            let mut map = ValueMap::new();
+            map.insert(value!("secret"), value!(secret));
+            map.insert(value!("uri"), value!(uri));
+            ApiResponse::<Value>::success_with_msg("请求成功".to_string(), Some(value!(map))).json()
+        }
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+/**
+ * 校验首个动态码并启用两步验证，返回的恢复码只显示一次
+ */
+#[routes]
+#[post("/account/totp/enable")]
+pub async fn totp_enable(
+    auth: Authenticated<AppClaims>,
+    app: web::Data<AppState>,
+    form: web::Json<TotpCode>,
+) -> impl Responder {
+    match TotpService::enable(auth.account_id as i64, &form.code, app.get_mysql_pool()).await {
+        Ok(codes) => ApiResponse::<Vec<String>>::success_with_msg(
+            "两步验证已启用，请妥善保存恢复码".to_string(),
+            Some(codes),
+        )
+        .json(),
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+/**
+ * 关闭两步验证
+ */
+#[routes]
+#[post("/account/totp/disable")]
+pub async fn totp_disable(
+    auth: Authenticated<AppClaims>,
+    app: web::Data<AppState>,
+    form: web::Json<TotpDisable>,
+) -> impl Responder {
+    match TotpService::disable(
+        auth.account_id as i64,
+        &form.password,
+        &form.code,
+        app.get_mysql_pool(),
+    )
+    .await
+    {
+        Ok(_) => ApiResponse::<String>::success_with_msg("两步验证已关闭".to_string(), None).json(),
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+/**
+ * 重新生成恢复码，旧恢复码失效
+ */
+#[routes]
+#[post("/account/totp/recoveryCodes")]
+pub async fn totp_recovery_codes(
+    auth: Authenticated<AppClaims>,
+    app: web::Data<AppState>,
+    form: web::Json<TotpCode>,
+) -> impl Responder {
+    match TotpService::regenerate_recovery_codes(
+        auth.account_id as i64,
+        &form.code,
+        app.get_mysql_pool(),
+    )
+    .await
+    {
+        Ok(codes) => ApiResponse::<Vec<String>>::success_with_msg(
+            "恢复码已重新生成，请妥善保存".to_string(),
+            Some(codes),
+        )
+        .json(),
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
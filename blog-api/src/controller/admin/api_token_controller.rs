@@ -0,0 +1,102 @@
+use crate::middleware::{Authorized, Permission, SystemRead, SystemWrite};
+use crate::service::ApiTokenService;
+use crate::{app::AppState, model::ApiResponse};
+use actix_web::{routes, web, HttpResponse, Responder};
+use chrono::NaiveDateTime;
+use rbs::value::map::ValueMap;
+use rbs::{value, Value};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct ApiTokenCreate {
+    pub name: String,
+    pub scopes: Vec<String>,
+    //为空时永不过期
+    #[serde(rename(deserialize = "expireTime"))]
+    pub expire_time: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApiTokenIdParam {
+    pub id: i64,
+}
+
+/**
+ * API token列表与可选的权限范围
+ */
+#[routes]
+#[get("/apiTokens")]
+pub async fn get_api_tokens(_: Authorized<SystemRead>, app: web::Data<AppState>) -> impl Responder {
+    match ApiTokenService::find_tokens(app.get_mysql_pool()).await {
+        Ok(tokens) => {
+            let scopes: Vec<&str> = Permission::SCOPES
+                .iter()
+                .map(|permission| permission.scope())
+                .collect();
+            let mut map = ValueMap::new();
+            map.insert(value!("tokens"), value!(tokens));
+            map.insert(value!("scopes"), value!(scopes));
+            ApiResponse::<Value>::success_with_msg(
+                "获取API token列表成功".to_string(),
+                Some(value!(map)),
+            )
+            .json()
+        }
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+/**
+ * 创建API token，token只在创建时返回一次
+ */
+#[routes]
+#[post("/apiToken")]
+pub async fn create_api_token(
+    auth: Authorized<SystemWrite>,
+    app: web::Data<AppState>,
+    form: web::Json<ApiTokenCreate>,
+) -> impl Responder {
+    if auth.is_api_token() {
+        return ApiResponse::<String>::error("不能使用API token创建API token".to_string()).json();
+    }
+    match ApiTokenService::create_token(
+        auth.account_id as i64,
+        &form.name,
+        &form.scopes,
+        form.expire_time,
+        app.get_mysql_pool(),
+    )
+    .await
+    {
+        Ok((api_token, token)) => {
+            let mut map = ValueMap::new();
+            map.insert(value!("apiToken"), value!(api_token));
+            map.insert(value!("token"), value!(token));
+            ApiResponse::<Value>::success_with_msg(
+                "创建成功，请妥善保存token".to_string(),
+                Some(value!(map)),
+            )
+            .json()
+        }
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+/**
+ * 撤销API token
+ */
+#[routes]
+#[delete("/apiToken")]
+pub async fn delete_api_token(
+    auth: Authorized<SystemWrite>,
+    app: web::Data<AppState>,
+    params: web::Query<ApiTokenIdParam>,
+) -> impl Responder {
+    if auth.is_api_token() {
+        return ApiResponse::<String>::error("不能使用API token撤销API token".to_string()).json();
+    }
+    match ApiTokenService::delete_token(params.id, app.get_mysql_pool()).await {
+        Ok(_) => ApiResponse::<String>::success_with_msg("撤销成功".to_string(), None).json(),
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
@@ -1,34 +1,40 @@
 use std::collections::HashMap;
 
 use crate::app::AppState;
-use crate::error::WebErrorCode;
+use crate::error::{DataBaseError, WebErrorCode};
 use crate::model::{ApiResponse, BlogVO};
-use crate::service::{BlogService, CategoryService, TagService};
+use crate::service::{
+    BlogMarkdownService, BlogRevisionService, BlogService, CategoryService, SearchService,
+    TagService,
+};
 use crate::{
-    middleware::AppClaims,
+    middleware::{Authorized, BlogRead, BlogWrite, SystemWrite},
     model::{BlogVisibility, SearchRequest},
 };
-use actix_jwt_session::Authenticated;
+use actix_web::http::header::ContentDisposition;
 use actix_web::web::Json;
 use actix_web::{
     routes,
     web::{self, Query},
-    Responder,
+    HttpResponse, Responder,
 };
+use chrono::Local;
 use rbs::value::map::ValueMap;
 use rbs::{value, Value};
+use serde::Deserialize;
 
 #[routes] // 定义路由
 #[get("/blogs")] // 定义GET请求的路由
 pub async fn blogs(
     query: Query<SearchRequest>,
-    _: Authenticated<AppClaims>,
+    auth: Authorized<BlogRead>,
     app: web::Data<AppState>,
 ) -> impl Responder {
     // 定义异步函数，返回一个实现了Responder trait的类型
     let connect = app.get_mysql_pool();
     let mut map = ValueMap::new(); // 创建一个ValueMap类型的变量
-    let page = BlogService::find_all_page(query.0, connect).await; // 调用BlogService的get_blog_all_page方法，传入query.0，获取博客分页数据
+    //编辑只能看到自己的文章
+    let page = BlogService::find_all_page(query.0, auth.blog_owner(), connect).await; // 调用BlogService的get_blog_all_page方法，传入query.0，获取博客分页数据
     let categories = CategoryService::find_categories(connect).await; // 调用CategoryService的get_categories方法，获取分类数据
     map.insert(value!("blogs"), value!(page)); // 将博客分页数据插入到map中
     map.insert(value!("categories"), value!(categories)); // 将分类数据插入到map中
@@ -44,14 +50,17 @@ pub async fn blogs(
 pub async fn visibility(
     path: web::Path<i64>,
     mut query: Json<BlogVisibility>,
-    _: Authenticated<AppClaims>,
+    auth: Authorized<BlogWrite>,
     app: web::Data<AppState>,
 ) -> impl Responder {
     let id = path.into_inner();
     query.set_id(id as i64);
+    if let Err(e) = BlogService::check_owner(id, auth.blog_owner(), app.get_mysql_pool()).await {
+        return HttpResponse::from_error(e);
+    }
     match BlogService::update_visibility(&query, app.get_mysql_pool()).await {
         Ok(_) => ApiResponse::<String>::success_with_msg("更新成功".to_string(), None).json(),
-        Err(e) => ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json(),
+        Err(e) => HttpResponse::from_error(e),
     }
 }
 
@@ -59,12 +68,16 @@ pub async fn visibility(
 #[put("/blog/top")]
 pub async fn top(
     query: Query<BlogVisibility>,
-    _: Authenticated<AppClaims>,
+    auth: Authorized<BlogWrite>,
     app: web::Data<AppState>,
 ) -> impl Responder {
+    let id = query.get_id().unwrap_or_default();
+    if let Err(e) = BlogService::check_owner(id, auth.blog_owner(), app.get_mysql_pool()).await {
+        return HttpResponse::from_error(e);
+    }
     match BlogService::update_visibility(&query, app.get_mysql_pool()).await {
         Ok(_) => ApiResponse::<String>::success_with_msg("更新成功".to_string(), None).json(),
-        Err(e) => ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json(),
+        Err(e) => HttpResponse::from_error(e),
     }
 }
 
@@ -72,12 +85,16 @@ pub async fn top(
 #[put("/blog/recommend")]
 pub async fn recommend(
     query: Query<BlogVisibility>,
-    _: Authenticated<AppClaims>,
+    auth: Authorized<BlogWrite>,
     app: web::Data<AppState>,
 ) -> impl Responder {
+    let id = query.get_id().unwrap_or_default();
+    if let Err(e) = BlogService::check_owner(id, auth.blog_owner(), app.get_mysql_pool()).await {
+        return HttpResponse::from_error(e);
+    }
     match BlogService::update_visibility(&query, app.get_mysql_pool()).await {
         Ok(_) => ApiResponse::<String>::success_with_msg("更新成功".to_string(), None).json(),
-        Err(e) => ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json(),
+        Err(e) => HttpResponse::from_error(e),
     }
 }
 /**
@@ -85,16 +102,16 @@ pub async fn recommend(
  */
 #[routes]
 #[get("/categoryAndTag")]
-pub async fn category_and_tag(app: web::Data<AppState>) -> impl Responder {
+pub async fn category_and_tag(_: Authorized<BlogRead>, app: web::Data<AppState>) -> impl Responder {
     let mut map: HashMap<String, Value> = HashMap::new();
     let connect = app.get_mysql_pool();
     let tag_list = match TagService::get_tags(connect).await {
         Ok(tag_list) => tag_list,
-        Err(e) => return ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json(),
+        Err(e) => return HttpResponse::from_error(e),
     };
     let category_list = match CategoryService::get_list(connect).await {
         Ok(category_list) => category_list,
-        Err(e) => return ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json(),
+        Err(e) => return HttpResponse::from_error(e),
     };
     map.insert("categories".to_string(), value!(category_list));
     map.insert("tags".to_string(), value!(tag_list));
@@ -108,6 +125,7 @@ pub async fn category_and_tag(app: web::Data<AppState>) -> impl Responder {
 #[get("/blog")]
 pub async fn blog(
     query: Query<HashMap<String, String>>,
+    auth: Authorized<BlogRead>,
     app: web::Data<AppState>,
 ) -> impl Responder {
     let id = query
@@ -118,6 +136,10 @@ pub async fn blog(
     if id <= 0 {
         return ApiResponse::<String>::error("参数错误".to_string()).json();
     }
+    if let Err(e) = BlogService::check_owner(id as i64, auth.blog_owner(), app.get_mysql_pool()).await
+    {
+        return HttpResponse::from_error(e);
+    }
     let blog = BlogService::find_by_id(id, app.get_mysql_pool()).await;
     match blog {
         Ok(blog) => ApiResponse::success_with_msg("请求成功!".to_string(), Some(value!(blog))).json(),
@@ -132,12 +154,17 @@ pub async fn blog(
 #[put("/blog")]
 pub async fn update_blog(
     query: Json<BlogVO>,
-    _: Authenticated<AppClaims>,
+    auth: Authorized<BlogWrite>,
     app: web::Data<AppState>,
 ) -> impl Responder {
+    if let Err(e) =
+        BlogService::check_owner(query.get_id(), auth.blog_owner(), app.get_mysql_pool()).await
+    {
+        return HttpResponse::from_error(e);
+    }
     match BlogService::update_blog(query.into_inner(), app.get_mysql_pool()).await {
         Ok(_) => ApiResponse::<String>::success_with_msg("更新成功".to_string(), None).json(),
-        Err(e) => ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json(),
+        Err(e) => HttpResponse::from_error(e),
     }
 }
 /**
@@ -147,12 +174,16 @@ pub async fn update_blog(
 #[post("/blog")]
 pub async fn create_blog(
     query: Json<BlogVO>,
-    _: Authenticated<AppClaims>,
+    auth: Authorized<BlogWrite>,
     app: web::Data<AppState>,
 ) -> impl Responder {
-    match BlogService::update_blog(query.into_inner(), app.get_mysql_pool()).await {
+    //新文章的作者为当前用户
+    let mut blog_vo = query.into_inner();
+    blog_vo.id = None;
+    blog_vo.user_id = Some(auth.account_id as i64);
+    match BlogService::update_blog(blog_vo, app.get_mysql_pool()).await {
         Ok(_) => ApiResponse::<String>::success_with_msg("创建成功".to_string(), None).json(),
-        Err(e) => ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json(),
+        Err(e) => HttpResponse::from_error(e),
     }
 }
 
@@ -163,7 +194,7 @@ pub async fn create_blog(
 #[delete("/blog")]
 pub async fn delete_blog(
     query: Query<HashMap<String, String>>,
-    _: Authenticated<AppClaims>,
+    auth: Authorized<BlogWrite>,
     app: web::Data<AppState>,
 ) -> impl Responder {
     // 解析参数 id
@@ -174,8 +205,195 @@ pub async fn delete_blog(
     if id <= 0 {
         return ApiResponse::<String>::error_with_code(WebErrorCode::VALIDATION_ERROR, "参数错误".to_string()).json();
     }
+    if let Err(e) = BlogService::check_owner(id, auth.blog_owner(), app.get_mysql_pool()).await {
+        return HttpResponse::from_error(e);
+    }
     match BlogService::delete_by_id(id, app.get_mysql_pool()).await {
         Ok(_) => ApiResponse::<String>::success_with_msg("删除成功".to_string(), None).json(),
-        Err(e) => ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json(),
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+/**
+ * 重建全文检索索引
+ */
+#[routes]
+#[post("/blog/searchIndex")]
+pub async fn rebuild_search_index(
+    _: Authorized<SystemWrite>,
+    app: web::Data<AppState>,
+) -> impl Responder {
+    match SearchService::rebuild_index(app.get_mysql_pool()).await {
+        Ok(count) => ApiResponse::<String>::success_with_msg(
+            format!("索引重建成功, 共{}篇文章", count),
+            None,
+        )
+        .json(),
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+/**
+ * 文章历史版本列表
+ */
+#[routes]
+#[get("/blog/{blog_id}/revisions")]
+pub async fn revisions(
+    path: web::Path<i64>,
+    auth: Authorized<BlogRead>,
+    app: web::Data<AppState>,
+) -> impl Responder {
+    let blog_id = path.into_inner();
+    if let Err(e) = BlogService::check_owner(blog_id, auth.blog_owner(), app.get_mysql_pool()).await
+    {
+        return HttpResponse::from_error(e);
+    }
+    match BlogRevisionService::find_revisions(blog_id, app.get_mysql_pool()).await {
+        Ok(list) => ApiResponse::success(Some(value!(list))).json(),
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RevisionDiffParam {
+    pub from: i64,
+    //不传时与文章当前版本比较
+    pub to: Option<i64>,
+}
+
+/**
+ * 比较文章的两个历史版本
+ */
+#[routes]
+#[get("/blog/{blog_id}/revisions/diff")]
+pub async fn revision_diff(
+    path: web::Path<i64>,
+    query: Query<RevisionDiffParam>,
+    auth: Authorized<BlogRead>,
+    app: web::Data<AppState>,
+) -> impl Responder {
+    let blog_id = path.into_inner();
+    if let Err(e) = BlogService::check_owner(blog_id, auth.blog_owner(), app.get_mysql_pool()).await
+    {
+        return HttpResponse::from_error(e);
+    }
+    match BlogRevisionService::diff(blog_id, query.from, query.to, app.get_mysql_pool()).await {
+        Ok(diff) => ApiResponse::success(Some(value!(diff))).json(),
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+/**
+ * 恢复文章到指定历史版本
+ */
+#[routes]
+#[post("/blog/{blog_id}/revisions/{revision_id}/restore")]
+pub async fn restore_revision(
+    path: web::Path<(i64, i64)>,
+    auth: Authorized<BlogWrite>,
+    app: web::Data<AppState>,
+) -> impl Responder {
+    let (blog_id, revision_id) = path.into_inner();
+    if let Err(e) = BlogService::check_owner(blog_id, auth.blog_owner(), app.get_mysql_pool()).await
+    {
+        return HttpResponse::from_error(e);
+    }
+    match BlogRevisionService::restore(blog_id, revision_id, app.get_mysql_pool()).await {
+        Ok(_) => ApiResponse::<String>::success_with_msg("恢复成功".to_string(), None).json(),
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+/// Markdown导入请求体的最大字节数
+const MAX_IMPORT_SIZE: usize = 50 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+pub struct MarkdownImportParam {
+    //上传单个Markdown文件时必填的文件名，用于生成slug
+    pub name: Option<String>,
+}
+
+/**
+ * 导入Markdown文章，请求体为zip压缩包或单个Markdown文件，按slug新增或更新
+ */
+#[routes]
+#[post("/blogs/import")]
+pub async fn import_markdown(
+    query: Query<MarkdownImportParam>,
+    payload: web::Payload,
+    auth: Authorized<BlogWrite>,
+    app: web::Data<AppState>,
+) -> impl Responder {
+    let bytes = match payload.to_bytes_limited(MAX_IMPORT_SIZE).await {
+        Ok(Ok(bytes)) => bytes,
+        Ok(Err(e)) => return HttpResponse::from_error(e),
+        Err(_) => {
+            return ApiResponse::<String>::error_with_code(
+                WebErrorCode::VALIDATION_ERROR,
+                format!("文件不能超过{}MB", MAX_IMPORT_SIZE / 1024 / 1024),
+            )
+            .json()
+        }
+    };
+    //zip文件以PK开头
+    let files = match bytes.starts_with(b"PK\x03\x04") {
+        //解压比较耗时，在线程池中执行
+        true => match web::block(move || BlogMarkdownService::read_zip(&bytes)).await {
+            Ok(Ok(files)) => files,
+            Ok(Err(e)) => return HttpResponse::from_error(e),
+            Err(e) => {
+                return HttpResponse::from_error(DataBaseError::Custom(format!(
+                    "无法读取zip文件:{}",
+                    e
+                )))
+            }
+        },
+        //没有文件名时无法生成slug，多次导入会互相覆盖
+        false => match query.name.as_deref().map(str::trim) {
+            Some(name) if !name.is_empty() => vec![(name.to_string(), bytes.to_vec())],
+            _ => {
+                return ApiResponse::<String>::error_with_code(
+                    WebErrorCode::VALIDATION_ERROR,
+                    "导入单个Markdown文件时需要通过name参数指定文件名".to_string(),
+                )
+                .json()
+            }
+        },
+    };
+    match BlogMarkdownService::import_files(
+        files,
+        auth.account_id as i64,
+        auth.blog_owner(),
+        app.get_mysql_pool(),
+    )
+    .await
+    {
+        Ok(report) => ApiResponse::success_with_msg(
+            format!("导入完成, 新增{}篇, 更新{}篇", report.created, report.updated),
+            Some(value!(report)),
+        )
+        .json(),
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+/**
+ * 导出全部文章为带front matter的Markdown压缩包
+ */
+#[routes]
+#[get("/blogs/export")]
+pub async fn export_markdown(
+    auth: Authorized<BlogRead>,
+    app: web::Data<AppState>,
+) -> impl Responder {
+    match BlogMarkdownService::export_zip(auth.blog_owner(), app.get_mysql_pool()).await {
+        Ok(bytes) => HttpResponse::Ok()
+            .content_type("application/zip")
+            .insert_header(ContentDisposition::attachment(format!(
+                "blogs-{}.zip",
+                Local::now().format("%Y%m%d%H%M%S")
+            )))
+            .body(bytes),
+        Err(e) => HttpResponse::from_error(e),
     }
 }
@@ -1,14 +1,13 @@
 use std::collections::HashMap;
 
 use crate::app::AppState;
-use crate::error::WebErrorCode;
+use crate::error::{DataBaseError, WebErrorCode};
 use crate::model::Category;
 use crate::model::SearchRequest;
 use crate::model::ApiResponse;
 use crate::service::CategoryService;
-use crate::{middleware::AppClaims, service::BlogService};
-use actix_jwt_session::Authenticated;
-use actix_web::{routes, web, Responder};
+use crate::{middleware::{Authorized, ContentRead, ContentWrite}, service::BlogService};
+use actix_web::{routes, web, HttpResponse, Responder};
 use rbs::value;
 
 /**
@@ -17,7 +16,7 @@ use rbs::value;
 #[routes]
 #[get("/categories")]
 pub async fn categories(
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentRead>,
     params: web::Query<SearchRequest>,
     app: web::Data<AppState>,
 ) -> impl Responder {
@@ -36,9 +35,7 @@ pub async fn categories(
     .await
     {
         Ok(data) => ApiResponse::success(Some(value!(data))).json(),
-        Err(e) => {
-            ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json()
-        }
+        Err(e) => HttpResponse::from_error(e),
     }
 }
 
@@ -48,7 +45,7 @@ pub async fn categories(
 #[routes]
 #[put("/category")]
 pub async fn update_category(
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentWrite>,
     form: web::Json<Category>,
     app: web::Data<AppState>,
 ) -> impl Responder {
@@ -84,7 +81,7 @@ pub async fn update_category(
 #[routes]
 #[delete("/category")]
 pub async fn delete_category(
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentWrite>,
     query: web::Query<HashMap<String, i64>>,
     app: web::Data<AppState>,
 ) -> impl Responder {
@@ -122,14 +119,9 @@ pub async fn delete_category(
             match CategoryService::delete_category(id, connection).await {
                 Ok(_) => ApiResponse::<String>::success_with_msg("删除分类成功!".to_string(), None)
                     .json(),
-                Err(e) => {
-                    ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string())
-                        .json()
-                }
+                Err(e) => HttpResponse::from_error(DataBaseError::from(e)),
             }
         }
-        Err(e) => {
-            ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json()
-        }
+        Err(e) => HttpResponse::from_error(e),
     }
 }
@@ -1,51 +1,87 @@
 use std::collections::HashMap;
 
-use actix_jwt_session::Authenticated;
 use actix_web::{
     routes,
     web::{self, Data},
-    Responder,
+    HttpResponse, Responder,
 };
 use rbs::value;
+use serde::Deserialize;
 
 use crate::{
     app::AppState,
-    error::WebErrorCode,
-    middleware::AppClaims,
+    middleware::{Authorized, CommentRead, CommentWrite},
     model::ApiResponse,
-    model::{CommentDTO, SearchRequest},
-    service::{BlogService, CommentService},
+    model::{CommentDTO, CommentQuery},
+    service::{BlogService, CommentBlockService, CommentService, MailService},
 };
 
+#[derive(Debug, Deserialize)]
+pub struct CommentIds {
+    pub ids: Vec<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommentPublished {
+    pub ids: Vec<i64>,
+    pub published: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommentBlockCreate {
+    //ip 或 email
+    #[serde(rename = "type")]
+    pub block_type: String,
+    //直接指定屏蔽的IP或邮箱
+    pub value: Option<String>,
+    //屏蔽该评论的IP或邮箱
+    #[serde(rename(deserialize = "commentId"))]
+    pub comment_id: Option<i64>,
+    pub reason: Option<String>,
+}
+
 #[routes]
 #[get("/comments")]
 pub async fn find_comments(
-    _: Authenticated<AppClaims>,
+    _: Authorized<CommentRead>,
     app: Data<AppState>,
-    query: web::Query<SearchRequest>,
+    query: web::Query<CommentQuery>,
 ) -> impl Responder {
-    let page_num = query.get_page_num();
-    let page_size = query.get_page_size();
-    match CommentService::find_comment_dto(page_num, page_size, app.get_mysql_pool()).await {
+    match CommentService::find_comment_dto(&query, false, app.get_mysql_pool()).await {
         Ok(comments) => {
             ApiResponse::success_with_msg("请求成功！".to_string(), Some(value!(comments))).json()
         }
-        Err(e) => ApiResponse::<String>::error(e.to_string()).json(),
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+//待审核评论，最早提交的在前
+#[routes]
+#[get("/comments/pending")]
+pub async fn find_pending_comments(
+    _: Authorized<CommentRead>,
+    app: Data<AppState>,
+    query: web::Query<CommentQuery>,
+) -> impl Responder {
+    let mut query = query.into_inner();
+    query.published = Some(false);
+    match CommentService::find_comment_dto(&query, true, app.get_mysql_pool()).await {
+        Ok(comments) => {
+            ApiResponse::success_with_msg("请求成功！".to_string(), Some(value!(comments))).json()
+        }
+        Err(e) => HttpResponse::from_error(e),
     }
 }
 
 #[routes]
 #[get("/blogIdAndTitle")]
 pub async fn find_blog_id_and_title(
-    _: Authenticated<AppClaims>,
+    _: Authorized<CommentRead>,
     app: Data<AppState>,
 ) -> impl Responder {
     match BlogService::find_blogs_and_title(app.get_mysql_pool()).await {
         Ok(comments) => ApiResponse::success(Some(value!(comments))).json(),
-        Err(e) => {
-            ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string())
-                .json()
-        }
+        Err(e) => HttpResponse::from_error(e),
     }
 }
 
@@ -53,16 +89,13 @@ pub async fn find_blog_id_and_title(
 #[routes]
 #[put("/comment")]
 pub async fn update_comment(
-    _: Authenticated<AppClaims>,
+    _: Authorized<CommentWrite>,
     app: Data<AppState>,
     comment: web::Json<CommentDTO>,
 ) -> impl Responder {
     match CommentService::save_comment(comment.into_inner(), app.get_mysql_pool()).await {
         Ok(_) => ApiResponse::<String>::success_with_msg("更新成功！".to_string(), None).json(),
-        Err(e) => {
-            ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string())
-                .json()
-        }
+        Err(e) => HttpResponse::from_error(e),
     }
 }
 
@@ -70,16 +103,106 @@ pub async fn update_comment(
 #[routes]
 #[delete("/comment")]
 pub async fn delete_comment(
-    _: Authenticated<AppClaims>,
+    _: Authorized<CommentWrite>,
     app: Data<AppState>,
     parameter: web::Query<HashMap<String, i64>>,
 ) -> impl Responder {
     let id = *parameter.get("id").unwrap_or(&0);
     match CommentService::delete_comment_recursive(id, app.get_mysql_pool()).await {
         Ok(_) => ApiResponse::<String>::success_with_msg("删除成功！".to_string(), None).json(),
-        Err(e) => {
-            ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string())
-                .json()
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+//批量审核通过或隐藏评论，审核通过时通知被回复的访客
+#[routes]
+#[put("/comments/published")]
+pub async fn update_comments_published(
+    _: Authorized<CommentWrite>,
+    app: Data<AppState>,
+    form: web::Json<CommentPublished>,
+) -> impl Responder {
+    let form = form.into_inner();
+    let db = app.get_mysql_pool();
+    match CommentService::update_published(form.ids, form.published, db).await {
+        Ok(approved) => {
+            MailService::notify_approved(approved, db.clone());
+            ApiResponse::<String>::success_with_msg("更新成功！".to_string(), None).json()
+        }
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+//批量删除评论及其回复
+#[routes]
+#[delete("/comments")]
+pub async fn delete_comments(
+    _: Authorized<CommentWrite>,
+    app: Data<AppState>,
+    form: web::Json<CommentIds>,
+) -> impl Responder {
+    match CommentService::delete_comments_recursive(form.into_inner().ids, app.get_mysql_pool())
+        .await
+    {
+        Ok(count) => {
+            ApiResponse::success_with_msg("删除成功！".to_string(), Some(value!(count))).json()
+        }
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+//评论黑名单
+#[routes]
+#[get("/commentBlocks")]
+pub async fn find_comment_blocks(
+    _: Authorized<CommentRead>,
+    app: Data<AppState>,
+) -> impl Responder {
+    match CommentBlockService::find_blocks(app.get_mysql_pool()).await {
+        Ok(blocks) => {
+            ApiResponse::success_with_msg("请求成功！".to_string(), Some(value!(blocks))).json()
         }
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+//屏蔽IP或邮箱，传入commentId时屏蔽该评论的IP或邮箱
+#[routes]
+#[post("/commentBlock")]
+pub async fn create_comment_block(
+    _: Authorized<CommentWrite>,
+    app: Data<AppState>,
+    form: web::Json<CommentBlockCreate>,
+) -> impl Responder {
+    let form = form.into_inner();
+    let db = app.get_mysql_pool();
+    let value = match form.comment_id {
+        Some(comment_id) => {
+            match CommentBlockService::find_comment_value(comment_id, &form.block_type, db).await {
+                Ok(value) => value,
+                Err(e) => return HttpResponse::from_error(e),
+            }
+        }
+        None => form.value.unwrap_or_default(),
+    };
+    match CommentBlockService::add_block(&form.block_type, &value, form.reason, db).await {
+        Ok(block) => {
+            ApiResponse::success_with_msg("屏蔽成功！".to_string(), Some(value!(block))).json()
+        }
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+#[routes]
+#[delete("/commentBlock")]
+pub async fn delete_comment_block(
+    _: Authorized<CommentWrite>,
+    app: Data<AppState>,
+    parameter: web::Query<HashMap<String, i64>>,
+) -> impl Responder {
+    let id = *parameter.get("id").unwrap_or(&0);
+    match CommentBlockService::delete_block(id, app.get_mysql_pool()).await {
+        Ok(_) => ApiResponse::<String>::success_with_msg("删除成功！".to_string(), None).json(),
+        Err(e) => HttpResponse::from_error(e),
     }
 }
@@ -1,6 +1,6 @@
 use crate::{app::AppState, model::ApiResponse};
 use crate::middleware::AppClaims;
-use crate::service::DashboardService;
+use crate::service::{DashboardService, VisitService};
 use actix_jwt_session::Authenticated;
 use actix_web::{routes, web, Responder};
 use rbs::{value, value::map::ValueMap};
@@ -9,18 +9,23 @@ use rbs::{value, value::map::ValueMap};
 #[get("/dashboard")]
 pub async fn dashboard(_: Authenticated<AppClaims>, app: web::Data<AppState>) -> impl Responder {
     let mut map = ValueMap::new();
-    let today_pv = 0;
-    let today_uv = 0;
+    let (today_pv, today_uv) = VisitService::find_today_pv_uv(app.get_mysql_pool()).await;
     let blog_count = DashboardService::get_blog_count(app.get_mysql_pool()).await;
     let comment_count = DashboardService::get_comment_count(app.get_mysql_pool()).await;
+    let pending_comment_count =
+        DashboardService::get_pending_comment_count(app.get_mysql_pool()).await;
+    let blog_pending_comments =
+        DashboardService::get_blog_pending_comments(app.get_mysql_pool()).await;
     let category_blog_count_map = DashboardService::get_categorys_count(app.get_mysql_pool()).await;
     let tag_blog_count_map = DashboardService::get_tags_count(app.get_mysql_pool()).await;
-    let visit_record_map = ValueMap::new();
-    let city_visitor_list = ValueMap::new();
+    let visit_record_map = DashboardService::get_visit_record(app.get_mysql_pool()).await;
+    let city_visitor_list = DashboardService::get_city_visitor(app.get_mysql_pool()).await;
     map.insert( value!("pv"), value!(today_pv));
     map.insert( value!("uv"), value!(today_uv));
     map.insert( value!("blogCount"), value!(blog_count));
     map.insert( value!("commentCount"), value!(comment_count));
+    map.insert( value!("pendingCommentCount"), value!(pending_comment_count));
+    map.insert( value!("blogPendingComments"), value!(blog_pending_comments));
     map.insert( value!("category"), value!(category_blog_count_map));
     map.insert( value!("tag"), value!(tag_blog_count_map));
     map.insert( value!("visitRecord"), value!(visit_record_map));
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+use crate::middleware::{Authorized, SystemRead, SystemWrite};
+use crate::service::ExceptionLogService;
+use crate::{app::AppState, model::ApiResponse};
+use actix_web::{routes, web, HttpResponse, Responder};
+use rbs::value;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct ExceptionLogQuery {
+    pub page_num: Option<u64>,
+    pub page_size: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FingerprintParam {
+    pub fingerprint: String,
+}
+
+/**
+ * 按异常指纹分组的异常日志列表，包含发生次数与最近一次的记录
+ */
+#[routes]
+#[get("/exceptionLogs")]
+pub async fn get_exception_log_groups(
+    _: Authorized<SystemRead>,
+    app: web::Data<AppState>,
+    query: web::Query<ExceptionLogQuery>,
+) -> impl Responder {
+    let page_num = query.page_num.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(10).max(1);
+    match ExceptionLogService::find_groups(page_num, page_size, app.get_mysql_pool()).await {
+        Ok((total, groups)) => {
+            let mut result = HashMap::new();
+            result.insert("total".to_string(), value!(total));
+            result.insert("records".to_string(), value!(groups));
+            ApiResponse::success_with_msg("获取异常日志列表成功".to_string(), Some(value!(result)))
+                .json()
+        }
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+/**
+ * 同一异常指纹的全部异常日志
+ */
+#[routes]
+#[get("/exceptionLogs/{fingerprint}")]
+pub async fn get_exception_logs_by_fingerprint(
+    _: Authorized<SystemRead>,
+    app: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<ExceptionLogQuery>,
+) -> impl Responder {
+    let page_num = query.page_num.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(10).max(1);
+    match ExceptionLogService::find_by_fingerprint(&path, page_num, page_size, app.get_mysql_pool())
+        .await
+    {
+        Ok((total, logs)) => {
+            let mut result = HashMap::new();
+            result.insert("total".to_string(), value!(total));
+            result.insert("records".to_string(), value!(logs));
+            ApiResponse::success_with_msg("获取异常日志成功".to_string(), Some(value!(result)))
+                .json()
+        }
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+#[routes]
+#[delete("/exceptionLogs")]
+pub async fn delete_exception_logs(
+    _: Authorized<SystemWrite>,
+    app: web::Data<AppState>,
+    params: web::Query<FingerprintParam>,
+) -> impl Responder {
+    match ExceptionLogService::delete_by_fingerprint(&params.fingerprint, app.get_mysql_pool())
+        .await
+    {
+        Ok(0) => ApiResponse::<String>::error("异常日志不存在".to_string()).json(),
+        Ok(_) => {
+            ApiResponse::<String>::success_with_msg("删除异常日志成功".to_string(), None).json()
+        }
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
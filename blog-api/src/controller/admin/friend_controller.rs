@@ -1,11 +1,10 @@
 use std::collections::HashMap;
 
 use crate::entity::friend;
-use crate::middleware::AppClaims;
+use crate::middleware::{Authorized, ContentRead, ContentWrite};
 use crate::model::Friend;
 use crate::service::FriendService;
 use crate::{app::AppState, model::ApiResponse};
-use actix_jwt_session::Authenticated;
 use actix_web::{routes, web, Responder};
 use chrono::Utc;
 use rbs::value;
@@ -30,7 +29,7 @@ pub struct FriendUpdatePublished {
 #[routes]
 #[get("/friends")]
 pub async fn get_friends_by_query(
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentRead>,
     app: web::Data<AppState>,
     query: web::Query<FriendQuery>,
 ) -> impl Responder {
@@ -83,7 +82,7 @@ pub async fn get_friends_by_query(
 #[routes]
 #[put("/friend/published")]
 pub async fn update_friend_published(
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentWrite>,
     app: web::Data<AppState>,
     params: web::Query<FriendUpdatePublished>,
 ) -> impl Responder {
@@ -116,7 +115,7 @@ pub async fn update_friend_published(
 #[routes]
 #[post("/friend")]
 pub async fn save_friend(
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentWrite>,
     app: web::Data<AppState>,
     friend_form: web::Json<Friend>,
 ) -> impl Responder {
@@ -143,7 +142,7 @@ pub async fn save_friend(
 #[routes]
 #[put("/friend")]
 pub async fn update_friend(
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentWrite>,
     app: web::Data<AppState>,
     friend_form: web::Json<Friend>,
 ) -> impl Responder {
@@ -181,7 +180,7 @@ pub async fn update_friend(
 #[routes]
 #[delete("/friend")]
 pub async fn delete_friend_by_id(
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentWrite>,
     app: web::Data<AppState>,
     params: web::Query<IdParam>,
 ) -> impl Responder {
@@ -203,7 +202,7 @@ pub async fn delete_friend_by_id(
 #[routes]
 #[get("/friendInfo")]
 pub async fn get_friend_info(
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentRead>,
     app: web::Data<AppState>,
 ) -> impl Responder {
     match FriendService::get_friend(app.get_mysql_pool()).await {
@@ -219,7 +218,7 @@ pub async fn get_friend_info(
 #[routes]
 #[put("/friendInfo/commentEnabled")]
 pub async fn update_friend_comment_enabled(
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentWrite>,
     _app: web::Data<AppState>,
 ) -> impl Responder {
     // 这里需要实现更新友链评论启用状态的逻辑
@@ -230,7 +229,7 @@ pub async fn update_friend_comment_enabled(
 #[routes]
 #[put("/friendInfo/content")]
 pub async fn update_friend_content(
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentWrite>,
     _app: web::Data<AppState>,
     // _content_update: web::Json<FriendContentUpdate>,
 ) -> impl Responder {
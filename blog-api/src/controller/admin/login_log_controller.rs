@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use crate::entity::login_log;
+use crate::middleware::{Authorized, SystemRead, SystemWrite};
+use crate::model::LoginLog;
+use crate::{app::AppState, model::ApiResponse};
+use actix_web::{routes, web, Responder};
+use rbs::value;
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct LoginLogQuery {
+    pub page_num: Option<u32>,
+    pub page_size: Option<u32>,
+    pub username: Option<String>,
+    pub ip: Option<String>,
+    pub status: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LogIdParam {
+    pub id: i64,
+}
+
+#[routes]
+#[get("/loginLogs")]
+pub async fn get_login_log_list(
+    _: Authorized<SystemRead>,
+    app: web::Data<AppState>,
+    query: web::Query<LoginLogQuery>,
+) -> impl Responder {
+    let db = app.get_mysql_pool();
+    let page_num = query.page_num.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(10).max(1);
+
+    // 构建查询条件
+    let mut query_builder = login_log::Entity::find();
+
+    if let Some(username) = &query.username {
+        query_builder = query_builder.filter(login_log::Column::Username.contains(username));
+    }
+
+    if let Some(ip) = &query.ip {
+        query_builder = query_builder.filter(login_log::Column::Ip.contains(ip));
+    }
+
+    if let Some(status) = query.status {
+        query_builder = query_builder.filter(login_log::Column::Status.eq(status));
+    }
+
+    // 获取分页数据
+    let paginator = query_builder
+        .order_by_desc(login_log::Column::Id)
+        .paginate(db, page_size as u64);
+
+    let total = paginator.num_items().await.unwrap_or(0);
+    let logs = paginator.fetch_page((page_num - 1) as u64).await;
+
+    match logs {
+        Ok(log_models) => {
+            let mut result = HashMap::new();
+            let logs: Vec<LoginLog> = log_models.into_iter().map(LoginLog::from).collect();
+            result.insert("total".to_string(), value!(total));
+            result.insert("records".to_string(), value!(logs));
+            ApiResponse::success_with_msg("获取登录日志列表成功".to_string(), Some(value!(result)))
+                .json()
+        }
+        Err(e) => ApiResponse::<String>::error(format!("获取登录日志列表失败: {}", e)).json(),
+    }
+}
+
+#[routes]
+#[delete("/loginLog")]
+pub async fn delete_login_log_by_id(
+    _: Authorized<SystemWrite>,
+    app: web::Data<AppState>,
+    params: web::Query<LogIdParam>,
+) -> impl Responder {
+    let db = app.get_mysql_pool();
+
+    match login_log::Entity::delete_by_id(params.id).exec(db).await {
+        Ok(result) => {
+            if result.rows_affected > 0 {
+                ApiResponse::<String>::success_with_msg("删除登录日志成功".to_string(), None).json()
+            } else {
+                ApiResponse::<String>::error("登录日志不存在".to_string()).json()
+            }
+        }
+        Err(e) => ApiResponse::<String>::error(format!("删除登录日志失败: {}", e)).json(),
+    }
+}
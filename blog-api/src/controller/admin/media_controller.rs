@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use actix_multipart::Multipart;
+use actix_web::{
+    routes,
+    web::{self, Data},
+    HttpResponse, Responder,
+};
+use futures_util::StreamExt;
+use rbs::value;
+
+use crate::{
+    app::{AppState, CONFIG},
+    error::WebErrorCode,
+    middleware::{Authorized, BlogRead, BlogWrite},
+    model::{ApiResponse, MediaQuery},
+    service::MediaService,
+};
+
+#[routes]
+#[get("/media")]
+pub async fn find_media(
+    _: Authorized<BlogRead>,
+    app: Data<AppState>,
+    query: web::Query<MediaQuery>,
+) -> impl Responder {
+    match MediaService::find_media(&query, app.get_mysql_pool()).await {
+        Ok(medias) => {
+            ApiResponse::success_with_msg("请求成功！".to_string(), Some(value!(medias))).json()
+        }
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+/**
+ * 上传文件，multipart表单中的每个文件各上传一次，返回上传后的文件列表
+ */
+#[routes]
+#[post("/media")]
+pub async fn upload_media(
+    auth: Authorized<BlogWrite>,
+    app: Data<AppState>,
+    mut payload: Multipart,
+) -> impl Responder {
+    let max_size = CONFIG.get_media_config().max_size;
+    let mut medias = vec![];
+    while let Some(field) = payload.next().await {
+        let mut field = match field {
+            Ok(field) => field,
+            Err(e) => return HttpResponse::from_error(e),
+        };
+        //跳过没有文件名的普通字段
+        let Some(file_name) = field
+            .content_disposition()
+            .and_then(|disposition| disposition.get_filename())
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        let content_type = field.content_type().map(|mime| mime.to_string());
+        let mut bytes = vec![];
+        while let Some(chunk) = field.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(e) => return HttpResponse::from_error(e),
+            };
+            if bytes.len() + chunk.len() > max_size {
+                return ApiResponse::<String>::error_with_code(
+                    WebErrorCode::VALIDATION_ERROR,
+                    format!("{} 超过{}KB", file_name, max_size / 1024),
+                )
+                .json();
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        match MediaService::upload(
+            &file_name,
+            content_type.as_deref(),
+            bytes,
+            auth.account_id as i64,
+            app.get_mysql_pool(),
+        )
+        .await
+        {
+            Ok(media) => medias.push(media),
+            Err(e) => return HttpResponse::from_error(e),
+        }
+    }
+    if medias.is_empty() {
+        return ApiResponse::<String>::error_with_code(
+            WebErrorCode::VALIDATION_ERROR,
+            "请选择要上传的文件".to_string(),
+        )
+        .json();
+    }
+    ApiResponse::success_with_msg("上传成功！".to_string(), Some(value!(medias))).json()
+}
+
+//仍被文章、动态等引用的文件不能删除，编辑只能删除自己上传的文件
+#[routes]
+#[delete("/media")]
+pub async fn delete_media(
+    auth: Authorized<BlogWrite>,
+    app: Data<AppState>,
+    parameter: web::Query<HashMap<String, i64>>,
+) -> impl Responder {
+    let id = *parameter.get("id").unwrap_or(&0);
+    match MediaService::delete_media(id, auth.blog_owner(), app.get_mysql_pool()).await {
+        Ok(_) => ApiResponse::<String>::success_with_msg("删除成功！".to_string(), None).json(),
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
@@ -1,12 +1,11 @@
 use crate::app::AppState;
 use crate::error::WebErrorCode;
-use crate::middleware::AppClaims;
+use crate::middleware::{Authorized, ContentRead, ContentWrite};
 use crate::model::MomentDTO;
 use crate::model::SearchRequest;
 use crate::model::ApiResponse;
 use crate::service::MomentService;
-use actix_jwt_session::Authenticated;
-use actix_web::{routes, web, Responder};
+use actix_web::{routes, web, HttpResponse, Responder};
 use rbs::value;
 use std::collections::HashMap;
 
@@ -17,20 +16,20 @@ use std::collections::HashMap;
 #[post("/moment")]
 pub async fn create_moment(
     moment: web::Json<MomentDTO>,
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentWrite>,
     app: web::Data<AppState>,
 ) -> impl Responder {
     let moment = MomentService::create_and_update(moment.into_inner(), app.get_mysql_pool()).await;
     match moment {
         Ok(_) => ApiResponse::<String>::success_with_msg("创建成功".to_string(), None).json(),
-        Err(e) => ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json(),
+        Err(e) => HttpResponse::from_error(e),
     }
 }
 
 #[routes]
 #[get("/moments")]
 pub async fn moments(
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentRead>,
     mut query: web::Query<SearchRequest>,
     app: web::Data<AppState>,
 ) -> impl Responder {
@@ -50,7 +49,7 @@ pub async fn moments(
         Ok(value_map) => {
             ApiResponse::success(Some( value!(value_map))).json()
         } // 返回一个包含map的JSON响应;
-        Err(e) => ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json(),
+        Err(e) => HttpResponse::from_error(e),
     }
 }
 
@@ -62,7 +61,7 @@ pub async fn moments(
 #[put("/moment/published")]
 pub async fn moment_published(
     query: web::Query<HashMap<String, String>>,
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentWrite>,
     app: web::Data<AppState>,
 ) -> impl Responder {
     let id = query.get("id").unwrap().parse::<i64>().unwrap_or(0);
@@ -73,7 +72,7 @@ pub async fn moment_published(
     let is_published = query.get("published").unwrap().parse::<bool>().unwrap();
     let row = MomentService::update_published(id, is_published, app.get_mysql_pool()).await;
     if let Err(e) = row {
-        return ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json();
+        return HttpResponse::from_error(e);
     }
     ApiResponse::<String>::success_with_msg("更新成功".to_string(), None).json()
 }
@@ -82,7 +81,7 @@ pub async fn moment_published(
 #[get("/moment")]
 pub async fn get_moment_by_id(
     query: web::Query<HashMap<String, String>>,
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentRead>,
     app: web::Data<AppState>,
 ) -> impl Responder {
     let id = query.get("id").unwrap().parse::<i64>().unwrap_or(0);
@@ -92,7 +91,7 @@ pub async fn get_moment_by_id(
     let moment = MomentService::get_moment_by_id(id, app.get_mysql_pool()).await;
     match moment {
         Ok(m) => ApiResponse::success(Some( value!(m))).json(),
-        Err(e) => ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json(),
+        Err(e) => HttpResponse::from_error(e),
     }
 }
 
@@ -104,7 +103,7 @@ pub async fn get_moment_by_id(
 #[delete("/moment")]
 pub async fn delete_moment(
     query: web::Query<HashMap<String, String>>,
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentWrite>,
     app: web::Data<AppState>,
 ) -> impl Responder {
     let id = query.get("id").unwrap().parse::<i64>().unwrap_or(0);
@@ -113,7 +112,7 @@ pub async fn delete_moment(
     }
     let row = MomentService::delete_moment(id, app.get_mysql_pool()).await;
     if let Err(e) = row {
-        return ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json();
+        return HttpResponse::from_error(e);
     }
     ApiResponse::<String>::success_with_msg("删除成功".to_string(), None).json()
 }
@@ -125,12 +124,12 @@ pub async fn delete_moment(
 #[put("/moment")]
 pub async fn update_moment(
     moment: web::Json<MomentDTO>,
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentWrite>,
     app: web::Data<AppState>,
 ) -> impl Responder {
     let moment = MomentService::create_and_update(moment.into_inner(), app.get_mysql_pool()).await;
     match moment {
         Ok(_) => ApiResponse::<String>::success_with_msg("更新成功".to_string(), None).json(),
-        Err(e) => ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json(),
+        Err(e) => HttpResponse::from_error(e),
     }
 }
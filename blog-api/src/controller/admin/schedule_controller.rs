@@ -2,11 +2,11 @@ use std::collections::HashMap;
 
 use crate::entity::schedule_job;
 use crate::entity::schedule_job_log;
-use crate::middleware::AppClaims;
+use crate::middleware::{Authorized, SystemRead, SystemWrite};
 use crate::model::ScheduleJob;
 use crate::model::ScheduleJobLog;
+use crate::schedule::Scheduler;
 use crate::{app::AppState, model::ApiResponse};
-use actix_jwt_session::Authenticated;
 use actix_web::{routes, web, Responder};
 use chrono::Utc;
 use rbs::value;
@@ -52,7 +52,7 @@ pub struct JobLogQuery {
 #[routes]
 #[get("/jobs")]
 pub async fn get_job_list(
-    _: Authenticated<AppClaims>,
+    _: Authorized<SystemRead>,
     app: web::Data<AppState>,
     query: web::Query<JobQuery>,
 ) -> impl Responder {
@@ -101,7 +101,7 @@ pub async fn get_job_list(
 #[routes]
 #[put("/job/status")]
 pub async fn update_job_status(
-    _: Authenticated<AppClaims>,
+    _: Authorized<SystemWrite>,
     app: web::Data<AppState>,
     params: web::Query<JobStatusUpdate>,
 ) -> impl Responder {
@@ -116,11 +116,17 @@ pub async fn update_job_status(
             active_job.status = Set(Some(params.status));
 
             match active_job.update(db).await {
-                Ok(_) => ApiResponse::<String>::success_with_msg(
-                    "更新定时任务状态成功".to_string(),
-                    None,
-                )
-                .json(),
+                Ok(_) => {
+                    //重新加载任务，状态变更立即生效
+                    if let Err(e) = Scheduler::refresh_job(job_id, db).await {
+                        log::error!("定时任务[{}]重新加载失败:{}", job_id, e);
+                    }
+                    ApiResponse::<String>::success_with_msg(
+                        "更新定时任务状态成功".to_string(),
+                        None,
+                    )
+                    .json()
+                }
                 Err(e) => {
                     ApiResponse::<String>::error(format!("更新定时任务状态失败: {}", e)).json()
                 }
@@ -134,22 +140,39 @@ pub async fn update_job_status(
 #[routes]
 #[post("/job/run")]
 pub async fn run_job_once(
-    _: Authenticated<AppClaims>,
-    // app: web::Data<AppState>,
-    // params: web::Query<JobIdParam>,
+    _: Authorized<SystemWrite>,
+    app: web::Data<AppState>,
+    params: web::Query<JobIdParam>,
 ) -> impl Responder {
-    // let db = app.get_mysql_pool();
-    // let job_id = params.job_id;
+    let db = app.get_mysql_pool();
+    let job_id = params.job_id;
+
+    let job = match schedule_job::Entity::find_by_id(job_id).one(db).await {
+        Ok(Some(job)) => job,
+        Ok(None) => return ApiResponse::<String>::error("定时任务不存在".to_string()).json(),
+        Err(e) => return ApiResponse::<String>::error(format!("查询定时任务失败: {}", e)).json(),
+    };
 
-    // 这里需要实现执行定时任务的逻辑
-    // 由于没有相应的服务实现，这里先返回一个占位响应
-    ApiResponse::<String>::success_with_msg("执行定时任务成功".to_string(), None).json()
+    match Scheduler::run_job(job, db).await {
+        Ok(job_log) => {
+            if job_log.status {
+                ApiResponse::<String>::success_with_msg("执行定时任务成功".to_string(), None).json()
+            } else {
+                ApiResponse::<String>::error(format!(
+                    "执行定时任务失败: {}",
+                    job_log.error.unwrap_or_default()
+                ))
+                .json()
+            }
+        }
+        Err(e) => ApiResponse::<String>::error(format!("执行定时任务失败: {}", e)).json(),
+    }
 }
 
 #[routes]
 #[delete("/job")]
 pub async fn delete_job_by_id(
-    _: Authenticated<AppClaims>,
+    _: Authorized<SystemWrite>,
     app: web::Data<AppState>,
     params: web::Query<JobIdParam>,
 ) -> impl Responder {
@@ -159,6 +182,7 @@ pub async fn delete_job_by_id(
     match schedule_job::Entity::delete_by_id(job_id).exec(db).await {
         Ok(result) => {
             if result.rows_affected > 0 {
+                Scheduler::remove_job(job_id);
                 ApiResponse::<String>::success_with_msg("删除定时任务成功".to_string(), None).json()
             } else {
                 ApiResponse::<String>::error("定时任务不存在".to_string()).json()
@@ -171,13 +195,17 @@ pub async fn delete_job_by_id(
 #[routes]
 #[post("/job")]
 pub async fn add_job(
-    _: Authenticated<AppClaims>,
+    _: Authorized<SystemWrite>,
     app: web::Data<AppState>,
     job: web::Json<ScheduleJob>,
 ) -> impl Responder {
     let db = app.get_mysql_pool();
     let now = Utc::now().naive_utc();
 
+    if let Err(e) = Scheduler::parse_cron(job.cron.as_deref().unwrap_or_default()) {
+        return ApiResponse::<String>::error(e.to_string()).json();
+    }
+
     let new_job = schedule_job::ActiveModel {
         job_id: NotSet,
         bean_name: Set(job.bean_name.clone()),
@@ -190,7 +218,10 @@ pub async fn add_job(
     };
 
     match new_job.insert(db).await {
-        Ok(_) => {
+        Ok(job_model) => {
+            if let Err(e) = Scheduler::refresh_job(job_model.job_id, db).await {
+                log::error!("定时任务[{}]加载失败:{}", job_model.job_id, e);
+            }
             ApiResponse::<String>::success_with_msg("添加定时任务成功".to_string(), None).json()
         }
         Err(e) => ApiResponse::<String>::error(format!("添加定时任务失败: {}", e)).json(),
@@ -200,7 +231,7 @@ pub async fn add_job(
 #[routes]
 #[put("/job")]
 pub async fn edit_job(
-    _: Authenticated<AppClaims>,
+    _: Authorized<SystemWrite>,
     app: web::Data<AppState>,
     job: web::Json<ScheduleJob>,
 ) -> impl Responder {
@@ -212,6 +243,10 @@ pub async fn edit_job(
 
     let job_id = job.job_id.unwrap();
 
+    if let Err(e) = Scheduler::parse_cron(job.cron.as_deref().unwrap_or_default()) {
+        return ApiResponse::<String>::error(e.to_string()).json();
+    }
+
     let result = schedule_job::Entity::find_by_id(job_id).one(db).await;
 
     match result {
@@ -226,6 +261,9 @@ pub async fn edit_job(
 
             match active_job.update(db).await {
                 Ok(_) => {
+                    if let Err(e) = Scheduler::refresh_job(job_id, db).await {
+                        log::error!("定时任务[{}]重新加载失败:{}", job_id, e);
+                    }
                     ApiResponse::<String>::success_with_msg("更新定时任务成功".to_string(), None)
                         .json()
                 }
@@ -240,7 +278,7 @@ pub async fn edit_job(
 #[routes]
 #[get("/job/logs")]
 pub async fn get_job_log_list(
-    _: Authenticated<AppClaims>,
+    _: Authorized<SystemRead>,
     app: web::Data<AppState>,
     query: web::Query<JobLogQuery>,
 ) -> impl Responder {
@@ -289,7 +327,7 @@ pub async fn get_job_log_list(
 #[routes]
 #[delete("/job/log")]
 pub async fn delete_job_log_by_log_id(
-    _: Authenticated<AppClaims>,
+    _: Authorized<SystemWrite>,
     app: web::Data<AppState>,
     params: web::Query<LogIdParam>,
 ) -> impl Responder {
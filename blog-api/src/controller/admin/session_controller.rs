@@ -0,0 +1,45 @@
+use crate::middleware::AppClaims;
+use crate::model::ApiResponse;
+use crate::service::SessionService;
+use actix_jwt_session::{Authenticated, SessionStorage, Uuid};
+use actix_web::{routes, web, HttpResponse, Responder};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct SessionIdParam {
+    pub jwt_id: String,
+}
+
+/**
+ * 当前账号的登录会话列表
+ */
+#[routes]
+#[get("/sessions")]
+pub async fn get_sessions(auth: Authenticated<AppClaims>) -> impl Responder {
+    match SessionService::find_sessions(auth.account_id as i64, auth.jwt_id).await {
+        Ok(sessions) => {
+            ApiResponse::success_with_msg("获取登录会话成功".to_string(), Some(sessions)).json()
+        }
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+/**
+ * 注销指定的登录会话
+ */
+#[routes]
+#[delete("/sessions")]
+pub async fn revoke_session(
+    auth: Authenticated<AppClaims>,
+    store: web::Data<SessionStorage>,
+    params: web::Query<SessionIdParam>,
+) -> impl Responder {
+    let jwt_id = match Uuid::parse_str(&params.jwt_id) {
+        Ok(jwt_id) => jwt_id,
+        Err(_) => return ApiResponse::<String>::error("会话不存在".to_string()).json(),
+    };
+    match SessionService::revoke_session(auth.account_id as i64, jwt_id, &store).await {
+        Ok(_) => ApiResponse::<String>::success_with_msg("注销会话成功".to_string(), None).json(),
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
@@ -1,14 +1,22 @@
-use crate::middleware::AppClaims;
+use crate::middleware::{Authorized, ContentRead, ContentWrite};
+use crate::model::SiteSetting;
 use crate::service::SiteSettingService;
 use crate::{app::AppState, model::ApiResponse};
-use actix_jwt_session::Authenticated;
 use actix_web::{routes, web, Responder};
 use rbs::value;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct SiteSettingUpdateRequest {
+    pub settings: Vec<SiteSetting>,
+    #[serde(rename = "deleteIds", default)]
+    pub delete_ids: Vec<i64>,
+}
 
 #[routes]
 #[get("/siteSettings")]
 pub async fn get_site_setting_data(
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentRead>,
     app: web::Data<AppState>,
 ) -> impl Responder {
     match SiteSettingService::get_site_info(app.get_mysql_pool()).await {
@@ -23,19 +31,29 @@ pub async fn get_site_setting_data(
 #[routes]
 #[post("/siteSettings")]
 pub async fn update_site_settings(
-    _: Authenticated<AppClaims>,
-    _app: web::Data<AppState>,
-    //  _request: web::Json<SiteSettingUpdateRequest>,
+    _: Authorized<ContentWrite>,
+    app: web::Data<AppState>,
+    request: web::Json<SiteSettingUpdateRequest>,
 ) -> impl Responder {
-    // 这里需要实现更新站点设置的逻辑
-    // 由于服务层没有提供更新方法，这里先返回一个占位响应
-    ApiResponse::<String>::success_with_msg("站点设置更新成功".to_string(), None).json()
+    let request = request.into_inner();
+    match SiteSettingService::update_site_settings(
+        request.settings,
+        request.delete_ids,
+        app.get_mysql_pool(),
+    )
+    .await
+    {
+        Ok(_) => {
+            ApiResponse::<String>::success_with_msg("站点设置更新成功".to_string(), None).json()
+        }
+        Err(e) => ApiResponse::<String>::error(format!("站点设置更新失败: {}", e)).json(),
+    }
 }
 
 #[routes]
 #[get("/webTitleSuffix")]
 pub async fn get_web_title_suffix(
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentRead>,
     //  app: web::Data<AppState>,
 ) -> impl Responder {
     // 这里需要实现获取网站标题后缀的逻辑
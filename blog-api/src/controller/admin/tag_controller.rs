@@ -3,19 +3,18 @@ use std::collections::HashMap;
 use crate::{
     app::AppState,
     error::WebErrorCode,
-    middleware::AppClaims,
+    middleware::{Authorized, ContentRead, ContentWrite},
     model::ApiResponse,
     model::{SearchRequest, TagDTO},
     service::TagService,
 };
-use actix_jwt_session::Authenticated;
-use actix_web::{routes, web, Responder};
+use actix_web::{routes, web, HttpResponse, Responder};
 use rbs::value;
 
 #[routes]
 #[get("/tags")]
 pub async fn get_all_tags(
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentRead>,
     params: web::Query<SearchRequest>,
     app: web::Data<AppState>,
 ) -> impl Responder {
@@ -35,9 +34,7 @@ pub async fn get_all_tags(
     .await;
     match tags_result {
         Ok(value_map) => ApiResponse::success(Some( value!(value_map))).json(),
-        Err(e) => {
-            ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json()
-        }
+        Err(e) => HttpResponse::from_error(e),
     }
 }
 
@@ -45,23 +42,21 @@ pub async fn get_all_tags(
 #[put("/tag")]
 #[post("/tag")]
 pub async fn insert_or_update(
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentWrite>,
     tag: web::Json<TagDTO>,
     app: web::Data<AppState>,
 ) -> impl Responder {
     let tag_result = TagService::insert_or_update(tag.into_inner(), app.get_mysql_pool()).await;
     match tag_result {
         Ok(_) => ApiResponse::<String>::success_with_msg("操作成功！".to_string(), None).json(),
-        Err(e) => {
-            ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json()
-        }
+        Err(e) => HttpResponse::from_error(e),
     }
 }
 
 #[routes]
 #[delete("/tag")]
 pub async fn delete_by_id(
-    _: Authenticated<AppClaims>,
+    _: Authorized<ContentWrite>,
     query: web::Query<HashMap<String, i64>>,
     app: web::Data<AppState>,
 ) -> impl Responder {
@@ -79,8 +74,6 @@ pub async fn delete_by_id(
     };
     match TagService::delete_by_id(id, app.get_mysql_pool()).await {
         Ok(_) => ApiResponse::<String>::success_with_msg("操作成功！".to_string(), None).json(),
-        Err(e) => {
-            ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json()
-        }
+        Err(e) => HttpResponse::from_error(e),
     }
 }
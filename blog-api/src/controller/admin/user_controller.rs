@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use crate::middleware::{Authorized, SystemRead, SystemWrite};
+use crate::service::{SessionService, UserService};
+use crate::{app::AppState, model::ApiResponse};
+use actix_jwt_session::SessionStorage;
+use actix_web::{routes, web, HttpResponse, Responder};
+use rbs::value;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct UserQuery {
+    pub page_num: Option<u64>,
+    pub page_size: Option<u64>,
+    pub username: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserCreate {
+    pub username: String,
+    pub password: String,
+    pub nickname: Option<String>,
+    pub email: Option<String>,
+    pub role: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserRole {
+    pub role: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserStatus {
+    pub status: bool,
+}
+
+/**
+ * 后台账号列表
+ */
+#[routes]
+#[get("/users")]
+pub async fn get_users(
+    _: Authorized<SystemRead>,
+    app: web::Data<AppState>,
+    query: web::Query<UserQuery>,
+) -> impl Responder {
+    let query = query.into_inner();
+    match UserService::find_users(
+        query.page_num.unwrap_or(1),
+        query.page_size.unwrap_or(10),
+        query.username.filter(|username| !username.is_empty()),
+        app.get_mysql_pool(),
+    )
+    .await
+    {
+        Ok((total, users)) => {
+            let mut result = HashMap::new();
+            result.insert("total".to_string(), value!(total));
+            result.insert("records".to_string(), value!(users));
+            ApiResponse::success_with_msg("获取账号列表成功".to_string(), Some(value!(result)))
+                .json()
+        }
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+/**
+ * 创建后台账号
+ */
+#[routes]
+#[post("/user")]
+pub async fn create_user(
+    _: Authorized<SystemWrite>,
+    app: web::Data<AppState>,
+    form: web::Json<UserCreate>,
+) -> impl Responder {
+    let nickname = form
+        .nickname
+        .clone()
+        .unwrap_or_else(|| form.username.clone());
+    match UserService::create_user(
+        &form.username,
+        &form.password,
+        &nickname,
+        form.email.as_deref().unwrap_or(""),
+        &form.role,
+        app.get_mysql_pool(),
+    )
+    .await
+    {
+        Ok(_) => ApiResponse::<String>::success_with_msg("创建账号成功".to_string(), None).json(),
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+/**
+ * 分配角色，该账号已登录的会话全部失效
+ */
+#[routes]
+#[put("/user/{id}/role")]
+pub async fn update_user_role(
+    auth: Authorized<SystemWrite>,
+    app: web::Data<AppState>,
+    store: web::Data<SessionStorage>,
+    path: web::Path<i64>,
+    form: web::Json<UserRole>,
+) -> impl Responder {
+    let id = path.into_inner();
+    if id == auth.account_id as i64 {
+        return ApiResponse::<String>::error("不能修改自己的角色".to_string()).json();
+    }
+    match UserService::update_role(id, &form.role, app.get_mysql_pool()).await {
+        Ok(username) => {
+            if let Err(e) = SessionService::revoke_all_sessions(id, &store).await {
+                log::error!("用户:{}角色修改后注销会话失败:{}", username, e);
+            }
+            ApiResponse::<String>::success_with_msg("分配角色成功".to_string(), None).json()
+        }
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+/**
+ * 启用或禁用账号，禁用后该账号已登录的会话全部失效
+ */
+#[routes]
+#[put("/user/{id}/status")]
+pub async fn update_user_status(
+    auth: Authorized<SystemWrite>,
+    app: web::Data<AppState>,
+    store: web::Data<SessionStorage>,
+    path: web::Path<i64>,
+    form: web::Json<UserStatus>,
+) -> impl Responder {
+    let id = path.into_inner();
+    if id == auth.account_id as i64 {
+        return ApiResponse::<String>::error("不能禁用自己的账号".to_string()).json();
+    }
+    match UserService::update_status(id, form.status, app.get_mysql_pool()).await {
+        Ok(username) => {
+            if !form.status {
+                if let Err(e) = SessionService::revoke_all_sessions(id, &store).await {
+                    log::error!("用户:{}禁用后注销会话失败:{}", username, e);
+                }
+            }
+            ApiResponse::<String>::success_with_msg("修改账号状态成功".to_string(), None).json()
+        }
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
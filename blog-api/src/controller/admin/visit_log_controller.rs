@@ -1,10 +1,9 @@
 use std::collections::HashMap;
 
 use crate::entity::visit_log;
-use crate::middleware::AppClaims;
+use crate::middleware::{Authorized, SystemRead, SystemWrite};
 use crate::model::VisitLog;
 use crate::{app::AppState, model::ApiResponse};
-use actix_jwt_session::Authenticated;
 use actix_web::{routes, web, Responder};
 use rbs::value;
 use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
@@ -27,7 +26,7 @@ pub struct LogIdParam {
 #[routes]
 #[get("/visitLogs")]
 pub async fn get_visit_log_list(
-    _: Authenticated<AppClaims>,
+    _: Authorized<SystemRead>,
     app: web::Data<AppState>,
     query: web::Query<VisitLogQuery>,
 ) -> impl Responder {
@@ -80,7 +79,7 @@ pub async fn get_visit_log_list(
 #[routes]
 #[delete("/visitLog")]
 pub async fn delete_visit_log_by_id(
-    _: Authenticated<AppClaims>,
+    _: Authorized<SystemWrite>,
     app: web::Data<AppState>,
     params: web::Query<LogIdParam>,
 ) -> impl Responder {
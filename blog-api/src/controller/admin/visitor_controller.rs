@@ -1,11 +1,12 @@
 use std::collections::HashMap;
 
 use crate::entity::visitor;
-use crate::middleware::AppClaims;
+use crate::middleware::{Authorized, SystemRead, SystemWrite};
 use crate::model::Visitor;
+use crate::service::VisitService;
 use crate::{app::AppState, model::ApiResponse};
-use actix_jwt_session::Authenticated;
 use actix_web::{routes, web, Responder};
+use chrono::{Local, NaiveDate};
 use rbs::value;
 use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
 use serde::Deserialize;
@@ -18,6 +19,11 @@ pub struct VisitorQuery {
     pub ip_source: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct VisitorSyncParam {
+    pub date: Option<NaiveDate>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct VisitorDeleteParam {
     pub id: i64,
@@ -27,7 +33,7 @@ pub struct VisitorDeleteParam {
 #[routes]
 #[get("/visitors")]
 pub async fn get_visitor_list(
-    _: Authenticated<AppClaims>,
+    _: Authorized<SystemRead>,
     app: web::Data<AppState>,
     query: web::Query<VisitorQuery>,
 ) -> impl Responder {
@@ -73,7 +79,7 @@ pub async fn get_visitor_list(
 #[routes]
 #[delete("/visitor")]
 pub async fn delete_visitor(
-    _: Authenticated<AppClaims>,
+    _: Authorized<SystemWrite>,
     app: web::Data<AppState>,
     params: web::Query<VisitorDeleteParam>,
 ) -> impl Responder {
@@ -105,3 +111,22 @@ pub async fn delete_visitor(
         Err(e) => ApiResponse::<String>::error(format!("查询访客失败: {}", e)).json(),
     }
 }
+
+/**
+ * 手动统计访问数据，date 默认为今天(yyyy-MM-dd)
+ */
+#[routes]
+#[post("/visitor/sync")]
+pub async fn sync_visit_info(
+    _: Authorized<SystemWrite>,
+    app: web::Data<AppState>,
+    params: web::Query<VisitorSyncParam>,
+) -> impl Responder {
+    let date = params.date.unwrap_or_else(|| Local::now().date_naive());
+    match VisitService::sync_visit_info(date, app.get_mysql_pool()).await {
+        Ok(_) => {
+            ApiResponse::<String>::success_with_msg(format!("{} 访问统计完成", date), None).json()
+        }
+        Err(e) => ApiResponse::<String>::error(format!("访问统计失败: {}", e)).json(),
+    }
+}
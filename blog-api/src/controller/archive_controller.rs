@@ -5,10 +5,9 @@
  * @LastEditTime: 2024-05-18 09:58:55
  */
 use crate::app::AppState;
-use crate::error::WebErrorCode;
 use crate::model::ApiResponse;
 use crate::service::BlogService;
-use actix_web::{get, web, Responder};
+use actix_web::{get, web, HttpResponse, Responder};
 use rbs::value;
 use rbs::value::map::ValueMap;
 
@@ -24,8 +23,6 @@ pub(crate) async fn archives(app: web::Data<AppState>) -> impl Responder {
             data.insert(value!("count"), value!(count.unwrap_or_default()));
             ApiResponse::success(Some(value!(data))).json()
         }
-        Err(e) => {
-            ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json()
-        }
+        Err(e) => HttpResponse::from_error(e),
     }
 }
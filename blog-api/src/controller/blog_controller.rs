@@ -1,13 +1,15 @@
 use crate::app::AppState;
-use crate::common::ParamUtils;
+use crate::common::{IpRegion, ParamUtils};
+use crate::constant::BlogInfoConstant;
 use crate::error::WebErrorCode;
 use crate::model::SearchRequest;
 use crate::model::ApiResponse;
 use crate::service;
 use actix_web::web::{self, Json, Query};
-use actix_web::{routes, Responder};
+use actix_web::{routes, HttpRequest, HttpResponse, Responder};
 use rbs::value;
-use service::BlogService;
+use rbs::value::map::ValueMap;
+use service::{BlogPasswordResult, BlogPasswordService, BlogService, SearchService};
 use std::collections::HashMap;
 
 //按置顶、创建时间排序 分页查询博客简要信息列表
@@ -21,14 +23,13 @@ pub async fn blogs(params: Query<SearchRequest>, app: web::Data<AppState>) -> im
 
     match BlogService::find_list_by_page(page_num, db_conn).await {
         Ok(page) => ApiResponse::success(Some(value!(page))).json(),
-        Err(e) => {
-            ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json()
-        }
+        Err(e) => HttpResponse::from_error(e),
     }
 }
 #[routes]
 #[get("/blog")]
 pub async fn blog(
+    req: HttpRequest,
     params: Query<HashMap<String, String>>,
     app: web::Data<AppState>,
 ) -> impl Responder {
@@ -43,7 +44,33 @@ pub async fn blog(
 
     let blog = BlogService::find_id_detail(id, app.get_mysql_pool()).await;
     match blog {
-        Some(blog) => ApiResponse::success(Some(value!(blog))).json(),
+        Some(mut blog) => {
+            //加密文章需要携带密码校验通过后签发的解锁token
+            let token = req
+                .headers()
+                .get(BlogInfoConstant::UNLOCK_TOKEN_HEADER)
+                .and_then(|value| value.to_str().ok());
+            if !BlogPasswordService::is_unlocked(id, blog.password.as_deref(), token) {
+                return ApiResponse::<String>::error_with_code(
+                    WebErrorCode::FORBIDDEN,
+                    "文章受密码保护，请验证密码！".to_string(),
+                )
+                .json();
+            }
+            //浏览量计数，按访客标识码去重，没有标识码时使用IP
+            let visitor = match req
+                .headers()
+                .get("Identification")
+                .and_then(|value| value.to_str().ok())
+            {
+                Some(identification) if !identification.is_empty() => identification.to_string(),
+                _ => IpRegion::get_request_ip(&req),
+            };
+            if BlogService::increase_views(id, &visitor).await {
+                blog.views += 1;
+            }
+            ApiResponse::success(Some(value!(blog))).json()
+        }
         None => {
             ApiResponse::<String>::error_with_code(WebErrorCode::NOT_FOUND, "博客不存在".to_string())
                 .json()
@@ -114,6 +141,7 @@ pub async fn tag(
 #[routes]
 #[post("/checkBlogPassword")]
 pub async fn check_blog_password(
+    req: HttpRequest,
     data: Json<SearchRequest>,
     app: web::Data<AppState>,
 ) -> impl Responder {
@@ -138,35 +166,66 @@ pub async fn check_blog_password(
         }
     };
 
-    let password = data.get_password();
-    if blog_info.password.clone().unwrap_or_default() == password {
-        ApiResponse::success_with_msg("验证成功,密码正确!".to_string(), Some(value!(blog_info)))
-            .json()
-    } else {
-        ApiResponse::<String>::error_with_code(WebErrorCode::VALIDATION_ERROR, "密码错误".to_string())
-            .json()
+    let ip = IpRegion::get_request_ip(&req);
+    match BlogPasswordService::check_password(
+        blog_id,
+        blog_info.password.as_deref(),
+        &data.get_password(),
+        &ip,
+        app.get_mysql_pool(),
+    )
+    .await
+    {
+        Ok(BlogPasswordResult::Unlocked(token, expires)) => {
+            let mut map = ValueMap::new();
+            map.insert(value!("token"), value!(token));
+            map.insert(value!("expires"), value!(expires));
+            map.insert(value!("blog"), value!(blog_info));
+            ApiResponse::success_with_msg("验证成功,密码正确!".to_string(), Some(value!(map)))
+                .json()
+        }
+        Ok(BlogPasswordResult::Wrong) => ApiResponse::<String>::error_with_code(
+            WebErrorCode::VALIDATION_ERROR,
+            "密码错误".to_string(),
+        )
+        .json(),
+        Ok(BlogPasswordResult::Locked(seconds)) => ApiResponse::<String>::error_with_code(
+            WebErrorCode::FORBIDDEN,
+            format!("密码错误次数过多，请{}秒后再试", seconds),
+        )
+        .json(),
+        Err(e) => HttpResponse::from_error(e),
     }
 }
 
+//搜索关键词最大长度
+const SEARCH_KEYWORD_MAX_LEN: usize = 50;
+
 #[routes]
 #[get("/searchBlog")]
-pub async fn search_blog(
-    query: Query<HashMap<String, String>>,
-    app: web::Data<AppState>,
-) -> impl Responder {
-    let blog_title = match ParamUtils::get_string_param(&query, "query") {
-        Ok(title) => title,
+pub async fn search_blog(query: Query<HashMap<String, String>>) -> impl Responder {
+    let keyword = match ParamUtils::get_string_param(&query, "query") {
+        Ok(keyword) => keyword,
         Err(e) => {
             return ApiResponse::<String>::error_with_code(e.error_code(), e.message().to_string())
                 .json()
         }
     };
+    if keyword.trim().is_empty() || keyword.chars().count() > SEARCH_KEYWORD_MAX_LEN {
+        return ApiResponse::<String>::error_with_code(
+            WebErrorCode::VALIDATION_ERROR,
+            format!("搜索关键词长度须在1-{}之间", SEARCH_KEYWORD_MAX_LEN),
+        )
+        .json();
+    }
+    let page_num = ParamUtils::get_i64_param(&query, "pageNum").unwrap_or(1).max(1) as u64;
+    let page_size = ParamUtils::get_i64_param(&query, "pageSize").unwrap_or(10).max(1) as u64;
+    let category_id = ParamUtils::get_i64_param(&query, "categoryId").ok();
+    let tag_id = ParamUtils::get_i64_param(&query, "tagId").ok();
 
-    //查找title内容的文章
-    match BlogService::search_content(blog_title, app.get_mysql_pool()).await {
+    //全文检索，按相关度排序
+    match SearchService::search(&keyword, category_id, tag_id, page_num, page_size) {
         Ok(result) => ApiResponse::success(Some(value!(result))).json(),
-        Err(e) => {
-            ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json()
-        }
+        Err(e) => HttpResponse::from_error(e),
     }
 }
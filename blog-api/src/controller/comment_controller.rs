@@ -1,14 +1,19 @@
 use crate::app::AppState;
+use crate::common::IpRegion;
+use crate::constant::BlogInfoConstant;
 use crate::error::WebErrorCode;
 use crate::model::ApiResponse;
-use crate::model::CommentDTO;
+use crate::model::CommentForm;
 use crate::model::SearchRequest;
-use crate::service::CommentService;
+use crate::service::{
+    CommentService, CommentSubmission, FilterResult, MailService, COMMENT_FILTER_CHAIN,
+};
 use actix_web::routes;
 use actix_web::web::{self, Query};
-use actix_web::{get, Responder};
+use actix_web::{get, HttpRequest, HttpResponse, Responder};
 use rbs::value;
 use rbs::value::map::ValueMap;
+use serde::Deserialize;
 
 #[get("/comments")]
 pub(crate) async fn get_comments(
@@ -38,18 +43,13 @@ pub(crate) async fn get_comments(
         page_request.get_page_num(),
         page_request.get_blog_id(),
         page_request.get_page(),
+        page_request.get_sort(),
         connect,
     )
     .await
     {
         Ok(list) => list,
-        Err(e) => {
-            return ApiResponse::<String>::error_with_code(
-                WebErrorCode::DATABASE_ERROR,
-                e.to_string(),
-            )
-            .json();
-        }
+        Err(e) => return HttpResponse::from_error(e),
     };
 
     let mut data = ValueMap::new();
@@ -59,44 +59,117 @@ pub(crate) async fn get_comments(
         Ok(close_comment) => {
             data.insert("allComment".into(), value!(close_comment));
         }
-        Err(e) => {
-            return ApiResponse::<String>::error_with_code(
-                WebErrorCode::DATABASE_ERROR,
-                e.to_string(),
-            )
-            .json();
-        }
+        Err(e) => return HttpResponse::from_error(e),
     }
     match CommentService::get_close_count(page_request.get_blog_id(),page_request.get_page(), connect).await {
         Ok(close_comment) => {
             data.insert("closeComment".into(), value!(close_comment));
         }
-        Err(e) => {
-            return ApiResponse::<String>::error_with_code(
-                WebErrorCode::DATABASE_ERROR,
-                e.to_string(),
-            )
-            .json();
-        }
+        Err(e) => return HttpResponse::from_error(e),
     }
 
     ApiResponse::success_with_msg("获取成功!".to_string(), Some(value!(data))).json()
 }
 
+//前台只能新增评论，提交的评论先经过过滤链检查
 #[routes]
 #[post("/comment")]
 pub async fn save_comment(
+    req: HttpRequest,
     state: web::Data<AppState>,
-    comment_dto: web::Json<CommentDTO>,
+    form: web::Json<CommentForm>,
 ) -> impl Responder {
-    match CommentService::save_comment(comment_dto.0, &state.mysql_connection).await {
-        Ok(_) => return ApiResponse::<String>::success(None).json(),
-        Err(e) => {
-            return ApiResponse::<String>::error_with_code(
-                WebErrorCode::DATABASE_ERROR,
-                e.to_string(),
-            )
-            .json()
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string())
+    };
+    let submission = CommentSubmission {
+        form: form.into_inner(),
+        ip: IpRegion::get_request_ip(&req),
+        visitor: header("Identification"),
+        unlock_token: header(BlogInfoConstant::UNLOCK_TOKEN_HEADER),
+    };
+    let db = state.get_mysql_pool();
+    let held = match COMMENT_FILTER_CHAIN.run(&submission, db).await {
+        Ok(FilterResult::Pass) => false,
+        Ok(FilterResult::Hold(_)) => true,
+        Ok(FilterResult::Reject(reason)) => {
+            return ApiResponse::<String>::error_with_code(WebErrorCode::VALIDATION_ERROR, reason)
+                .json()
         }
+        Err(e) => return HttpResponse::from_error(e),
+    };
+    let comment =
+        match CommentService::create_comment(submission.form, submission.ip, held, db).await {
+            Ok(comment) => comment,
+            Err(e) => return HttpResponse::from_error(e),
+        };
+    MailService::notify_comment(comment, db.clone());
+    match held {
+        true => {
+            ApiResponse::<String>::success_with_msg("评论已提交，审核通过后显示".to_string(), None)
+                .json()
+        }
+        false => ApiResponse::<String>::success_with_msg("评论成功".to_string(), None).json(),
     }
 }
+
+#[derive(Debug, Deserialize)]
+pub struct UnsubscribeParam {
+    pub email: String,
+    pub token: String,
+}
+
+/**
+ * 邮件中的退订链接：只展示确认页面，避免邮件扫描或预取链接时误退订
+ */
+#[routes]
+#[get("/unsubscribe")]
+pub async fn unsubscribe_page(params: Query<UnsubscribeParam>) -> impl Responder {
+    if !MailService::verify_unsubscribe(&params.email, &params.token) {
+        return unsubscribe_html("<p>退订链接无效</p>".to_string());
+    }
+    //RFC 8058 一键退订的请求体为 List-Unsubscribe=One-Click，确认页面提交相同的内容
+    unsubscribe_html(format!(
+        "<p>确定不再接收评论邮件通知吗？</p>\
+         <form method=\"post\" action=\"unsubscribe?email={}&amp;token={}\">\
+         <input type=\"hidden\" name=\"List-Unsubscribe\" value=\"One-Click\">\
+         <button type=\"submit\">确认退订</button></form>",
+        urlencoding::encode(&params.email),
+        urlencoding::encode(&params.token)
+    ))
+}
+
+/**
+ * 退订邮件通知：确认页面提交或邮件客户端按 List-Unsubscribe-Post 一键退订
+ */
+#[routes]
+#[post("/unsubscribe")]
+pub async fn unsubscribe(
+    params: Query<UnsubscribeParam>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let result =
+        MailService::unsubscribe(&params.email, &params.token, state.get_mysql_pool()).await;
+    let message = match result {
+        Ok(true) => "已退订邮件通知",
+        Ok(false) => "退订链接无效",
+        Err(e) => {
+            log::error!("退订邮件通知失败:{}", e);
+            "退订失败，请稍后再试"
+        }
+    };
+    unsubscribe_html(format!("<p>{}</p>", message))
+}
+
+fn unsubscribe_html(content: String) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(format!(
+            "<div style=\"text-align:center;margin-top:40px;\">{}</div>",
+            content
+        ))
+}
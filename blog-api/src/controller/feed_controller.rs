@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::str::FromStr;
+use std::time::SystemTime;
+
+use crate::app::AppState;
+use crate::common::{Feed, FeedFormat};
+use crate::error::WebErrorCode;
+use crate::model::ApiResponse;
+use crate::service::FeedService;
+use actix_web::http::header::{self, ETag, EntityTag, HttpDate, LastModified};
+use actix_web::web::Query;
+use actix_web::{routes, web, HttpRequest, HttpResponse};
+
+/**
+ * 文章订阅源，可通过 category、tag 参数订阅指定分类或标签
+ */
+#[routes]
+#[get("/feed.xml")]
+#[get("/atom.xml")]
+#[get("/feed.json")]
+pub async fn blog_feed(
+    req: HttpRequest,
+    query: Query<HashMap<String, String>>,
+    app: web::Data<AppState>,
+) -> HttpResponse {
+    let format = FeedFormat::from_path(req.path());
+    let category_name = query.get("category").filter(|name| !name.is_empty());
+    let tag_name = query.get("tag").filter(|name| !name.is_empty());
+    match FeedService::find_blog_feed(
+        category_name.cloned(),
+        tag_name.cloned(),
+        feed_path(&req),
+        app.get_mysql_pool(),
+    )
+    .await
+    {
+        Ok(Some(feed)) => feed_response(&req, &feed, format),
+        Ok(None) => ApiResponse::<String>::error_with_code(
+            WebErrorCode::NOT_FOUND,
+            "分类或标签不存在".to_string(),
+        )
+        .json(),
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+/**
+ * 动态订阅源
+ */
+#[routes]
+#[get("/moments/feed.xml")]
+#[get("/moments/atom.xml")]
+#[get("/moments/feed.json")]
+pub async fn moment_feed(req: HttpRequest, app: web::Data<AppState>) -> HttpResponse {
+    let format = FeedFormat::from_path(req.path());
+    match FeedService::find_moment_feed(feed_path(&req), app.get_mysql_pool()).await {
+        Ok(feed) => feed_response(&req, &feed, format),
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
+
+/**
+ * 订阅源的请求路径，由 FeedService 拼接配置的前端地址，不使用请求头中的 Host
+ */
+fn feed_path(req: &HttpRequest) -> String {
+    req.uri()
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or_else(|| req.path())
+        .to_string()
+}
+
+/**
+ * 输出订阅源并携带 ETag、Last-Modified，内容未变化时返回304
+ */
+fn feed_response(req: &HttpRequest, feed: &Feed, format: FeedFormat) -> HttpResponse {
+    let body = feed.render(format);
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    let etag = EntityTag::new_strong(format!("{:x}", hasher.finish()));
+    let last_modified = feed
+        .last_modified()
+        .map(|time| HttpDate::from(SystemTime::from(time)));
+
+    if is_not_modified(req, &etag, last_modified) {
+        return HttpResponse::NotModified()
+            .insert_header(ETag(etag))
+            .finish();
+    }
+    let mut response = HttpResponse::Ok();
+    response
+        .content_type(format.content_type())
+        .insert_header(ETag(etag));
+    if let Some(last_modified) = last_modified {
+        response.insert_header(LastModified(last_modified));
+    }
+    response.body(body)
+}
+
+fn is_not_modified(req: &HttpRequest, etag: &EntityTag, last_modified: Option<HttpDate>) -> bool {
+    let headers = req.headers();
+    //存在 If-None-Match 时忽略 If-Modified-Since
+    if let Some(if_none_match) = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+    {
+        return if_none_match.split(',').map(str::trim).any(|tag| {
+            tag == "*"
+                || EntityTag::from_str(tag)
+                    .map(|tag| tag.weak_eq(etag))
+                    .unwrap_or(false)
+        });
+    }
+    let if_modified_since = headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| HttpDate::from_str(value).ok());
+    match (last_modified, if_modified_since) {
+        (Some(last_modified), Some(since)) => {
+            SystemTime::from(last_modified) <= SystemTime::from(since)
+        }
+        _ => false,
+    }
+}
@@ -1,8 +1,7 @@
 use crate::app::AppState;
-use crate::error::WebErrorCode;
 use crate::model::ApiResponse;
 use crate::service::FriendService;
-use actix_web::{get, web, Responder};
+use actix_web::{get, web, HttpResponse, Responder};
 
 //获取友链信息
 #[get("/friends")]
@@ -11,8 +10,6 @@ pub(crate) async fn get_friend(app: web::Data<AppState>) -> impl Responder {
         Ok(friend) => {
             ApiResponse::success(Some(friend)).json()
         }
-        Err(e) => {
-            ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json()
-        }
+        Err(e) => HttpResponse::from_error(e),
     }
 }
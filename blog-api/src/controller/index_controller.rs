@@ -2,7 +2,7 @@ use crate::app::AppState;
 use crate::error::WebErrorCode;
 use crate::model::ApiResponse;
 use crate::service::{BlogService, CategoryService, SiteSettingService, TagService};
-use actix_web::{routes, web, Responder};
+use actix_web::{routes, web, HttpResponse, Responder};
 use rbs::value;
 /**
    Site 数据
@@ -16,61 +16,31 @@ pub async fn site(app: web::Data<AppState>) -> impl Responder {
     // 获取站点信息
     let mut map = match SiteSettingService::find_site_info(connect).await {
         Ok(data) => data,
-        Err(e) => {
-            return ApiResponse::<String>::error_with_code(
-                WebErrorCode::DATABASE_ERROR,
-                e.to_string(),
-            )
-            .json()
-        }
+        Err(e) => return HttpResponse::from_error(e),
     };
 
     // 获取分类列表
     let category_list = match CategoryService::get_list(connect).await {
         Ok(data) => data,
-        Err(e) => {
-            return ApiResponse::<String>::error_with_code(
-                WebErrorCode::DATABASE_ERROR,
-                e.to_string(),
-            )
-            .json()
-        }
+        Err(e) => return HttpResponse::from_error(e),
     };
 
     // 获取随机博客列表
     let random_list = match BlogService::find_list_random(connect).await {
         Ok(data) => data,
-        Err(e) => {
-            return ApiResponse::<String>::error_with_code(
-                WebErrorCode::DATABASE_ERROR,
-                e.to_string(),
-            )
-            .json()
-        }
+        Err(e) => return HttpResponse::from_error(e),
     };
 
     // 获取最新博客列表
     let new_list = match BlogService::find_list_new(connect).await {
         Ok(data) => data,
-        Err(e) => {
-            return ApiResponse::<String>::error_with_code(
-                WebErrorCode::DATABASE_ERROR,
-                e.to_string(),
-            )
-            .json()
-        }
+        Err(e) => return HttpResponse::from_error(e),
     };
 
     // 获取标签列表
     let tag_list = match TagService::get_tags(connect).await {
         Ok(data) => data,
-        Err(e) => {
-            return ApiResponse::<String>::error_with_code(
-                WebErrorCode::DATABASE_ERROR,
-                e.to_string(),
-            )
-            .json()
-        }
+        Err(e) => return HttpResponse::from_error(e),
     };
 
     // 组合数据
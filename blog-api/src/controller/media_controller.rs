@@ -0,0 +1,36 @@
+use std::io::ErrorKind;
+
+use crate::error::{DataBaseError, WebErrorCode};
+use crate::model::ApiResponse;
+use crate::service::MediaService;
+use actix_files::NamedFile;
+use actix_web::http::header::{HeaderValue, CACHE_CONTROL, X_CONTENT_TYPE_OPTIONS};
+use actix_web::web::Path;
+use actix_web::{routes, HttpRequest, HttpResponse};
+
+//本地存储的媒体文件，文件名为内容哈希，可以长期缓存；ETag与Range请求由NamedFile处理
+#[routes]
+#[get("/upload/{key:.*}")]
+pub async fn media_file(req: HttpRequest, key: Path<String>) -> HttpResponse {
+    let Some(path) = MediaService::local_path(&key) else {
+        return not_found();
+    };
+    match NamedFile::open_async(path).await {
+        Ok(file) => {
+            let mut response = file.into_response(&req);
+            let headers = response.headers_mut();
+            headers.insert(
+                CACHE_CONTROL,
+                HeaderValue::from_static("public, max-age=31536000, immutable"),
+            );
+            headers.insert(X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+            response
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => not_found(),
+        Err(e) => HttpResponse::from_error(DataBaseError::Custom(format!("文件读取失败:{}", e))),
+    }
+}
+
+fn not_found() -> HttpResponse {
+    ApiResponse::<String>::error_with_code(WebErrorCode::NOT_FOUND, "文件不存在".to_string()).json()
+}
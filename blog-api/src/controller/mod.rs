@@ -9,7 +9,10 @@ pub mod admin;
 pub mod archive_controller;
 pub mod blog_controller;
 pub mod comment_controller;
+pub mod feed_controller;
 pub mod friend_controller;
 pub mod index_controller;
+pub mod media_controller;
 pub mod moment_controller;
+pub mod sitemap_controller;
 pub mod user_controller;
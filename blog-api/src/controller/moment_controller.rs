@@ -5,7 +5,7 @@ use crate::model::ApiResponse;
 use crate::service::MomentService;
 use actix_web::web::Path;
 use actix_web::{routes, web};
-use actix_web::{web::Query, Responder};
+use actix_web::{web::Query, HttpResponse, Responder};
 use rbs::value;
 
 //动态
@@ -28,7 +28,7 @@ pub(crate) async fn moments(
     .await
     {
         Ok(data) => ApiResponse::success(Some(value!(data))).json(),
-        Err(e) => ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json(),
+        Err(e) => HttpResponse::from_error(e),
     }
 }
 
@@ -41,6 +41,6 @@ pub async fn moment_like(id: Path<i64>, app: web::Data<AppState>) -> impl Respon
     }
     match MomentService::moment_like(id, app.get_mysql_pool()).await {
         Ok(_) => ApiResponse::<String>::success_with_msg("点赞成功".to_string(),None).json(),
-        Err(e) => ApiResponse::<String>::error_with_code(WebErrorCode::DATABASE_ERROR, e.to_string()).json(),
+        Err(e) => HttpResponse::from_error(e),
     }
 }
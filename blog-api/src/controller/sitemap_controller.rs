@@ -0,0 +1,42 @@
+use crate::app::AppState;
+use crate::error::WebErrorCode;
+use crate::model::ApiResponse;
+use crate::service::SitemapService;
+use actix_web::web::Path;
+use actix_web::{routes, web, HttpResponse};
+
+const XML_CONTENT_TYPE: &str = "application/xml; charset=utf-8";
+
+//站点地图(URL数量超过上限时为索引)
+#[routes]
+#[get("/sitemap.xml")]
+pub async fn sitemap(app: web::Data<AppState>) -> HttpResponse {
+    sitemap_response(None, &app).await
+}
+
+//站点地图分片
+#[routes]
+#[get("/sitemap-{page}.xml")]
+pub async fn sitemap_page(page: Path<usize>, app: web::Data<AppState>) -> HttpResponse {
+    sitemap_response(Some(page.into_inner()), &app).await
+}
+
+#[routes]
+#[get("/robots.txt")]
+pub async fn robots() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .body(SitemapService::robots())
+}
+
+async fn sitemap_response(page: Option<usize>, app: &AppState) -> HttpResponse {
+    match SitemapService::find_sitemap(page, app.get_mysql_pool()).await {
+        Ok(Some(xml)) => HttpResponse::Ok().content_type(XML_CONTENT_TYPE).body(xml),
+        Ok(None) => ApiResponse::<String>::error_with_code(
+            WebErrorCode::NOT_FOUND,
+            "站点地图不存在".to_string(),
+        )
+        .json(),
+        Err(e) => HttpResponse::from_error(e),
+    }
+}
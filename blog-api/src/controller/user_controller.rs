@@ -8,19 +8,24 @@
 
 use crate::app::AppState;
 use crate::app::CONFIG;
-use crate::model::ApiResponse;
+use crate::common::IpRegion;
+use crate::constant::{LoginConstant, RoleConstant};
+use crate::error::WebErrorCode;
+use crate::model::{ApiResponse, User};
+use crate::service::{LoginService, SessionService, TotpService};
 use crate::{middleware::AppClaims, service::UserService};
+use actix_jwt_session::{Authenticated, MaybeAuthenticated, RefreshToken, JWT_COOKIE_NAME};
 use actix_jwt_session::{
     JwtTtl, OffsetDateTime, RefreshTtl, SessionStorage, Uuid, JWT_HEADER_NAME, REFRESH_HEADER_NAME,
 };
-use actix_jwt_session::{MaybeAuthenticated, JWT_COOKIE_NAME};
 use actix_web::{
     routes,
     web::{Data, Json},
-    HttpResponse, Responder,
+    HttpRequest, HttpResponse, Responder,
 };
 use rbs::value::map::ValueMap;
 use rbs::{value, Value};
+use sea_orm::DatabaseConnection;
 use serde::Deserialize;
 
 #[derive(Deserialize)]
@@ -32,6 +37,7 @@ struct SignInPayload {
 #[routes]
 #[post("/login")]
 pub async fn login(
+    req: HttpRequest,
     user_form: Json<SignInPayload>,
     store: Data<SessionStorage>,
     jwt_ttl: Data<JwtTtl>,
@@ -39,110 +45,392 @@ pub async fn login(
     session: MaybeAuthenticated<AppClaims>,
     app: Data<AppState>,
 ) -> impl Responder {
+    let ip = IpRegion::get_request_ip(&req);
+    let user_agent = req
+        .headers()
+        .get("User-Agent")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    //用户名或IP登录失败次数过多，锁定期间拒绝登录
+    if let Some(seconds) = LoginService::find_lock_seconds(&user_form.username, &ip).await {
+        LoginService::save_login_log(
+            &user_form.username,
+            &ip,
+            &user_agent,
+            false,
+            "登录失败次数过多，锁定中",
+            app.get_mysql_pool(),
+        )
+        .await;
+        return ApiResponse::<Value>::error(lock_message(seconds)).json();
+    }
     //验证账号 密码是否正确
     let mut user = UserService::get_by_username(&user_form.username, app.get_mysql_pool()).await;
     if let Ok(user) = user.as_mut() {
-        //验证账号密码是否正确,排除非Admin账号登录
-        if user_form.password != user.get_password() || user.get_role() != "ROLE_admin" {
-            //密码错误或者非Admin账号登录
-            log::error!(
-                "用户{}登录失败，密码错误或者非Admin账号登录",
-                user_form.username
-            );
-            return ApiResponse::<Value>::error("用户名或密码错误！".to_string()).json();
+        //验证账号密码是否正确(明文密码校验通过后会升级为哈希),排除非Admin账号登录
+        let password_ok = match UserService::check_login_password(
+            user,
+            &user_form.password,
+            app.get_mysql_pool(),
+        )
+        .await
+        {
+            Ok(ok) => ok,
+            Err(e) => {
+                log::error!("用户{}密码校验异常:{}", user_form.username, e);
+                false
+            }
+        };
+        //没有后台角色或者账号已禁用的用户不能登录
+        let role_ok = RoleConstant::is_valid(&user.get_role());
+        if !password_ok || !role_ok || !user.is_enabled() {
+            let reason = if !password_ok {
+                "密码错误"
+            } else if !role_ok {
+                "没有后台角色"
+            } else {
+                "账号已禁用"
+            };
+            log::error!("用户{}登录失败，{}", user_form.username, reason);
+            return login_failed(
+                &user_form.username,
+                &ip,
+                &user_agent,
+                reason,
+                true,
+                app.get_mysql_pool(),
+            )
+            .await;
         } else {
+            //已启用两步验证：返回预登录token，校验动态码后再签发token
+            if user.is_totp_enabled() {
+                let token = match TotpService::create_pre_auth(&user.get_username()).await {
+                    Ok(token) => token,
+                    Err(e) => return HttpResponse::from_error(e),
+                };
+                let mut map: ValueMap = ValueMap::new();
+                map.insert(value!("totpRequired"), value!(true));
+                map.insert(value!("preAuthToken"), value!(token));
+                map.insert(value!("expires"), value!(LoginConstant::PRE_AUTH_SECONDS));
+                return ApiResponse::<Value>::success_with_msg(
+                    "请输入两步验证码".to_string(),
+                    Some(value!(map)),
+                )
+                .json();
+            }
             //密码正确并且权限正确，登录成功返回token
-            let mut map: ValueMap = ValueMap::new();
-            //验证是否登录过
-            if session.is_authenticated() {
-                //获取session
-                let sess = match session.as_ref() {
-                    Some(sess) => sess,
-                    None => {
-                        log::warn!("获取session异常");
+            return login_success(
+                user,
+                &ip,
+                &user_agent,
+                &session,
+                &store,
+                &jwt_ttl,
+                &refresh_ttl,
+                app.get_mysql_pool(),
+            )
+            .await;
+        }
+    }
+    log::warn!("用户名{}尝试登录，未找到用户", user_form.username);
+    login_failed(
+        &user_form.username,
+        &ip,
+        &user_agent,
+        "用户不存在",
+        false,
+        app.get_mysql_pool(),
+    )
+    .await
+}
+
+#[derive(Deserialize)]
+struct TotpPayload {
+    #[serde(rename = "preAuthToken")]
+    pre_auth_token: String,
+    code: String,
+}
+
+/**
+ * 登录第二步：凭预登录token与动态码(或恢复码)完成登录
+ */
+#[routes]
+#[post("/login/totp")]
+pub async fn login_totp(
+    req: HttpRequest,
+    totp_form: Json<TotpPayload>,
+    store: Data<SessionStorage>,
+    jwt_ttl: Data<JwtTtl>,
+    refresh_ttl: Data<RefreshTtl>,
+    session: MaybeAuthenticated<AppClaims>,
+    app: Data<AppState>,
+) -> impl Responder {
+    let ip = IpRegion::get_request_ip(&req);
+    let user_agent = req
+        .headers()
+        .get("User-Agent")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    let username = match TotpService::find_pre_auth(&totp_form.pre_auth_token).await {
+        Ok(Some(username)) => username,
+        Ok(None) => {
+            return ApiResponse::<Value>::error("两步验证已过期，请重新登录！".to_string()).json()
+        }
+        Err(e) => return HttpResponse::from_error(e),
+    };
+    if let Some(seconds) = LoginService::find_lock_seconds(&username, &ip).await {
+        LoginService::save_login_log(
+            &username,
+            &ip,
+            &user_agent,
+            false,
+            "登录失败次数过多，锁定中",
+            app.get_mysql_pool(),
+        )
+        .await;
+        return ApiResponse::<Value>::error(lock_message(seconds)).json();
+    }
+    match TotpService::verify_login(&username, &totp_form.code, app.get_mysql_pool()).await {
+        Ok(true) => {}
+        Ok(false) => {
+            log::error!("用户{}登录失败，两步验证码错误", username);
+            return login_failed(
+                &username,
+                &ip,
+                &user_agent,
+                "两步验证码错误",
+                true,
+                app.get_mysql_pool(),
+            )
+            .await;
+        }
+        Err(e) => return HttpResponse::from_error(e),
+    }
+    if let Err(e) = TotpService::remove_pre_auth(&totp_form.pre_auth_token).await {
+        log::error!("删除预登录token失败:{}", e);
+    }
+    let mut user = match UserService::get_by_username(&username, app.get_mysql_pool()).await {
+        Ok(user) => user,
+        Err(e) => return HttpResponse::from_error(e),
+    };
+    login_success(
+        &mut user,
+        &ip,
+        &user_agent,
+        &session,
+        &store,
+        &jwt_ttl,
+        &refresh_ttl,
+        app.get_mysql_pool(),
+    )
+    .await
+}
+
+/**
+ * 登录成功：清除失败次数、记录登录日志并签发token，同一账号已登录过的沿用原token
+ */
+#[allow(clippy::too_many_arguments)]
+async fn login_success(
+    user: &mut User,
+    ip: &str,
+    user_agent: &str,
+    session: &MaybeAuthenticated<AppClaims>,
+    store: &SessionStorage,
+    jwt_ttl: &JwtTtl,
+    refresh_ttl: &RefreshTtl,
+    db: &DatabaseConnection,
+) -> HttpResponse {
+    LoginService::clear_failures(&user.get_username(), ip).await;
+    LoginService::save_login_log(&user.get_username(), ip, user_agent, true, "登录成功", db).await;
+    let mut map: ValueMap = ValueMap::new();
+    //验证是否登录过，只沿用同一账号的token，其它账号的token不能返回给当前登录的账号
+    let same_account = session.as_ref().is_some_and(|sess| {
+        sess.subject == user.get_username() && sess.account_id as i64 == user.get_id()
+    });
+    if session.is_authenticated() && same_account {
+        //获取session
+        let sess = match session.as_ref() {
+            Some(sess) => sess,
+            None => {
+                log::warn!("获取session异常");
+                return ApiResponse::<Value>::error("登录失败，请重新登录！".to_string()).json();
+            }
+        };
+        //获取uuid
+        let uuid = sess.jwt_id.clone();
+        //通过UUID查找是否登录过
+        match store.find_jwt::<AppClaims>(uuid).await {
+            Ok(_) => {
+                let token = match sess.encode().clone() {
+                    Ok(token) => token,
+                    Err(e) => {
+                        log::warn!("获取uuid异常:{}", e);
                         return ApiResponse::<Value>::error("登录失败，请重新登录！".to_string())
                             .json();
                     }
                 };
-                //获取uuid
-                let uuid = sess.jwt_id.clone();
-                //通过UUID查找是否登录过
-                match store.find_jwt::<AppClaims>(uuid).await {
-                    Ok(_) => {
-                        let token = match sess.encode().clone() {
-                            Ok(token) => token,
-                            Err(e) => {
-                                log::warn!("获取uuid异常:{}", e);
-                                return ApiResponse::<Value>::error(
-                                    "登录失败，请重新登录！".to_string(),
-                                )
-                                .json();
-                            }
-                        };
-                        user.set_password("".to_string());
-                        map.insert(value!("user"), value!(user));
-                        map.insert(value!("token"), value!(token.clone()));
-                        map.insert(
-                            value!("expires"),
-                            value!(CONFIG.get_server_config().token_expires),
-                        );
-                        let result = ApiResponse::<Value>::success_with_msg(
-                            "请求成功".to_string(),
-                            Some(value!(map)),
-                        );
-                        log::info!("用户:{}已登录过,无需重复登录", user_form.username);
-                        return HttpResponse::Ok()
-                            .append_header((JWT_HEADER_NAME, token.clone()))
-                            .cookie(
-                                actix_web::cookie::Cookie::build(JWT_COOKIE_NAME, token).finish(),
-                            )
-                            .json(result);
-                    }
-                    Err(e) => {
-                        log::warn!("find_jwt异常:{}", e);
-                    }
-                };
+                user.set_password("".to_string());
+                map.insert(value!("user"), value!(&*user));
+                map.insert(value!("token"), value!(token.clone()));
+                map.insert(
+                    value!("expires"),
+                    value!(CONFIG.get_server_config().token_expires),
+                );
+                let result = ApiResponse::<Value>::success_with_msg(
+                    "请求成功".to_string(),
+                    Some(value!(map)),
+                );
+                log::info!("用户:{}已登录过,无需重复登录", user.get_username());
+                return HttpResponse::Ok()
+                    .append_header((JWT_HEADER_NAME, token.clone()))
+                    .cookie(actix_web::cookie::Cookie::build(JWT_COOKIE_NAME, token).finish())
+                    .json(result);
             }
-            //登录
-            log::info!("用户:{}登录成功", user_form.username);
-            let uuid = Uuid::new_v4();
-            //创建认证数据
-            let claims = AppClaims {
-                issues_at: OffsetDateTime::now_utc().unix_timestamp() as usize,
-                subject: user.get_username(),
-                expiration_time: jwt_ttl.0.as_seconds_f64() as u64,
-                //audience: Audience::Web,
-                jwt_id: Uuid::parse_str(uuid.to_string().as_str()).unwrap(),
-                account_id: user.get_id() as i32,
-                not_before: 0,
-            };
-            let pair = store
-                .clone()
-                .store(claims, *jwt_ttl.into_inner(), *refresh_ttl.into_inner())
-                .await
-                .unwrap();
+            Err(e) => {
+                log::warn!("find_jwt异常:{}", e);
+            }
+        };
+    }
+    //登录
+    log::info!("用户:{}登录成功", user.get_username());
+    let uuid = Uuid::new_v4();
+    //创建认证数据
+    let claims = AppClaims {
+        issues_at: OffsetDateTime::now_utc().unix_timestamp() as usize,
+        subject: user.get_username(),
+        expiration_time: jwt_ttl.0.as_seconds_f64() as u64,
+        //audience: Audience::Web,
+        jwt_id: Uuid::parse_str(uuid.to_string().as_str()).unwrap(),
+        account_id: user.get_id() as i32,
+        not_before: 0,
+        role: user.get_role(),
+    };
+    let pair = store
+        .clone()
+        .store(claims, *jwt_ttl, *refresh_ttl)
+        .await
+        .unwrap();
+    if let Err(e) = SessionService::save_session(
+        user.get_id(),
+        &user.get_username(),
+        uuid,
+        ip,
+        user_agent,
+        refresh_ttl.0.whole_seconds().max(0) as u64,
+    )
+    .await
+    {
+        log::error!("保存登录会话失败:{}", e);
+    }
 
-            user.set_password("".to_string());
-            map.insert(value!("user"), value!(user));
-            map.insert(value!("token"), value!(pair.jwt.encode().unwrap()));
-            map.insert(
-                value!("expires"),
-                value!(CONFIG.get_server_config().token_expires),
-            );
-            let result = ApiResponse::<Value>::success_with_msg(
-                "请求成功".to_string(),
-                Some(value!(map)),
-            );
-            return HttpResponse::Ok()
-                .append_header((JWT_HEADER_NAME, pair.jwt.encode().unwrap()))
-                .append_header((REFRESH_HEADER_NAME, pair.refresh.encode().unwrap()))
-                .cookie(
-                    actix_web::cookie::Cookie::build(JWT_COOKIE_NAME, pair.jwt.encode().unwrap())
-                        .finish(),
-                )
-                .json(result);
+    user.set_password("".to_string());
+    map.insert(value!("user"), value!(user));
+    map.insert(value!("token"), value!(pair.jwt.encode().unwrap()));
+    map.insert(
+        value!("expires"),
+        value!(CONFIG.get_server_config().token_expires),
+    );
+    let result = ApiResponse::<Value>::success_with_msg("请求成功".to_string(), Some(value!(map)));
+    HttpResponse::Ok()
+        .append_header((JWT_HEADER_NAME, pair.jwt.encode().unwrap()))
+        .append_header((REFRESH_HEADER_NAME, pair.refresh.encode().unwrap()))
+        .cookie(
+            actix_web::cookie::Cookie::build(JWT_COOKIE_NAME, pair.jwt.encode().unwrap()).finish(),
+        )
+        .json(result)
+}
+
+/**
+ * 使用刷新token换取新的token
+ */
+#[routes]
+#[post("/refresh")]
+pub async fn refresh(
+    refresh_token: Authenticated<RefreshToken>,
+    store: Data<SessionStorage>,
+    refresh_ttl: Data<RefreshTtl>,
+) -> impl Responder {
+    let pair = match store.refresh::<AppClaims>(refresh_token.access_jti).await {
+        Ok(pair) => pair,
+        Err(e) => {
+            log::warn!("用户:{}刷新token失败:{}", refresh_token.sub, e);
+            return ApiResponse::<Value>::error_with_code(
+                WebErrorCode::UNAUTHORIZED,
+                "登录已过期，请重新登录！".to_string(),
+            )
+            .json();
         }
+    };
+    if let Err(e) = SessionService::refresh_session(
+        pair.jwt.account_id as i64,
+        refresh_token.access_jti,
+        pair.jwt.jwt_id,
+        refresh_ttl.0.whole_seconds().max(0) as u64,
+    )
+    .await
+    {
+        log::error!("更新登录会话失败:{}", e);
     }
-    log::warn!("用户名{}尝试登录，未找到用户", user_form.username);
-    ApiResponse::<String>::error("用户名或密码错误！".to_string()).json()
+    let (jwt, refresh) = match (pair.jwt.encode(), pair.refresh.encode()) {
+        (Ok(jwt), Ok(refresh)) => (jwt, refresh),
+        _ => return ApiResponse::<Value>::error("刷新token失败！".to_string()).json(),
+    };
+    let mut map: ValueMap = ValueMap::new();
+    map.insert(value!("token"), value!(jwt.clone()));
+    map.insert(
+        value!("expires"),
+        value!(CONFIG.get_server_config().token_expires),
+    );
+    let result = ApiResponse::<Value>::success_with_msg("请求成功".to_string(), Some(value!(map)));
+    HttpResponse::Ok()
+        .append_header((JWT_HEADER_NAME, jwt.clone()))
+        .append_header((REFRESH_HEADER_NAME, refresh))
+        .cookie(actix_web::cookie::Cookie::build(JWT_COOKIE_NAME, jwt).finish())
+        .json(result)
+}
+
+/**
+ * 退出登录：注销当前会话并清除token cookie
+ */
+#[routes]
+#[post("/logout")]
+pub async fn logout(auth: Authenticated<AppClaims>, store: Data<SessionStorage>) -> impl Responder {
+    if let Err(e) =
+        SessionService::remove_session(auth.account_id as i64, auth.jwt_id, &store).await
+    {
+        return HttpResponse::from_error(e);
+    }
+    log::info!("用户:{}退出登录", auth.subject);
+    let mut cookie = actix_web::cookie::Cookie::build(JWT_COOKIE_NAME, "").finish();
+    cookie.make_removal();
+    HttpResponse::Ok()
+        .cookie(cookie)
+        .json(ApiResponse::<Value>::success_with_msg(
+            "退出登录成功".to_string(),
+            None,
+        ))
+}
+
+/**
+ * 记录登录失败，失败次数达到上限时提示锁定时长
+ */
+async fn login_failed(
+    username: &str,
+    ip: &str,
+    user_agent: &str,
+    reason: &str,
+    user_exists: bool,
+    db: &DatabaseConnection,
+) -> HttpResponse {
+    LoginService::save_login_log(username, ip, user_agent, false, reason, db).await;
+    match LoginService::record_failure(username, ip, user_exists).await {
+        Some(seconds) => ApiResponse::<Value>::error(lock_message(seconds)).json(),
+        None => ApiResponse::<Value>::error("用户名或密码错误！".to_string()).json(),
+    }
+}
+
+fn lock_message(seconds: u64) -> String {
+    format!("登录失败次数过多，请{}分钟后再试！", seconds.div_ceil(60))
 }
@@ -3,6 +3,7 @@
 use chrono::Local;
 use sea_orm::entity::prelude::*;
 use crate::model::BlogVO;
+use super::sea_orm_active_enums::BlogStatus;
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Default)]
 #[sea_orm(table_name = "blog")]
 pub struct Model {
@@ -27,6 +28,10 @@ pub struct Model {
     pub category_id: i64,
     pub password: Option<String>,
     pub user_id: Option<i64>,
+    pub status: BlogStatus,
+    pub publish_at: Option<DateTime>,
+    #[sea_orm(unique)]
+    pub slug: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -78,6 +83,9 @@ impl From<BlogVO> for Model {
             category_id: blog_vo.category_id,
             password: blog_vo.password,
             user_id: blog_vo.user_id,
+            status: blog_vo.status.unwrap_or_default(),
+            publish_at: blog_vo.publish_at,
+            slug: None,
         }
     }
 }
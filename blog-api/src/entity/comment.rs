@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.0
 
+use crate::common::MarkdownParser;
 use crate::model::CommentDTO;
 use sea_orm::entity::prelude::*;
 
@@ -11,6 +12,7 @@ pub struct Model {
     pub nickname: String,
     pub email: String,
     pub content: String,
+    pub content_html: Option<String>,
     pub avatar: String,
     pub create_time: Option<DateTime>,
     pub ip: Option<String>,
@@ -35,6 +37,7 @@ impl From<CommentDTO> for Model {
             id: dto.id,
             nickname: dto.nickname,
             email: dto.email.unwrap_or_default(),
+            content_html: Some(MarkdownParser::parser_comment_html(&dto.content)),
             content: dto.content,
             avatar: dto.avatar,
             is_published: dto.published.unwrap_or_default(),
@@ -19,6 +19,7 @@ pub struct Model {
     pub browser: Option<String>,
     pub create_time: DateTime,
     pub user_agent: Option<String>,
+    pub fingerprint: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -3,18 +3,24 @@
 pub mod prelude;
 
 pub mod about;
+pub mod api_token;
 pub mod blog;
+pub mod blog_revision;
 pub mod blog_tag;
 pub mod category;
 pub mod city_visitor;
 pub mod comment;
+pub mod comment_block;
 pub mod exception_log;
 pub mod friend;
 pub mod login_log;
+pub mod mail_unsubscribe;
+pub mod media;
 pub mod moment;
 pub mod operation_log;
 pub mod schedule_job;
 pub mod schedule_job_log;
+pub mod sea_orm_active_enums;
 pub mod site_setting;
 pub mod tag;
 pub mod user;
@@ -15,6 +15,11 @@ pub struct Model {
     pub create_time: DateTime,
     pub update_time: DateTime,
     pub role: String,
+    pub totp_secret: Option<String>,
+    pub totp_enabled: bool,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub totp_recovery_codes: Option<String>,
+    pub status: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -1,7 +1,11 @@
+use actix_web::{HttpResponse, ResponseError};
 use deadpool_redis::PoolError;
 use sea_orm::{DbErr, TransactionError};
 use thiserror::Error;
 
+use crate::error::WebErrorCode;
+use crate::model::ApiResponse;
+
 #[derive(Error, Debug)]
 pub enum DataBaseError {
     #[error("redis 异常原因：{0}")]
@@ -25,9 +29,31 @@ pub enum DataBaseError {
     #[error("正则表达式异常原因 : {0}")]
     RegexError(#[from] regex::Error),
 
+    #[error("全文检索异常原因 : {0}")]
+    SearchError(#[from] tantivy::TantivyError),
+
     #[error("未知异常原因：{0}")]
     Custom(String),
+
+    /// 请求参数或业务校验不通过，不属于数据库异常，不记录异常日志
+    #[error("{0}")]
+    Validation(String),
 }
+
+/**
+ * 控制器通过 HttpResponse::from_error 返回数据库异常，响应体与其它接口一致，
+ * 同时异常会保留在响应中，供异常日志中间件记录(校验错误除外)
+ */
+impl ResponseError for DataBaseError {
+    fn error_response(&self) -> HttpResponse {
+        let code = match self {
+            DataBaseError::Validation(_) => WebErrorCode::VALIDATION_ERROR,
+            _ => WebErrorCode::DATABASE_ERROR,
+        };
+        ApiResponse::<String>::error_with_code(code, self.to_string()).json()
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
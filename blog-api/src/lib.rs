@@ -6,6 +6,7 @@ mod entity;
 mod error;
 mod middleware;
 mod model;
+mod schedule;
 mod service;
 
-pub use app::AppServer;
+pub use app::{AppCommand, AppServer};
@@ -0,0 +1,171 @@
+use crate::app::AppState;
+use crate::error::{DataBaseError, WebErrorCode};
+use crate::model::ApiResponse;
+use crate::service::{ExceptionLogService, OperationLogService};
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::Method,
+    web::Data,
+    Error, HttpRequest, HttpResponse,
+};
+use std::{
+    any::Any,
+    future::{ready, Future, Ready},
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+/**
+ * 记录失败的请求：5xx响应、数据库异常以及处理请求时发生的panic
+ * panic 会被捕获并返回500，不会导致工作线程退出
+ */
+#[derive(Default, Debug)]
+pub struct ExceptionLogger;
+
+impl<S, B> Transform<S, ServiceRequest> for ExceptionLogger
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = ExceptionLogMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ExceptionLogMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct ExceptionLogMiddleware<S> {
+    /// The next service to call
+    service: Rc<S>,
+}
+
+type LocalBoxFuture<T> = Pin<Box<dyn Future<Output = T> + 'static>>;
+
+impl<S, B> Service<ServiceRequest> for ExceptionLogMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        Box::pin(async move {
+            //GET请求只记录查询参数，其它请求只读取有限长度的JSON与表单请求体
+            let param = match *req.method() == Method::GET {
+                true => OperationLogService::format_param(req.query_string(), &[], ""),
+                false => OperationLogService::read_param(&mut req).await,
+            };
+            let http_req = req.request().clone();
+
+            let result = match panic::catch_unwind(AssertUnwindSafe(|| service.call(req))) {
+                Ok(fut) => CatchUnwind(Box::pin(fut)).await,
+                Err(panic) => Err(panic),
+            };
+            match result {
+                Ok(Ok(res)) => {
+                    let failure = match res.response().error() {
+                        Some(error) => match error.as_error::<DataBaseError>() {
+                            //校验错误是正常的业务响应，不记录
+                            Some(DataBaseError::Validation(_)) => None,
+                            Some(error) => {
+                                Some(("数据库异常", ExceptionLogService::error_chain(error)))
+                            }
+                            None if res.status().is_server_error() => {
+                                Some(("服务器错误", error.to_string()))
+                            }
+                            None => None,
+                        },
+                        None if res.status().is_server_error() => {
+                            Some(("服务器错误", format!("HTTP {}", res.status())))
+                        }
+                        None => None,
+                    };
+                    if let Some((description, error)) = failure {
+                        Self::save(res.request(), param, description, &error).await;
+                    }
+                    Ok(res.map_into_left_body())
+                }
+                Ok(Err(e)) => {
+                    if e.as_response_error().status_code().is_server_error() {
+                        Self::save(&http_req, param, "服务器错误", &e.to_string()).await;
+                    }
+                    Err(e)
+                }
+                Err(panic) => {
+                    let error = format!("panic: {}", Self::panic_message(&panic));
+                    log::error!("请求 {} 处理时发生{}", http_req.path(), error);
+                    Self::save(&http_req, param, "程序崩溃", &error).await;
+                    let response = HttpResponse::InternalServerError().json(
+                        ApiResponse::<String>::error_with_code(
+                            WebErrorCode::INTERNAL_ERROR,
+                            "服务器内部错误".to_string(),
+                        ),
+                    );
+                    Ok(ServiceResponse::new(http_req, response).map_into_right_body())
+                }
+            }
+        })
+    }
+}
+
+impl<S> ExceptionLogMiddleware<S> {
+    async fn save(req: &HttpRequest, param: Option<String>, description: &str, error: &str) {
+        match req.app_data::<Data<AppState>>() {
+            Some(app) => {
+                ExceptionLogService::save_exception_log(
+                    req,
+                    param,
+                    description,
+                    error,
+                    app.get_mysql_pool(),
+                )
+                .await
+            }
+            None => log::error!("保存异常日志失败: AppState is None"),
+        }
+    }
+
+    fn panic_message(panic: &Box<dyn Any + Send>) -> String {
+        if let Some(message) = panic.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = panic.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "unknown".to_string()
+        }
+    }
+}
+
+/**
+ * 捕获 Future 执行过程中的 panic
+ */
+struct CatchUnwind<F>(Pin<Box<F>>);
+
+impl<F: Future> Future for CatchUnwind<F> {
+    type Output = Result<F::Output, Box<dyn Any + Send>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let fut = self.0.as_mut();
+        match panic::catch_unwind(AssertUnwindSafe(|| fut.poll(cx))) {
+            Ok(Poll::Pending) => Poll::Pending,
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Err(panic) => Poll::Ready(Err(panic)),
+        }
+    }
+}
@@ -19,6 +19,9 @@ pub struct AppClaims {
     pub account_id: i32,
     #[serde(rename = "nbf")]
     pub not_before: u64,
+    //角色，旧token没有角色时不授予任何后台权限
+    #[serde(rename = "rol", default)]
+    pub role: String,
 }
 
 impl actix_jwt_session::Claims for AppClaims {
@@ -5,7 +5,16 @@
  * @LastEditTime: 2024-05-16 12:06:55
  *
  */
+mod exception_log;
 mod jwt;
+mod operation_log;
+mod permission;
 mod visit_log;
+pub use exception_log::ExceptionLogger;
 pub use jwt::AppClaims;
+pub use operation_log::OperationLogger;
+pub use permission::{
+    Authorized, BlogRead, BlogWrite, CommentRead, CommentWrite, ContentRead, ContentWrite,
+    Permission, SystemRead, SystemWrite,
+};
 pub use visit_log::VisiLog;
@@ -0,0 +1,111 @@
+use crate::app::AppState;
+use crate::middleware::AppClaims;
+use crate::middleware::permission::ApiTokenSubject;
+use crate::service::{ApiTokenService, OperationLogService};
+use actix_jwt_session::MaybeAuthenticated;
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::Method,
+    web::Data,
+    Error, HttpMessage,
+};
+use chrono::Local;
+use std::{
+    future::{ready, Future, Ready},
+    pin::Pin,
+    rc::Rc,
+};
+
+/**
+ * 记录已登录用户在后台的写操作(非GET请求)
+ */
+#[derive(Default, Debug)]
+pub struct OperationLogger;
+
+impl<S, B> Transform<S, ServiceRequest> for OperationLogger
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = OperationLogMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(OperationLogMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct OperationLogMiddleware<S> {
+    /// The next service to call
+    service: Rc<S>,
+}
+
+type LocalBoxFuture<T> = Pin<Box<dyn Future<Output = T> + 'static>>;
+
+impl<S, B> Service<ServiceRequest> for OperationLogMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        Box::pin(async move {
+            if matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+                return service.call(req).await;
+            }
+            //未登录的请求(如登录接口)不记录，API token的操作人在接口鉴权后才能确定
+            let subject = req
+                .extract::<MaybeAuthenticated<AppClaims>>()
+                .await
+                .ok()
+                .and_then(|auth| auth.into_option())
+                .map(|auth| auth.subject.clone());
+            if subject.is_none() && ApiTokenService::from_request(req.request()).is_none() {
+                return service.call(req).await;
+            }
+            let param = OperationLogService::read_param(&mut req).await;
+            let start_time = Local::now().naive_local();
+            let res = service.call(req).await?;
+            let times = Local::now()
+                .naive_local()
+                .signed_duration_since(start_time)
+                .num_milliseconds() as i32;
+            let subject = subject.or_else(|| {
+                res.request()
+                    .extensions()
+                    .get::<ApiTokenSubject>()
+                    .map(|subject| subject.0.clone())
+            });
+            let Some(subject) = subject else {
+                return Ok(res);
+            };
+            match res.request().app_data::<Data<AppState>>() {
+                Some(app) => {
+                    OperationLogService::save_operation_log(
+                        &subject,
+                        res.request(),
+                        param,
+                        times,
+                        app.get_mysql_pool(),
+                    )
+                    .await
+                }
+                None => log::error!("保存操作日志失败: AppState is None"),
+            }
+            Ok(res)
+        })
+    }
+}
@@ -0,0 +1,261 @@
+use crate::app::AppState;
+use crate::constant::RoleConstant;
+use crate::error::WebErrorCode;
+use crate::middleware::AppClaims;
+use crate::model::ApiResponse;
+use crate::service::ApiTokenService;
+use actix_jwt_session::{Authenticated, Uuid};
+use actix_web::{
+    dev::Payload,
+    error::{ErrorInternalServerError, InternalError},
+    web::Data,
+    FromRequest, HttpMessage, HttpRequest,
+};
+use std::{future::Future, marker::PhantomData, ops::Deref, pin::Pin};
+
+/**
+ * 后台权限，各角色拥有的权限见 Permission::granted
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    //文章(含历史版本、媒体库)
+    BlogRead,
+    BlogWrite,
+    //评论
+    CommentRead,
+    CommentWrite,
+    //动态、友链、分类、标签、站点设置
+    ContentRead,
+    ContentWrite,
+    //定时任务、日志、访客、账号管理
+    SystemRead,
+    SystemWrite,
+}
+
+impl Permission {
+    /// API token可以申请的权限范围
+    pub const SCOPES: [Permission; 8] = [
+        Permission::BlogRead,
+        Permission::BlogWrite,
+        Permission::CommentRead,
+        Permission::CommentWrite,
+        Permission::ContentRead,
+        Permission::ContentWrite,
+        Permission::SystemRead,
+        Permission::SystemWrite,
+    ];
+
+    /**
+     * 角色是否拥有该权限
+     */
+    pub fn granted(self, role: &str) -> bool {
+        use Permission::*;
+        match role {
+            RoleConstant::ADMIN => true,
+            RoleConstant::EDITOR => matches!(self, BlogRead | BlogWrite | ContentRead),
+            RoleConstant::MODERATOR => matches!(self, CommentRead | CommentWrite),
+            RoleConstant::VIEWER => {
+                matches!(self, BlogRead | CommentRead | ContentRead | SystemRead)
+            }
+            _ => false,
+        }
+    }
+
+    /**
+     * API token中对应的权限范围
+     */
+    pub fn scope(self) -> &'static str {
+        match self {
+            Permission::BlogRead => "blog:read",
+            Permission::BlogWrite => "blog:write",
+            Permission::CommentRead => "comment:read",
+            Permission::CommentWrite => "comment:moderate",
+            Permission::ContentRead => "content:read",
+            Permission::ContentWrite => "content:write",
+            Permission::SystemRead => "system:read",
+            Permission::SystemWrite => "system:write",
+        }
+    }
+
+    pub fn from_scope(scope: &str) -> Option<Permission> {
+        Self::SCOPES
+            .into_iter()
+            .find(|permission| permission.scope() == scope)
+    }
+}
+
+/**
+ * 路由所需的权限，由 Authorized<P> 的类型参数指定
+ */
+pub trait RequiredPermission {
+    const PERMISSION: Permission;
+}
+
+macro_rules! required_permissions {
+    ($($name:ident),* $(,)?) => {
+        $(
+            pub struct $name;
+
+            impl RequiredPermission for $name {
+                const PERMISSION: Permission = Permission::$name;
+            }
+        )*
+    };
+}
+
+required_permissions!(
+    BlogRead,
+    BlogWrite,
+    CommentRead,
+    CommentWrite,
+    ContentRead,
+    ContentWrite,
+    SystemRead,
+    SystemWrite,
+);
+
+/**
+ * 使用API token的请求在鉴权后写入请求扩展，供操作日志记录操作人
+ */
+pub(crate) struct ApiTokenSubject(pub String);
+
+/**
+ * 已登录(JWT或API token)并且角色拥有权限 P，用法：`auth: Authorized<BlogWrite>`
+ * 使用API token时，token的权限范围也必须包含 P
+ */
+pub struct Authorized<P> {
+    claims: AppClaims,
+    api_token: bool,
+    _permission: PhantomData<P>,
+}
+
+impl<P> Authorized<P> {
+    /**
+     * 只能管理自己文章的角色(编辑)返回用户ID，其它角色返回None
+     */
+    pub fn blog_owner(&self) -> Option<i64> {
+        (self.role == RoleConstant::EDITOR).then_some(self.account_id as i64)
+    }
+
+    /**
+     * 是否通过API token访问
+     */
+    pub fn is_api_token(&self) -> bool {
+        self.api_token
+    }
+}
+
+impl<P> Deref for Authorized<P> {
+    type Target = AppClaims;
+
+    fn deref(&self) -> &AppClaims {
+        &self.claims
+    }
+}
+
+impl<P: RequiredPermission + 'static> Authorized<P> {
+    fn authorize(claims: AppClaims, api_token: bool) -> Result<Self, actix_web::Error> {
+        if !P::PERMISSION.granted(&claims.role) {
+            log::warn!(
+                "用户:{}(角色:{})没有{:?}权限",
+                claims.subject,
+                claims.role,
+                P::PERMISSION
+            );
+            return Err(reject(WebErrorCode::FORBIDDEN, "权限不足".to_string()));
+        }
+        Ok(Self {
+            claims,
+            api_token,
+            _permission: PhantomData,
+        })
+    }
+
+    async fn from_api_token(req: HttpRequest, token: String) -> Result<Self, actix_web::Error> {
+        let app = req
+            .app_data::<Data<AppState>>()
+            .ok_or_else(|| ErrorInternalServerError("AppState is None"))?;
+        let Some((user, api_token)) =
+            ApiTokenService::authenticate(&token, app.get_mysql_pool()).await?
+        else {
+            return Err(reject(
+                WebErrorCode::UNAUTHORIZED,
+                "API token无效或已过期".to_string(),
+            ));
+        };
+        if !user.status {
+            return Err(reject(WebErrorCode::FORBIDDEN, "账号已禁用".to_string()));
+        }
+        if !api_token
+            .scopes
+            .split(',')
+            .any(|scope| scope == P::PERMISSION.scope())
+        {
+            return Err(reject(
+                WebErrorCode::FORBIDDEN,
+                format!("API token没有{}权限", P::PERMISSION.scope()),
+            ));
+        }
+        req.extensions_mut().insert(ApiTokenSubject(format!(
+            "{}(API token:{})",
+            user.username, api_token.name
+        )));
+        let claims = AppClaims {
+            expiration_time: 0,
+            issues_at: 0,
+            subject: user.username,
+            jwt_id: Uuid::nil(),
+            account_id: user.id as i32,
+            not_before: 0,
+            role: user.role,
+        };
+        Self::authorize(claims, true)
+    }
+}
+
+impl<P: RequiredPermission + 'static> FromRequest for Authorized<P> {
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        if let Some(token) = ApiTokenService::from_request(req) {
+            return Box::pin(Self::from_api_token(req.clone(), token));
+        }
+        let auth = Authenticated::<AppClaims>::from_request(req, payload);
+        Box::pin(async move {
+            let auth = auth.await?;
+            Self::authorize(AppClaims::clone(&auth), false)
+        })
+    }
+}
+
+/**
+ * 鉴权失败，响应体与其它接口一致
+ */
+fn reject(code: u16, msg: String) -> actix_web::Error {
+    let response = ApiResponse::<String>::error_with_code(code, msg.clone()).json();
+    InternalError::from_response(msg, response).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Permission;
+    use crate::constant::RoleConstant;
+
+    #[test]
+    fn test_permission_granted() {
+        assert!(Permission::SystemWrite.granted(RoleConstant::ADMIN));
+        assert!(Permission::BlogWrite.granted(RoleConstant::EDITOR));
+        assert!(!Permission::CommentWrite.granted(RoleConstant::EDITOR));
+        assert!(Permission::CommentWrite.granted(RoleConstant::MODERATOR));
+        assert!(!Permission::BlogRead.granted(RoleConstant::MODERATOR));
+        assert!(Permission::SystemRead.granted(RoleConstant::VIEWER));
+        assert!(!Permission::ContentWrite.granted(RoleConstant::VIEWER));
+        assert!(!Permission::BlogRead.granted(""));
+        assert_eq!(
+            Permission::from_scope("comment:moderate"),
+            Some(Permission::CommentWrite)
+        );
+        assert_eq!(Permission::from_scope("blog:delete"), None);
+    }
+}
@@ -0,0 +1,40 @@
+use crate::entity::api_token;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/**
+ * API token，不包含token本身与哈希
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: i64,
+    pub user_id: i64,
+    pub username: Option<String>,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+    pub expire_time: Option<NaiveDateTime>,
+    pub last_used_time: Option<NaiveDateTime>,
+    pub create_time: NaiveDateTime,
+}
+
+impl From<api_token::Model> for ApiToken {
+    fn from(item: api_token::Model) -> Self {
+        ApiToken {
+            id: item.id,
+            user_id: item.user_id,
+            username: None,
+            name: item.name,
+            token_prefix: item.token_prefix,
+            scopes: item
+                .scopes
+                .split(',')
+                .filter(|scope| !scope.is_empty())
+                .map(|scope| scope.to_string())
+                .collect(),
+            expire_time: item.expire_time,
+            last_used_time: item.last_used_time,
+            create_time: item.create_time,
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::common::DiffLine;
+
+//文章历史版本，不含正文
+#[derive(Debug, Clone, Serialize)]
+pub struct BlogRevision {
+    //为None时表示文章当前版本
+    pub(crate) id: Option<i64>,
+    #[serde(rename(serialize = "blogId"))]
+    pub(crate) blog_id: i64,
+    pub(crate) title: String,
+    pub(crate) description: String,
+    #[serde(rename(serialize = "categoryId"))]
+    pub(crate) category_id: i64,
+    #[serde(rename(serialize = "tagIds"))]
+    pub(crate) tag_ids: Vec<i64>,
+    #[serde(rename(serialize = "createTime"))]
+    pub(crate) create_time: NaiveDateTime,
+}
+
+//两个版本之间的逐行差异
+#[derive(Debug, Clone, Serialize)]
+pub struct BlogRevisionDiff {
+    pub(crate) from: BlogRevision,
+    pub(crate) to: BlogRevision,
+    pub(crate) title: Vec<DiffLine>,
+    pub(crate) description: Vec<DiffLine>,
+    pub(crate) content: Vec<DiffLine>,
+}
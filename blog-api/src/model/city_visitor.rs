@@ -0,0 +1,18 @@
+use crate::entity::city_visitor;
+use serde::{Deserialize, Serialize};
+
+//城市访客数量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CityVisitor {
+    pub city: String,
+    pub uv: i32,
+}
+
+impl From<city_visitor::Model> for CityVisitor {
+    fn from(model: city_visitor::Model) -> Self {
+        Self {
+            city: model.city,
+            uv: model.uv,
+        }
+    }
+}
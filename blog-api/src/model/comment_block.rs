@@ -0,0 +1,29 @@
+use crate::entity::comment_block;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/**
+ * 评论黑名单，类型为 ip 或 email
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentBlock {
+    pub id: i64,
+    #[serde(rename = "type")]
+    pub block_type: String,
+    pub value: String,
+    pub reason: Option<String>,
+    #[serde(rename = "createTime")]
+    pub create_time: NaiveDateTime,
+}
+
+impl From<comment_block::Model> for CommentBlock {
+    fn from(item: comment_block::Model) -> Self {
+        CommentBlock {
+            id: item.id,
+            block_type: item.block_type,
+            value: item.value,
+            reason: item.reason,
+            create_time: item.create_time,
+        }
+    }
+}
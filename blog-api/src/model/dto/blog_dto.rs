@@ -9,6 +9,7 @@ use crate::{
 
 use super::tag_dto::TagDTO;
 use crate::entity::blog;
+use crate::entity::sea_orm_active_enums::BlogStatus;
 
 //Blog
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -40,6 +41,9 @@ pub struct BlogDTO {
     top: bool,
     password: Option<String>,
     user_id: Option<i64>,
+    status: BlogStatus,
+    #[serde(rename(serialize = "publishAt"))]
+    publish_at: Option<NaiveDateTime>,
     #[serde(rename(deserialize = "category_id"), skip_serializing)]
     //跳过该字段，不进行序列化操作。
     //category_id: i64,
@@ -68,6 +72,8 @@ impl From<blog::Model> for BlogDTO {
             top: model.is_top,
             password: model.password,
             user_id: model.user_id,
+            status: model.status,
+            publish_at: model.publish_at,
             category: None,
             //    category_id: model.category_id,
             tags: None,
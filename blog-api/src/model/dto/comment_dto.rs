@@ -31,7 +31,7 @@ impl From<comment::Model> for CommentDTO {
             id: model.id,
             nickname: model.nickname,
             avatar: model.avatar,
-            published: Some(model.is_admin_comment),
+            published: Some(model.is_published),
             email: Some(model.email),
             ip: Some(model.ip.unwrap_or_default()),
             create_time: model.create_time.unwrap_or(Local::now().naive_local()),
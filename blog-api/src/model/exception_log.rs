@@ -0,0 +1,51 @@
+use crate::entity::exception_log;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExceptionLog {
+    pub id: i64,
+    pub uri: String,
+    pub method: String,
+    pub param: Option<String>,
+    pub description: Option<String>,
+    pub error: Option<String>,
+    pub ip: Option<String>,
+    pub ip_source: Option<String>,
+    pub os: Option<String>,
+    pub browser: Option<String>,
+    pub create_time: NaiveDateTime,
+    pub user_agent: Option<String>,
+    pub fingerprint: String,
+}
+
+impl From<exception_log::Model> for ExceptionLog {
+    fn from(item: exception_log::Model) -> Self {
+        ExceptionLog {
+            id: item.id,
+            uri: item.uri,
+            method: item.method,
+            param: item.param,
+            description: item.description,
+            error: item.error,
+            ip: item.ip,
+            ip_source: item.ip_source,
+            os: item.os,
+            browser: item.browser,
+            create_time: item.create_time,
+            user_agent: item.user_agent,
+            fingerprint: item.fingerprint,
+        }
+    }
+}
+
+/**
+ * 按异常指纹分组的异常日志，latest为最近一次发生的记录
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExceptionLogGroup {
+    pub fingerprint: String,
+    pub count: i64,
+    pub first_time: NaiveDateTime,
+    pub last_time: NaiveDateTime,
+    pub latest: Option<ExceptionLog>,
+}
@@ -0,0 +1,33 @@
+use crate::entity::login_log;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginLog {
+    pub id: i64,
+    pub username: String,
+    pub ip: Option<String>,
+    pub ip_source: Option<String>,
+    pub os: Option<String>,
+    pub browser: Option<String>,
+    pub status: Option<bool>,
+    pub description: Option<String>,
+    pub create_time: NaiveDateTime,
+    pub user_agent: Option<String>,
+}
+
+impl From<login_log::Model> for LoginLog {
+    fn from(item: login_log::Model) -> Self {
+        LoginLog {
+            id: item.id,
+            username: item.username,
+            ip: item.ip,
+            ip_source: item.ip_source,
+            os: item.os,
+            browser: item.browser,
+            status: item.status,
+            description: item.description,
+            create_time: item.create_time,
+            user_agent: item.user_agent,
+        }
+    }
+}
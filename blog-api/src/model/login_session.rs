@@ -0,0 +1,22 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/**
+ * 登录会话，保存在Redis中，与 actix-jwt-session 的 jwt_id 对应
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginSession {
+    pub jwt_id: String,
+    pub username: String,
+    pub ip: String,
+    pub ip_source: String,
+    pub os: String,
+    pub browser: String,
+    pub user_agent: String,
+    pub issue_time: NaiveDateTime,
+    pub refresh_time: Option<NaiveDateTime>,
+    pub expire_time: NaiveDateTime,
+    //是否为当前请求所用的会话，不保存
+    #[serde(default, skip_deserializing)]
+    pub current: bool,
+}
@@ -0,0 +1,22 @@
+use serde::Serialize;
+
+/**
+ * Markdown导入结果
+ */
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct MarkdownImportReport {
+    //新增的文章数
+    pub created: u64,
+    //按slug更新的文章数
+    pub updated: u64,
+    pub failures: Vec<MarkdownImportFailure>,
+}
+
+/**
+ * 导入失败的文件及原因
+ */
+#[derive(Debug, Clone, Serialize)]
+pub struct MarkdownImportFailure {
+    pub file: String,
+    pub reason: String,
+}
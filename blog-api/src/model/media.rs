@@ -0,0 +1,42 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/**
+ * 媒体文件，url 按当前存储方式生成，非图片文件没有缩略图与尺寸
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Media {
+    pub id: i64,
+    #[serde(rename = "fileName")]
+    pub file_name: String,
+    pub url: String,
+    #[serde(rename = "thumbnailUrl")]
+    pub thumbnail_url: Option<String>,
+    #[serde(rename = "webpUrl")]
+    pub webp_url: Option<String>,
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+    pub size: i64,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub hash: String,
+    #[serde(rename = "userId")]
+    pub user_id: i64,
+    //上传者昵称
+    pub uploader: Option<String>,
+    #[serde(rename = "createTime")]
+    pub create_time: NaiveDateTime,
+    //引用该文件的文章、动态、友链等
+    pub references: Vec<MediaReference>,
+}
+
+/**
+ * 文件的引用位置，type 为 blog / moment / friend / user / setting
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MediaReference {
+    #[serde(rename = "type")]
+    pub reference_type: String,
+    pub id: i64,
+    pub title: String,
+}
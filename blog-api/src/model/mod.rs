@@ -5,13 +5,19 @@
  * @LastEditTime: 2024-05-06 23:24:33
  */
 mod about;
+mod api_token;
+mod blog_revision;
 mod category;
 mod city_visitor;
 mod comment;
+mod comment_block;
 mod dto;
 mod exception_log;
 mod friend;
 mod login_log;
+mod login_session;
+mod markdown_import;
+mod media;
 mod moment;
 mod operation_log;
 mod response_result;
@@ -25,12 +31,18 @@ mod visitor;
 mod vo;
 
 //pub use about::About;
+pub use api_token::ApiToken;
+pub use blog_revision::{BlogRevision, BlogRevisionDiff};
 pub use category::Category;
-//pub use city_visitor::CityVisitor;
-//pub use exception_log::ExceptionLog;
-//pub use login_log::LoginLog;
+pub use city_visitor::CityVisitor;
+pub use comment_block::CommentBlock;
+pub use exception_log::{ExceptionLog, ExceptionLogGroup};
+pub use login_log::LoginLog;
+pub use login_session::LoginSession;
+pub use markdown_import::{MarkdownImportFailure, MarkdownImportReport};
+pub use media::{Media, MediaReference};
 pub use moment::Moment;
-//pub use operation_log::OperationLog;
+pub use operation_log::OperationLog;
 pub use dto::*;
 pub use friend::Friend;
 pub use response_result::ApiResponse;
@@ -39,5 +51,6 @@ pub use schedule_job_log::ScheduleJobLog;
 pub use site_setting::SiteSetting;
 pub use user::User;
 pub use visit_log::VisitLog;
+pub use visit_record::VisitRecord;
 pub use visitor::Visitor;
 pub use vo::*;
@@ -0,0 +1,39 @@
+use crate::entity::operation_log;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationLog {
+    pub id: i64,
+    pub username: String,
+    pub uri: String,
+    pub method: String,
+    pub param: Option<String>,
+    pub description: Option<String>,
+    pub ip: Option<String>,
+    pub ip_source: Option<String>,
+    pub os: Option<String>,
+    pub browser: Option<String>,
+    pub times: i32,
+    pub create_time: NaiveDateTime,
+    pub user_agent: Option<String>,
+}
+
+impl From<operation_log::Model> for OperationLog {
+    fn from(item: operation_log::Model) -> Self {
+        OperationLog {
+            id: item.id,
+            username: item.username,
+            uri: item.uri,
+            method: item.method,
+            param: item.param,
+            description: item.description,
+            ip: item.ip,
+            ip_source: item.ip_source,
+            os: item.os,
+            browser: item.browser,
+            times: item.times,
+            create_time: item.create_time,
+            user_agent: item.user_agent,
+        }
+    }
+}
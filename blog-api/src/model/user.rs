@@ -18,6 +18,8 @@ pub struct User {
     create_time: NaiveDateTime, //创建时间
     update_time: NaiveDateTime, //更新时间
     role: String,               //角色访问权限
+    totp_enabled: bool,         //是否已启用两步验证
+    status: bool,               //账号状态，false为禁用
 }
 
 impl User {
@@ -40,6 +42,14 @@ impl User {
     pub fn get_role(&self) -> String {
         self.role.clone()
     }
+
+    pub fn is_totp_enabled(&self) -> bool {
+        self.totp_enabled
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.status
+    }
 }
 
 impl From<user::Model> for User {
@@ -54,6 +64,8 @@ impl From<user::Model> for User {
             create_time: model.create_time,
             update_time: model.update_time,
             role: model.role,
+            totp_enabled: model.totp_enabled,
+            status: model.status,
         }
     }
 }
@@ -0,0 +1,22 @@
+use crate::entity::visit_record;
+use serde::{Deserialize, Serialize};
+
+//访问记录(每日PV、UV)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VisitRecord {
+    pub id: i64,
+    pub pv: i32,
+    pub uv: i32,
+    pub date: String,
+}
+
+impl From<visit_record::Model> for VisitRecord {
+    fn from(model: visit_record::Model) -> Self {
+        Self {
+            id: model.id,
+            pv: model.pv,
+            uv: model.uv,
+            date: model.date,
+        }
+    }
+}
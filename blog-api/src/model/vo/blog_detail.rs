@@ -19,7 +19,8 @@ pub struct BlogDetail {
     #[serde(rename(serialize = "readTime"))]
     pub(crate) read_time: i32,
     pub(crate) is_top: bool,
-
+    //密码哈希，仅用于校验解锁token，不返回给前端
+    #[serde(skip)]
     pub(crate) password: Option<String>,
 }
 
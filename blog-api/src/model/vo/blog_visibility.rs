@@ -1,4 +1,8 @@
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
+
+use crate::entity::sea_orm_active_enums::BlogStatus;
+
 //Blog可见性
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct BlogVisibility {
@@ -14,6 +18,9 @@ pub struct BlogVisibility {
     recommend: Option<bool>, // 推荐可见性，默认为false。如果设置为false，则推荐不可见。
     #[serde(rename(serialize = "is_top", deserialize = "top"))]
     top: Option<bool>, // 置顶可见性，默认为false。如果设置为false，则置顶不可见。
+    status: Option<BlogStatus>, // 文章状态，指定时优先于published
+    #[serde(rename(deserialize = "publishAt"))]
+    publish_at: Option<NaiveDateTime>, // 定时发布时间，status为scheduled时必填
 }
 
 
@@ -71,4 +78,10 @@ impl BlogVisibility {
     pub fn get_top(&self) -> Option<bool> {
         self.top
     }
+    pub fn get_status(&self) -> Option<BlogStatus> {
+        self.status
+    }
+    pub fn get_publish_at(&self) -> Option<NaiveDateTime> {
+        self.publish_at
+    }
 }
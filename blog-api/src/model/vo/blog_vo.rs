@@ -1,6 +1,7 @@
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 
+use crate::entity::sea_orm_active_enums::BlogStatus;
 use crate::{common::TypeValue, model::category::Category};
 
 //Blog
@@ -33,6 +34,11 @@ pub struct BlogVO {
     pub(crate) top: bool,
     pub(crate) password: Option<String>,
     pub(crate) user_id: Option<i64>,
+    //未指定时按 published 开关处理
+    pub(crate) status: Option<BlogStatus>,
+    //定时发布时间
+    #[serde(rename(deserialize = "publishAt"))]
+    pub(crate) publish_at: Option<NaiveDateTime>,
     #[serde(rename(deserialize = "cate"))]
     //跳过该字段，不进行序列化操作。
     pub(crate) category_id: i64,
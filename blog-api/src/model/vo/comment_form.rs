@@ -0,0 +1,28 @@
+use serde::Deserialize;
+
+//前台提交的评论，只用于新增评论
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct CommentForm {
+    #[serde(default)]
+    pub(crate) nickname: String,
+    #[serde(default)]
+    pub(crate) email: String,
+    #[serde(default)]
+    pub(crate) content: String,
+    pub(crate) website: Option<String>,
+    pub(crate) qq: Option<String>,
+    //接收邮件提醒
+    #[serde(default)]
+    pub(crate) notice: bool,
+    //0普通文章，1关于我页面，2友链页面
+    #[serde(default)]
+    pub(crate) page: i8,
+    #[serde(rename(deserialize = "blogId"))]
+    pub(crate) blog_id: Option<i64>,
+    //为空时为根评论
+    #[serde(rename(deserialize = "parentCommentId"))]
+    pub(crate) parent_comment_id: Option<i64>,
+    //蜜罐字段，前端隐藏该输入框，正常用户不会填写
+    #[serde(default)]
+    pub(crate) homepage: String,
+}
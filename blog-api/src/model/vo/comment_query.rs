@@ -0,0 +1,37 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+/**
+ * 后台评论列表的筛选条件，未传的条件不参与筛选
+ */
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct CommentQuery {
+    #[serde(rename = "pageNum")]
+    pub(crate) page_num: Option<u64>,
+    #[serde(rename = "pageSize")]
+    pub(crate) page_size: Option<u64>,
+    //0普通文章，1关于我页面，2友链页面
+    pub(crate) page: Option<i8>,
+    #[serde(rename = "blogId")]
+    pub(crate) blog_id: Option<i64>,
+    //true已公开，false待审核
+    pub(crate) published: Option<bool>,
+    //昵称或评论内容
+    pub(crate) keyword: Option<String>,
+    pub(crate) email: Option<String>,
+    pub(crate) ip: Option<String>,
+    //评论时间范围(包含首尾两天)，格式 yyyy-MM-dd
+    #[serde(rename = "startDate")]
+    pub(crate) start_date: Option<NaiveDate>,
+    #[serde(rename = "endDate")]
+    pub(crate) end_date: Option<NaiveDate>,
+}
+
+impl CommentQuery {
+    pub fn get_page_num(&self) -> u64 {
+        self.page_num.unwrap_or(1).max(1)
+    }
+    pub fn get_page_size(&self) -> u64 {
+        self.page_size.unwrap_or(10).max(1)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::common::MarkdownParser;
 use crate::entity::comment;
 use chrono::{Local, NaiveDateTime};
 use serde::{Deserialize, Serialize};
@@ -29,7 +30,10 @@ impl From<comment::Model> for CommentVO {
         Self {
             id: model.id,
             nickname: model.nickname,
-            content: model.content,
+            //历史评论没有渲染结果时即时渲染
+            content: model
+                .content_html
+                .unwrap_or_else(|| MarkdownParser::parser_comment_html(&model.content)),
             avatar: model.avatar,
             create_time: model.create_time.unwrap_or(Local::now().naive_local()),
             is_admin_comment: model.is_admin_comment,
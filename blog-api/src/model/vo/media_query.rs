@@ -0,0 +1,29 @@
+use serde::Deserialize;
+
+/**
+ * 后台媒体库的筛选条件，未传的条件不参与筛选
+ */
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct MediaQuery {
+    #[serde(rename = "pageNum")]
+    pub(crate) page_num: Option<u64>,
+    #[serde(rename = "pageSize")]
+    pub(crate) page_size: Option<u64>,
+    //文件名
+    pub(crate) keyword: Option<String>,
+    //文件类型前缀，如 image/
+    #[serde(rename = "mimeType")]
+    pub(crate) mime_type: Option<String>,
+    //上传者
+    #[serde(rename = "userId")]
+    pub(crate) user_id: Option<i64>,
+}
+
+impl MediaQuery {
+    pub fn get_page_num(&self) -> u64 {
+        self.page_num.unwrap_or(1).max(1)
+    }
+    pub fn get_page_size(&self) -> u64 {
+        self.page_size.unwrap_or(20).max(1)
+    }
+}
@@ -5,11 +5,14 @@ mod blog_info;
 mod blog_visibility;
 mod blog_vo;
 mod categorie;
+mod comment_form;
+mod comment_query;
 mod comment_vo;
 mod copyright;
 mod favorite;
 mod friend_info;
 mod introduction;
+mod media_query;
 mod search_request;
 mod search_blog;
 mod serise;
@@ -21,11 +24,14 @@ pub use blog_info::BlogInfo;
 pub use blog_visibility::BlogVisibility;
 pub use blog_vo::BlogVO;
 pub use categorie::Categorie;
+pub use comment_form::CommentForm;
+pub use comment_query::CommentQuery;
 pub use comment_vo::CommentVO;
 pub use copyright::Copyright;
 pub use favorite::Favorite;
 pub use friend_info::FriendInfo;
 pub use introduction::Introduction;
+pub use media_query::MediaQuery;
 pub use search_request::SearchRequest;
 pub use search_blog::SearchBlog;
 pub use serise::Serise;
@@ -1,4 +1,3 @@
-use crate::entity::blog;
 use serde::{Deserialize, Serialize};
 /**
  * 文章搜索
@@ -11,17 +10,10 @@ pub struct SearchBlog {
     content: String,
 }
 impl SearchBlog {
-    pub fn set_content(&mut self, content: String) {
-        self.content = content;
-    }
-}
-
-impl From<blog::Model> for SearchBlog {
-    fn from(b: blog::Model) -> Self {
-        Self {
-            id: b.id,
-            title: b.title,
-            content: b.content,
-        }
+    /**
+     * title 与 content 为已转义并带有高亮标记的HTML片段
+     */
+    pub fn new(id: i64, title: String, content: String) -> Self {
+        Self { id, title, content }
     }
 }
@@ -23,6 +23,8 @@ pub struct SearchRequest {
     title: Option<String>,
     #[serde(rename = "categoryId", default)]
     category_id: Option<String>,
+    #[serde(rename = "sort")]
+    sort: Option<String>,
 }
 
 impl Default for SearchRequest {
@@ -35,6 +37,7 @@ impl Default for SearchRequest {
             password: None,
             title: None,
             category_id: None,
+            sort: None,
         }
     }
 }
@@ -55,6 +58,9 @@ impl SearchRequest {
     pub fn get_password(&self) -> String {
         self.password.clone().unwrap_or_default()
     }
+    pub fn get_sort(&self) -> Option<String> {
+        self.sort.clone()
+    }
     pub fn get_title(&self) -> Option<String> {
         self.title.clone()
     }
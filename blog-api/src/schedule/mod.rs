@@ -0,0 +1,5 @@
+mod scheduler;
+mod task_registry;
+
+pub use scheduler::Scheduler;
+pub use task_registry::TaskRegistry;
@@ -0,0 +1,220 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::{LazyLock, RwLock};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+use cron::Schedule;
+use sea_orm::DatabaseConnection;
+
+use super::TaskRegistry;
+use crate::entity::{schedule_job, schedule_job_log};
+use crate::error::DataBaseError;
+use crate::service::ScheduleJobService;
+
+/**
+ * 已加载到调度器中的任务
+ */
+struct ScheduledJob {
+    job: schedule_job::Model,
+    schedule: Schedule,
+    next_time: Option<DateTime<Local>>,
+}
+
+/// 调度中的任务(仅包含启用状态的任务)
+static JOBS: LazyLock<RwLock<HashMap<i64, ScheduledJob>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// 正在执行的任务，避免同一任务重叠执行
+static RUNNING: LazyLock<RwLock<HashSet<i64>>> = LazyLock::new(|| RwLock::new(HashSet::new()));
+
+/// 调度轮询间隔
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct Scheduler;
+
+impl Scheduler {
+    /**
+     * 启动调度器：加载启用的任务并开始轮询
+     */
+    pub async fn start(db: DatabaseConnection) {
+        match ScheduleJobService::find_enabled_jobs(&db).await {
+            Ok(jobs) => {
+                for job in jobs {
+                    Self::load_job(job);
+                }
+                log::info!("定时任务调度器启动, 已加载{}个任务", Self::job_count());
+            }
+            Err(e) => log::error!("加载定时任务失败:{}", e),
+        }
+        actix_web::rt::spawn(async move {
+            loop {
+                actix_web::rt::time::sleep(TICK_INTERVAL).await;
+                Self::tick(&db);
+            }
+        });
+    }
+
+    /**
+     * 校验 Quartz 风格的 cron 表达式，如 `0 0 1 * * ?`
+     */
+    pub fn parse_cron(cron: &str) -> Result<Schedule, DataBaseError> {
+        Schedule::from_str(cron.trim())
+            .map_err(|e| DataBaseError::Validation(format!("cron表达式[{}]错误:{}", cron, e)))
+    }
+
+    /**
+     * 任务新增、修改或状态变更后重新加载，无需重启服务
+     */
+    pub async fn refresh_job(job_id: i64, db: &DatabaseConnection) -> Result<(), DataBaseError> {
+        Self::remove_job(job_id);
+        if let Some(job) = ScheduleJobService::find_by_id(job_id, db).await? {
+            if job.status.unwrap_or(false) {
+                Self::load_job(job);
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * 从调度器中移除任务
+     */
+    pub fn remove_job(job_id: i64) {
+        if let Ok(mut jobs) = JOBS.write() {
+            if jobs.remove(&job_id).is_some() {
+                log::info!("定时任务[{}]已从调度器移除", job_id);
+            }
+        }
+    }
+
+    /**
+     * 立即执行一次任务并记录执行日志
+     */
+    pub async fn run_job(
+        job: schedule_job::Model,
+        db: &DatabaseConnection,
+    ) -> Result<schedule_job_log::Model, DataBaseError> {
+        let bean_name = job.bean_name.clone().unwrap_or_default();
+        let method_name = job.method_name.clone().unwrap_or_default();
+        let start = Instant::now();
+        let result = match TaskRegistry::get(&bean_name, &method_name) {
+            Some(task) => task(job.params.clone(), db.clone()).await,
+            None => Err(DataBaseError::Custom(format!(
+                "未注册的定时任务:{}.{}",
+                bean_name, method_name
+            ))),
+        };
+        let times = start.elapsed().as_millis() as i32;
+        let error = match result {
+            Ok(_) => {
+                log::info!(
+                    "定时任务[{}] {}.{} 执行成功, 耗时{}ms",
+                    job.job_id,
+                    bean_name,
+                    method_name,
+                    times
+                );
+                None
+            }
+            Err(e) => {
+                log::error!(
+                    "定时任务[{}] {}.{} 执行失败:{}",
+                    job.job_id,
+                    bean_name,
+                    method_name,
+                    e
+                );
+                Some(e.to_string())
+            }
+        };
+        ScheduleJobService::save_job_log(&job, error, times, db).await
+    }
+
+    fn load_job(job: schedule_job::Model) {
+        let cron = job.cron.clone().unwrap_or_default();
+        let schedule = match Self::parse_cron(&cron) {
+            Ok(schedule) => schedule,
+            Err(e) => {
+                log::error!("定时任务[{}]加载失败:{}", job.job_id, e);
+                return;
+            }
+        };
+        let next_time = schedule.after(&Local::now()).next();
+        if let Ok(mut jobs) = JOBS.write() {
+            log::info!(
+                "定时任务[{}]已加载, cron:{}, 下次执行时间:{:?}",
+                job.job_id,
+                cron,
+                next_time
+            );
+            jobs.insert(
+                job.job_id,
+                ScheduledJob {
+                    job,
+                    schedule,
+                    next_time,
+                },
+            );
+        }
+    }
+
+    fn job_count() -> usize {
+        JOBS.read().map(|jobs| jobs.len()).unwrap_or(0)
+    }
+
+    /**
+     * 找出到期的任务并派发执行
+     */
+    fn tick(db: &DatabaseConnection) {
+        let now = Local::now();
+        let mut due_jobs = Vec::new();
+        if let Ok(mut jobs) = JOBS.write() {
+            for scheduled in jobs.values_mut() {
+                if let Some(next_time) = scheduled.next_time {
+                    if next_time <= now {
+                        due_jobs.push(scheduled.job.clone());
+                        scheduled.next_time = scheduled.schedule.after(&now).next();
+                    }
+                }
+            }
+        }
+        for job in due_jobs {
+            let job_id = job.job_id;
+            match RUNNING.write() {
+                Ok(mut running) => {
+                    if !running.insert(job_id) {
+                        log::warn!("定时任务[{}]上次执行尚未结束, 本次跳过", job_id);
+                        continue;
+                    }
+                }
+                Err(_) => continue,
+            }
+            let db = db.clone();
+            actix_web::rt::spawn(async move {
+                if let Err(e) = Self::run_job(job, &db).await {
+                    log::error!("定时任务[{}]保存执行日志失败:{}", job_id, e);
+                }
+                if let Ok(mut running) = RUNNING.write() {
+                    running.remove(&job_id);
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Scheduler;
+    use chrono::{Local, TimeZone, Timelike};
+
+    #[test]
+    fn test_parse_quartz_cron() {
+        let schedule = Scheduler::parse_cron("0 0 1 * * ?").unwrap();
+        let now = Local.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let next = schedule.after(&now).next().unwrap();
+        assert_eq!(next.hour(), 1);
+        assert_eq!(next.minute(), 0);
+        assert!(Scheduler::parse_cron("0 0 0 * * ?").is_ok());
+        assert!(Scheduler::parse_cron("not a cron").is_err());
+    }
+}
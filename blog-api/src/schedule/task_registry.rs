@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::LazyLock;
+
+use chrono::{Duration, Local, NaiveDate};
+use sea_orm::DatabaseConnection;
+
+use crate::error::DataBaseError;
+use crate::service::{BlogService, ScheduleJobService, VisitService};
+
+/// 任务执行结果
+pub type TaskFuture = Pin<Box<dyn Future<Output = Result<(), DataBaseError>> + Send>>;
+
+/// 任务函数，参数为 schedule_job.params
+pub type TaskFn = fn(Option<String>, DatabaseConnection) -> TaskFuture;
+
+/**
+ * 任务注册表：bean_name.method_name -> 任务函数
+ */
+static TASKS: LazyLock<HashMap<&'static str, TaskFn>> = LazyLock::new(|| {
+    let mut tasks: HashMap<&'static str, TaskFn> = HashMap::new();
+    tasks.insert(
+        "redisSyncScheduleTask.syncBlogViewsToDatabase",
+        sync_blog_views,
+    );
+    tasks.insert(
+        "visitorSyncScheduleTask.syncVisitInfoToDatabase",
+        sync_visit_info,
+    );
+    tasks.insert("scheduleJobLogTask.cleanJobLog", clean_job_log);
+    tasks.insert(
+        "blogPublishScheduleTask.publishScheduledBlogs",
+        publish_scheduled_blogs,
+    );
+    tasks
+});
+
+pub struct TaskRegistry;
+
+impl TaskRegistry {
+    /**
+     * 根据 bean_name 与 method_name 查找任务函数
+     */
+    pub fn get(bean_name: &str, method_name: &str) -> Option<TaskFn> {
+        TASKS
+            .get(format!("{}.{}", bean_name, method_name).as_str())
+            .copied()
+    }
+}
+
+/**
+ * 从Redis将博客浏览量同步到数据库
+ */
+fn sync_blog_views(_: Option<String>, db: DatabaseConnection) -> TaskFuture {
+    Box::pin(async move { BlogService::sync_blog_views(&db).await })
+}
+
+/**
+ * 统计访问数据，params 为统计日期(yyyy-MM-dd)，默认统计前一天(任务在零点执行)
+ */
+fn sync_visit_info(params: Option<String>, db: DatabaseConnection) -> TaskFuture {
+    Box::pin(async move {
+        let date = params
+            .and_then(|date| NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok())
+            .unwrap_or_else(|| Local::now().date_naive() - Duration::days(1));
+        VisitService::sync_visit_info(date, &db).await
+    })
+}
+
+/**
+ * 清理任务执行日志，params 为保留天数(默认30天)
+ */
+fn clean_job_log(params: Option<String>, db: DatabaseConnection) -> TaskFuture {
+    Box::pin(async move {
+        let days = params
+            .and_then(|days| days.trim().parse::<i64>().ok())
+            .unwrap_or(30);
+        let rows = ScheduleJobService::delete_job_logs_before(days, &db).await?;
+        log::info!("清理{}天前的任务日志{}条", days, rows);
+        Ok(())
+    })
+}
+
+/**
+ * 发布已到发布时间的定时文章
+ */
+fn publish_scheduled_blogs(_: Option<String>, db: DatabaseConnection) -> TaskFuture {
+    Box::pin(async move { BlogService::publish_scheduled_blogs(&db).await })
+}
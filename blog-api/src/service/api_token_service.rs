@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use actix_web::{http::header::AUTHORIZATION, HttpRequest};
+use chrono::{Local, NaiveDateTime};
+use rand::{distributions::Alphanumeric, rngs::OsRng, Rng};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, DatabaseConnection, EntityTrait,
+    QueryFilter, QueryOrder, Set,
+};
+use sha2::{Digest, Sha256};
+
+use crate::entity::{api_token, user};
+use crate::error::DataBaseError;
+use crate::middleware::Permission;
+use crate::model::ApiToken;
+
+/// token前缀，用于区分API token与JWT
+const TOKEN_PREFIX: &str = "dbt_";
+/// token前缀之后的随机字符数
+const TOKEN_RANDOM_LENGTH: usize = 40;
+/// 列表中展示的token前缀长度
+const DISPLAY_PREFIX_LENGTH: usize = 12;
+/// 最后使用时间的更新间隔(秒)，避免每次请求都写库
+const TOUCH_INTERVAL_SECONDS: i64 = 60;
+
+pub struct ApiTokenService;
+
+impl ApiTokenService {
+    /**
+     * 创建API token，返回token信息与明文token(仅显示这一次)
+     */
+    pub async fn create_token(
+        user_id: i64,
+        name: &str,
+        scopes: &[String],
+        expire_time: Option<NaiveDateTime>,
+        db: &DatabaseConnection,
+    ) -> Result<(ApiToken, String), DataBaseError> {
+        if name.trim().is_empty() {
+            return Err(DataBaseError::Validation("名称不能为空".to_string()));
+        }
+        if scopes.is_empty() {
+            return Err(DataBaseError::Validation("权限范围不能为空".to_string()));
+        }
+        if let Some(scope) = scopes
+            .iter()
+            .find(|scope| Permission::from_scope(scope).is_none())
+        {
+            return Err(DataBaseError::Validation(format!(
+                "权限范围不存在:{}",
+                scope
+            )));
+        }
+        let now = Local::now().naive_local();
+        if expire_time.is_some_and(|expire_time| expire_time <= now) {
+            return Err(DataBaseError::Validation(
+                "过期时间必须晚于当前时间".to_string(),
+            ));
+        }
+        let token = Self::generate_token();
+        let model = api_token::ActiveModel {
+            id: NotSet,
+            user_id: Set(user_id),
+            name: Set(name.trim().to_string()),
+            token_hash: Set(Self::hash_token(&token)),
+            token_prefix: Set(token[..DISPLAY_PREFIX_LENGTH].to_string()),
+            scopes: Set(scopes.join(",")),
+            expire_time: Set(expire_time),
+            last_used_time: Set(None),
+            create_time: Set(now),
+        }
+        .insert(db)
+        .await?;
+        Ok((ApiToken::from(model), token))
+    }
+
+    /**
+     * 所有API token，按创建时间倒序
+     */
+    pub async fn find_tokens(db: &DatabaseConnection) -> Result<Vec<ApiToken>, DataBaseError> {
+        let models = api_token::Entity::find()
+            .order_by_desc(api_token::Column::Id)
+            .all(db)
+            .await?;
+        let usernames: HashMap<i64, String> = user::Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|user| (user.id, user.username))
+            .collect();
+        Ok(models
+            .into_iter()
+            .map(|model| {
+                let mut token = ApiToken::from(model);
+                token.username = usernames.get(&token.user_id).cloned();
+                token
+            })
+            .collect())
+    }
+
+    /**
+     * 撤销API token
+     */
+    pub async fn delete_token(id: i64, db: &DatabaseConnection) -> Result<(), DataBaseError> {
+        let result = api_token::Entity::delete_by_id(id).exec(db).await?;
+        if result.rows_affected == 0 {
+            return Err(DataBaseError::Validation("API token不存在".to_string()));
+        }
+        Ok(())
+    }
+
+    /**
+     * 请求头 `Authorization: Bearer dbt_...` 中的API token
+     */
+    pub fn from_request(req: &HttpRequest) -> Option<String> {
+        req.headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(|token| token.trim())
+            .filter(|token| token.starts_with(TOKEN_PREFIX))
+            .map(|token| token.to_string())
+    }
+
+    /**
+     * 校验API token，返回所属账号与token，token不存在或已过期时返回None
+     */
+    pub async fn authenticate(
+        token: &str,
+        db: &DatabaseConnection,
+    ) -> Result<Option<(user::Model, api_token::Model)>, DataBaseError> {
+        let Some(model) = api_token::Entity::find()
+            .filter(api_token::Column::TokenHash.eq(Self::hash_token(token)))
+            .one(db)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let now = Local::now().naive_local();
+        if model
+            .expire_time
+            .is_some_and(|expire_time| expire_time <= now)
+        {
+            return Ok(None);
+        }
+        let Some(user) = user::Entity::find_by_id(model.user_id).one(db).await? else {
+            return Ok(None);
+        };
+        let touch = model.last_used_time.map_or(true, |time| {
+            (now - time).num_seconds() >= TOUCH_INTERVAL_SECONDS
+        });
+        if touch {
+            api_token::ActiveModel {
+                id: Set(model.id),
+                last_used_time: Set(Some(now)),
+                ..Default::default()
+            }
+            .update(db)
+            .await?;
+        }
+        Ok(Some((user, model)))
+    }
+
+    /**
+     * token的SHA-256，数据库只保存哈希
+     */
+    pub fn hash_token(token: &str) -> String {
+        format!("{:x}", Sha256::digest(token.as_bytes()))
+    }
+
+    fn generate_token() -> String {
+        let random: String = OsRng
+            .sample_iter(&Alphanumeric)
+            .take(TOKEN_RANDOM_LENGTH)
+            .map(char::from)
+            .collect();
+        format!("{}{}", TOKEN_PREFIX, random)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ApiTokenService, TOKEN_PREFIX, TOKEN_RANDOM_LENGTH};
+
+    #[test]
+    fn test_token_hash() {
+        let token = ApiTokenService::generate_token();
+        assert!(token.starts_with(TOKEN_PREFIX));
+        assert_eq!(token.len(), TOKEN_PREFIX.len() + TOKEN_RANDOM_LENGTH);
+        assert_eq!(
+            ApiTokenService::hash_token("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_ne!(ApiTokenService::generate_token(), token);
+    }
+}
@@ -0,0 +1,439 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+
+use actix_web::web;
+use chrono::Local;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, DatabaseConnection, DatabaseTransaction,
+    DbErr, EntityTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::common::MarkdownPost;
+use crate::constant::RedisKeyConstant;
+use crate::entity::sea_orm_active_enums::BlogStatus;
+use crate::entity::{blog, blog_tag, category, tag};
+use crate::error::DataBaseError;
+use crate::model::{MarkdownImportFailure, MarkdownImportReport};
+use crate::service::{
+    BlogPasswordService, BlogRevisionService, BlogService, RedisService, SearchService, UserService,
+};
+
+/// 单个Markdown文件的最大字节数
+const MAX_FILE_SIZE: u64 = 5 * 1024 * 1024;
+/// zip中Markdown文件解压后的最大总字节数
+const MAX_TOTAL_SIZE: u64 = 50 * 1024 * 1024;
+/// 一次导入的最大文件数
+const MAX_FILE_COUNT: usize = 2000;
+/// 没有分类的文章归入该分类
+const DEFAULT_CATEGORY: &str = "未分类";
+/// 阅读速度(字/分钟)，用于计算阅读时长
+const WORDS_PER_MINUTE: i32 = 300;
+
+/**
+ * 待导入的文件：(文件路径, 内容)
+ */
+pub type MarkdownFile = (String, Vec<u8>);
+
+pub struct BlogMarkdownService;
+
+impl BlogMarkdownService {
+    /**
+     * 读取zip中的Markdown文件，忽略其他文件与隐藏目录
+     */
+    pub fn read_zip(bytes: &[u8]) -> Result<Vec<MarkdownFile>, DataBaseError> {
+        let mut archive = ZipArchive::new(Cursor::new(bytes))
+            .map_err(|e| DataBaseError::Validation(format!("无法读取zip文件:{}", e)))?;
+        let mut files = vec![];
+        let mut total = 0;
+        for index in 0..archive.len() {
+            let mut file = archive
+                .by_index(index)
+                .map_err(|e| DataBaseError::Validation(format!("无法读取zip文件:{}", e)))?;
+            let name = file.name().to_string();
+            if file.is_dir() || !Self::is_markdown(&name) {
+                continue;
+            }
+            if file.size() > MAX_FILE_SIZE {
+                return Err(DataBaseError::Validation(format!("文件过大:{}", name)));
+            }
+            //按实际解压的字节数限制，不信任zip中声明的文件大小
+            let limit = MAX_FILE_SIZE.min(MAX_TOTAL_SIZE - total);
+            let mut content = vec![];
+            file.by_ref()
+                .take(limit + 1)
+                .read_to_end(&mut content)
+                .map_err(|e| DataBaseError::Validation(format!("无法读取{}:{}", name, e)))?;
+            if content.len() as u64 > MAX_FILE_SIZE {
+                return Err(DataBaseError::Validation(format!("文件过大:{}", name)));
+            }
+            total += content.len() as u64;
+            if total > MAX_TOTAL_SIZE {
+                return Err(DataBaseError::Validation(format!(
+                    "解压后的文件总大小不能超过{}MB",
+                    MAX_TOTAL_SIZE / 1024 / 1024
+                )));
+            }
+            files.push((name, content));
+            if files.len() > MAX_FILE_COUNT {
+                return Err(DataBaseError::Validation(format!(
+                    "一次最多导入{}个文件",
+                    MAX_FILE_COUNT
+                )));
+            }
+        }
+        Ok(files)
+    }
+
+    /**
+     * 递归读取目录中的Markdown文件，路径为.zip时按zip读取
+     */
+    pub fn read_path(path: &Path) -> Result<Vec<MarkdownFile>, DataBaseError> {
+        if path.is_file() {
+            let bytes = fs::read(path)
+                .map_err(|e| DataBaseError::Custom(format!("无法读取{:?}:{}", path, e)))?;
+            let name = path.to_string_lossy().to_string();
+            return match name.to_lowercase().ends_with(".zip") {
+                true => Self::read_zip(&bytes),
+                false => Ok(vec![(name, bytes)]),
+            };
+        }
+        let mut files = vec![];
+        let mut dirs = vec![path.to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            let entries = fs::read_dir(&dir)
+                .map_err(|e| DataBaseError::Custom(format!("无法读取目录{:?}:{}", dir, e)))?;
+            for entry in entries.flatten() {
+                let entry_path = entry.path();
+                let name = entry_path.to_string_lossy().to_string();
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                if file_name.starts_with('.') {
+                    continue;
+                }
+                if entry_path.is_dir() {
+                    dirs.push(entry_path);
+                } else if Self::is_markdown(&file_name) {
+                    let bytes = fs::read(&entry_path)
+                        .map_err(|e| DataBaseError::Custom(format!("无法读取{}:{}", name, e)))?;
+                    files.push((name, bytes));
+                }
+            }
+        }
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(files)
+    }
+
+    /**
+     * 导入Markdown文件，按slug新增或更新文章，单个文件失败不影响其他文件
+     * owner不为None时(编辑角色)不能覆盖其他作者的文章
+     */
+    pub async fn import_files(
+        files: Vec<MarkdownFile>,
+        user_id: i64,
+        owner: Option<i64>,
+        db: &DatabaseConnection,
+    ) -> Result<MarkdownImportReport, DataBaseError> {
+        let mut report = MarkdownImportReport::default();
+        let mut blog_ids = vec![];
+        let mut slugs = HashSet::new();
+        for (file, bytes) in files {
+            let result = match String::from_utf8(bytes) {
+                Ok(text) => MarkdownPost::parse(&file, &text).map_err(DataBaseError::Validation),
+                Err(_) => Err(DataBaseError::Validation("文件不是UTF-8编码".to_string())),
+            };
+            let result = match result {
+                Ok(post) if !slugs.insert(post.slug.clone()) => {
+                    Err(DataBaseError::Validation(format!("slug重复:{}", post.slug)))
+                }
+                Ok(post) => Self::import_post(post, user_id, owner, db).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok((blog_id, created)) => {
+                    blog_ids.push(blog_id);
+                    match created {
+                        true => report.created += 1,
+                        false => report.updated += 1,
+                    }
+                }
+                Err(e) => report.failures.push(MarkdownImportFailure {
+                    file,
+                    reason: e.to_string(),
+                }),
+            }
+        }
+        if !blog_ids.is_empty() {
+            RedisService::evict_cache(&RedisKeyConstant::BLOG_CACHE_KEYS).await;
+            RedisService::evict_cache(&RedisKeyConstant::CATEGORY_CACHE_KEYS).await;
+            RedisService::evict_cache(&RedisKeyConstant::TAG_CACHE_KEYS).await;
+            for blog_id in blog_ids {
+                SearchService::sync_blog(blog_id, db).await;
+            }
+        }
+        Ok(report)
+    }
+
+    /**
+     * 导入一篇文章，返回(文章ID, 是否新增)
+     */
+    async fn import_post(
+        post: MarkdownPost,
+        user_id: i64,
+        owner: Option<i64>,
+        db: &DatabaseConnection,
+    ) -> Result<(i64, bool), DataBaseError> {
+        let existing = blog::Entity::find()
+            .filter(blog::Column::Slug.eq(post.slug.as_str()))
+            .one(db)
+            .await?;
+        if let (Some(existing), Some(owner)) = (&existing, owner) {
+            if existing.user_id != Some(owner) {
+                return Err(DataBaseError::Validation("只能管理自己的文章".to_string()));
+            }
+        }
+        //front matter 指定了状态时按指定的状态，否则新文章直接发布，已有文章保留原状态与定时发布时间
+        let status = match post.status.as_deref() {
+            Some(status) => Some(
+                serde_json::from_value::<BlogStatus>(serde_json::Value::String(status.to_string()))
+                    .map_err(|_| DataBaseError::Validation(format!("文章状态不存在:{}", status)))?,
+            ),
+            None => None,
+        };
+        let now = Local::now().naive_local();
+        let status =
+            match BlogService::resolve_status(status, post.publish_at, post.published, now)? {
+                Some(status) => Some(status),
+                None if existing.is_none() => Some((BlogStatus::Published, None)),
+                None => None,
+            };
+        //导出的密码已是哈希，直接保存
+        let password = match post.password.as_deref() {
+            Some(password) if UserService::is_hashed(password) => Some(password.to_string()),
+            Some(password) => BlogPasswordService::hash_password(
+                password,
+                existing.as_ref().and_then(|blog| blog.password.as_deref()),
+            )?,
+            None => None,
+        };
+        let result = db
+            .transaction(|conn| {
+                Box::pin(async move {
+                    let category_name = post
+                        .categories
+                        .first()
+                        .map(String::as_str)
+                        .unwrap_or(DEFAULT_CATEGORY);
+                    let category_id = Self::find_or_create_category(category_name, conn).await?;
+                    let mut tag_ids = vec![];
+                    for tag_name in post.tags.iter() {
+                        let tag_id = Self::find_or_create_tag(tag_name, conn).await?;
+                        if !tag_ids.contains(&tag_id) {
+                            tag_ids.push(tag_id);
+                        }
+                    }
+
+                    let words = post.content.chars().filter(|c| !c.is_whitespace()).count() as i32;
+                    let mut model = blog::ActiveModel {
+                        title: Set(post.title),
+                        first_picture: Set(post.cover),
+                        content: Set(post.content),
+                        description: Set(post.description),
+                        update_time: Set(post.updated.or(post.date).unwrap_or(now)),
+                        words: Set(words),
+                        read_time: Set((words + WORDS_PER_MINUTE - 1) / WORDS_PER_MINUTE),
+                        category_id: Set(category_id),
+                        password: Set(password),
+                        slug: Set(Some(post.slug)),
+                        ..Default::default()
+                    };
+                    if let Some((status, publish_at)) = status {
+                        model.is_published = Set(status == BlogStatus::Published);
+                        model.status = Set(status);
+                        model.publish_at = Set(publish_at);
+                    }
+                    let (blog_id, created) = match existing {
+                        Some(existing) => {
+                            BlogRevisionService::save_revision(&existing, conn).await?;
+                            model.id = Set(existing.id);
+                            if let Some(date) = post.date {
+                                model.create_time = Set(date);
+                            }
+                            let model = model.update(conn).await?;
+                            blog_tag::Entity::delete_many()
+                                .filter(blog_tag::Column::BlogId.eq(model.id))
+                                .exec(conn)
+                                .await?;
+                            (model.id, false)
+                        }
+                        None => {
+                            model.id = NotSet;
+                            model.create_time = Set(post.date.unwrap_or(now));
+                            model.is_recommend = Set(false);
+                            model.is_appreciation = Set(false);
+                            model.is_comment_enabled = Set(true);
+                            model.is_top = Set(false);
+                            model.views = Set(0);
+                            model.user_id = Set(Some(user_id));
+                            (model.insert(conn).await?.id, true)
+                        }
+                    };
+                    if !tag_ids.is_empty() {
+                        let models = tag_ids.into_iter().map(|tag_id| blog_tag::ActiveModel {
+                            blog_id: Set(blog_id),
+                            tag_id: Set(tag_id),
+                            ..Default::default()
+                        });
+                        blog_tag::Entity::insert_many(models).exec(conn).await?;
+                    }
+                    Ok::<(i64, bool), DbErr>((blog_id, created))
+                })
+            })
+            .await?;
+        Ok(result)
+    }
+
+    async fn find_or_create_category(name: &str, conn: &DatabaseTransaction) -> Result<i64, DbErr> {
+        let model = category::Entity::find()
+            .filter(category::Column::CategoryName.eq(name))
+            .one(conn)
+            .await?;
+        match model {
+            Some(model) => Ok(model.id),
+            None => {
+                let model = category::ActiveModel {
+                    id: NotSet,
+                    category_name: Set(name.to_string()),
+                }
+                .insert(conn)
+                .await?;
+                Ok(model.id)
+            }
+        }
+    }
+
+    async fn find_or_create_tag(name: &str, conn: &DatabaseTransaction) -> Result<i64, DbErr> {
+        let model = tag::Entity::find()
+            .filter(tag::Column::TagName.eq(name))
+            .one(conn)
+            .await?;
+        match model {
+            Some(model) => Ok(model.id),
+            None => {
+                let model = tag::ActiveModel {
+                    id: NotSet,
+                    tag_name: Set(name.to_string()),
+                    color: Set(Some("red".to_string())),
+                }
+                .insert(conn)
+                .await?;
+                Ok(model.id)
+            }
+        }
+    }
+
+    /**
+     * 导出文章为带front matter的Markdown压缩包，owner不为None时只导出该作者的文章
+     * 没有slug的文章使用 post-{id}，再次导入时会新增
+     */
+    pub async fn export_zip(
+        owner: Option<i64>,
+        db: &DatabaseConnection,
+    ) -> Result<Vec<u8>, DataBaseError> {
+        let mut select = blog::Entity::find().order_by_asc(blog::Column::Id);
+        if let Some(owner) = owner {
+            select = select.filter(blog::Column::UserId.eq(owner));
+        }
+        let blogs = select.all(db).await?;
+        let categories: HashMap<i64, String> = category::Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|model| (model.id, model.category_name))
+            .collect();
+        let tags: HashMap<i64, String> = tag::Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|model| (model.id, model.tag_name))
+            .collect();
+        let mut blog_tags: HashMap<i64, Vec<String>> = HashMap::new();
+        for model in blog_tag::Entity::find()
+            .order_by_asc(blog_tag::Column::Id)
+            .all(db)
+            .await?
+        {
+            if let Some(tag_name) = tags.get(&model.tag_id) {
+                blog_tags
+                    .entry(model.blog_id)
+                    .or_default()
+                    .push(tag_name.clone());
+            }
+        }
+
+        let posts: Vec<MarkdownPost> = blogs
+            .into_iter()
+            .map(|blog| MarkdownPost {
+                slug: blog
+                    .slug
+                    .clone()
+                    .unwrap_or_else(|| format!("post-{}", blog.id)),
+                title: blog.title,
+                date: Some(blog.create_time),
+                updated: Some(blog.update_time),
+                categories: categories
+                    .get(&blog.category_id)
+                    .cloned()
+                    .into_iter()
+                    .collect(),
+                tags: blog_tags.remove(&blog.id).unwrap_or_default(),
+                description: blog.description,
+                cover: blog.first_picture,
+                password: blog.password.filter(|password| !password.is_empty()),
+                published: Some(blog.status == BlogStatus::Published),
+                status: serde_json::to_value(blog.status)
+                    .ok()
+                    .and_then(|status| status.as_str().map(str::to_string)),
+                publish_at: blog.publish_at,
+                content: blog.content,
+            })
+            .collect();
+        //压缩比较耗时，在线程池中执行
+        web::block(move || Self::write_zip(&posts))
+            .await
+            .map_err(|e| DataBaseError::Custom(format!("生成压缩包失败:{}", e)))?
+    }
+
+    /**
+     * 将文章写入zip压缩包，文件名为 {slug}.md
+     */
+    fn write_zip(posts: &[MarkdownPost]) -> Result<Vec<u8>, DataBaseError> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        for post in posts {
+            let result = match writer.start_file(format!("{}.md", post.slug), options) {
+                Ok(_) => writer
+                    .write_all(post.to_markdown().as_bytes())
+                    .map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            if let Err(e) = result {
+                return Err(DataBaseError::Custom(format!("生成压缩包失败:{}", e)));
+            }
+        }
+        let cursor = writer
+            .finish()
+            .map_err(|e| DataBaseError::Custom(format!("生成压缩包失败:{}", e)))?;
+        Ok(cursor.into_inner())
+    }
+
+    fn is_markdown(name: &str) -> bool {
+        let lower = name.to_lowercase();
+        (lower.ends_with(".md") || lower.ends_with(".markdown"))
+            && !name
+                .split(['/', '\\'])
+                .any(|part| part.starts_with('.') || part == "__MACOSX")
+    }
+}
@@ -0,0 +1,200 @@
+use std::sync::LazyLock;
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rand::{rngs::OsRng, RngCore};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, Set};
+use sha2::Sha256;
+
+use crate::constant::{BlogInfoConstant, RedisKeyConstant};
+use crate::entity::blog;
+use crate::error::DataBaseError;
+use crate::service::{RedisService, UserService};
+
+/// 解锁token签名密钥，每次启动随机生成，服务重启后已签发的token失效
+static UNLOCK_SECRET: LazyLock<[u8; 32]> = LazyLock::new(|| {
+    let mut secret = [0u8; 32];
+    OsRng.fill_bytes(&mut secret);
+    secret
+});
+
+/**
+ * 文章访问密码的校验结果
+ */
+pub enum BlogPasswordResult {
+    //密码正确，返回解锁token与过期时间戳(秒)
+    Unlocked(String, i64),
+    //密码错误
+    Wrong,
+    //失败次数过多，返回剩余锁定秒数
+    Locked(u64),
+}
+
+pub struct BlogPasswordService;
+
+impl BlogPasswordService {
+    /**
+     * 表单中的文章密码转为存储值：空字符串表示取消密码，与库中哈希相同表示未修改，其余重新哈希
+     */
+    pub fn hash_password(
+        password: &str,
+        stored: Option<&str>,
+    ) -> Result<Option<String>, DataBaseError> {
+        if password.is_empty() {
+            return Ok(None);
+        }
+        if stored == Some(password) && UserService::is_hashed(password) {
+            return Ok(Some(password.to_string()));
+        }
+        UserService::hash_password(password).map(Some)
+    }
+
+    /**
+     * 校验文章访问密码，同一IP在统计窗口内失败次数过多时锁定
+     * 库中仍为明文的密码校验通过后就地升级为哈希
+     */
+    pub async fn check_password(
+        blog_id: i64,
+        stored: Option<&str>,
+        password: &str,
+        ip: &str,
+        db: &DatabaseConnection,
+    ) -> Result<BlogPasswordResult, DataBaseError> {
+        let fail_key = format!("{}:{}", RedisKeyConstant::BLOG_PASSWORD_FAIL, ip);
+        let failures = RedisService::get_string(fail_key.clone())
+            .await?
+            .and_then(|count| count.parse::<i64>().ok())
+            .unwrap_or_default();
+        if failures >= BlogInfoConstant::MAX_PASSWORD_FAILURES {
+            let ttl = RedisService::get_ttl(fail_key).await?;
+            return Ok(BlogPasswordResult::Locked(ttl.max(1) as u64));
+        }
+        let stored = stored.unwrap_or_default().to_string();
+        if stored.is_empty() || !UserService::verify_password(password, &stored) {
+            RedisService::increment_with_expire(
+                fail_key,
+                BlogInfoConstant::PASSWORD_FAIL_WINDOW_SECONDS,
+            )
+            .await?;
+            return Ok(BlogPasswordResult::Wrong);
+        }
+        let stored = match UserService::is_hashed(&stored) {
+            true => stored,
+            false => {
+                let hashed = UserService::hash_password(password)?;
+                blog::ActiveModel {
+                    id: Set(blog_id),
+                    password: Set(Some(hashed.clone())),
+                    ..Default::default()
+                }
+                .update(db)
+                .await?;
+                log::info!("文章:{}的明文密码已升级为哈希存储", blog_id);
+                hashed
+            }
+        };
+        let expires = Utc::now().timestamp() + BlogInfoConstant::UNLOCK_TOKEN_SECONDS;
+        Ok(BlogPasswordResult::Unlocked(
+            Self::sign(blog_id, expires, &stored),
+            expires,
+        ))
+    }
+
+    /**
+     * 校验解锁token，未设置密码的文章直接通过
+     */
+    pub fn is_unlocked(blog_id: i64, stored: Option<&str>, token: Option<&str>) -> bool {
+        let stored = stored.unwrap_or_default();
+        if stored.is_empty() {
+            return true;
+        }
+        token
+            .is_some_and(|token| Self::verify_token(token, blog_id, stored, Utc::now().timestamp()))
+    }
+
+    /**
+     * 解锁token：文章ID.过期时间戳.签名，签名包含密码哈希，修改密码后旧token失效
+     */
+    fn sign(blog_id: i64, expires: i64, password_hash: &str) -> String {
+        let signature: String = Self::mac(blog_id, expires, password_hash)
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        format!("{}.{}.{}", blog_id, expires, signature)
+    }
+
+    fn verify_token(token: &str, blog_id: i64, password_hash: &str, now: i64) -> bool {
+        let mut parts = token.split('.');
+        let (Some(id), Some(expires), Some(signature), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return false;
+        };
+        let (Ok(id), Ok(expires)) = (id.parse::<i64>(), expires.parse::<i64>()) else {
+            return false;
+        };
+        if id != blog_id || expires < now || signature.len() % 2 != 0 {
+            return false;
+        }
+        let signature: Option<Vec<u8>> = (0..signature.len())
+            .step_by(2)
+            .map(|index| u8::from_str_radix(signature.get(index..index + 2)?, 16).ok())
+            .collect();
+        signature.is_some_and(|signature| {
+            Self::mac(blog_id, expires, password_hash)
+                .verify_slice(&signature)
+                .is_ok()
+        })
+    }
+
+    fn mac(blog_id: i64, expires: i64, password_hash: &str) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&*UNLOCK_SECRET).expect("HMAC可以接受任意长度的密钥");
+        mac.update(format!("{}.{}.{}", blog_id, expires, password_hash).as_bytes());
+        mac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BlogPasswordService;
+
+    #[test]
+    fn test_unlock_token() {
+        let token = BlogPasswordService::sign(7, 1_000, "$argon2id$hash");
+        assert!(BlogPasswordService::verify_token(
+            &token,
+            7,
+            "$argon2id$hash",
+            999
+        ));
+        //过期、其他文章、密码已修改、被篡改
+        assert!(!BlogPasswordService::verify_token(
+            &token,
+            7,
+            "$argon2id$hash",
+            1_001
+        ));
+        assert!(!BlogPasswordService::verify_token(
+            &token,
+            8,
+            "$argon2id$hash",
+            999
+        ));
+        assert!(!BlogPasswordService::verify_token(
+            &token,
+            7,
+            "$argon2id$other",
+            999
+        ));
+        let forged = token.replace(".1000.", ".2000.");
+        assert!(!BlogPasswordService::verify_token(
+            &forged,
+            7,
+            "$argon2id$hash",
+            1_500
+        ));
+    }
+}
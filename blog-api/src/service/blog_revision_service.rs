@@ -0,0 +1,253 @@
+use chrono::Local;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr,
+    EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+
+use crate::app::CONFIG;
+use crate::common::LineDiff;
+use crate::constant::RedisKeyConstant;
+use crate::entity::{blog, blog_revision, blog_tag, category, tag};
+use crate::error::DataBaseError;
+use crate::model::{BlogRevision, BlogRevisionDiff};
+use crate::service::{RedisService, SearchService};
+
+/// 未配置 revision_retain 时每篇文章保留的历史版本数量
+const DEFAULT_REVISION_RETAIN: u64 = 20;
+
+pub struct BlogRevisionService;
+
+impl BlogRevisionService {
+    /**
+     * 将文章当前的标题、描述、正文、分类与标签保存为历史版本，与最近一次版本相同时不保存
+     * 保存后清理超出保留数量的旧版本
+     */
+    pub(crate) async fn save_revision<C: ConnectionTrait>(
+        blog: &blog::Model,
+        conn: &C,
+    ) -> Result<(), DbErr> {
+        let tag_ids = Self::join_tag_ids(&Self::find_tag_ids(blog.id, conn).await?);
+        let latest = blog_revision::Entity::find()
+            .filter(blog_revision::Column::BlogId.eq(blog.id))
+            .order_by_desc(blog_revision::Column::Id)
+            .one(conn)
+            .await?;
+        if let Some(latest) = latest {
+            if latest.title == blog.title
+                && latest.description == blog.description
+                && latest.content == blog.content
+                && latest.category_id == blog.category_id
+                && latest.tag_ids == tag_ids
+            {
+                return Ok(());
+            }
+        }
+        blog_revision::ActiveModel {
+            id: NotSet,
+            blog_id: Set(blog.id),
+            title: Set(blog.title.clone()),
+            description: Set(blog.description.clone()),
+            content: Set(blog.content.clone()),
+            category_id: Set(blog.category_id),
+            tag_ids: Set(tag_ids),
+            create_time: Set(Local::now().naive_local()),
+        }
+        .insert(conn)
+        .await?;
+
+        let retain = CONFIG
+            .get_server_config()
+            .revision_retain
+            .unwrap_or(DEFAULT_REVISION_RETAIN)
+            .max(1);
+        let revision_ids: Vec<i64> = blog_revision::Entity::find()
+            .select_only()
+            .column(blog_revision::Column::Id)
+            .filter(blog_revision::Column::BlogId.eq(blog.id))
+            .order_by_desc(blog_revision::Column::Id)
+            .into_tuple()
+            .all(conn)
+            .await?;
+        let expired_ids: Vec<i64> = revision_ids.into_iter().skip(retain as usize).collect();
+        if !expired_ids.is_empty() {
+            blog_revision::Entity::delete_many()
+                .filter(blog_revision::Column::Id.is_in(expired_ids))
+                .exec(conn)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /**
+     * 文章的历史版本列表，按时间倒序
+     */
+    pub async fn find_revisions(
+        blog_id: i64,
+        db: &DatabaseConnection,
+    ) -> Result<Vec<BlogRevision>, DataBaseError> {
+        let revisions = blog_revision::Entity::find()
+            .filter(blog_revision::Column::BlogId.eq(blog_id))
+            .order_by_desc(blog_revision::Column::Id)
+            .all(db)
+            .await?;
+        Ok(revisions
+            .iter()
+            .map(|revision| Self::to_revision(revision))
+            .collect())
+    }
+
+    /**
+     * 比较两个历史版本，to为None时与文章当前版本比较
+     */
+    pub async fn diff(
+        blog_id: i64,
+        from_id: i64,
+        to_id: Option<i64>,
+        db: &DatabaseConnection,
+    ) -> Result<BlogRevisionDiff, DataBaseError> {
+        let from = Self::find_revision(blog_id, from_id, db).await?;
+        let to = match to_id {
+            Some(to_id) => Self::find_revision(blog_id, to_id, db).await?,
+            None => Self::current_revision(blog_id, db).await?,
+        };
+        Ok(BlogRevisionDiff {
+            title: LineDiff::diff(&from.title, &to.title),
+            description: LineDiff::diff(&from.description, &to.description),
+            content: LineDiff::diff(&from.content, &to.content),
+            from: Self::to_revision(&from),
+            to: Self::to_revision(&to),
+        })
+    }
+
+    /**
+     * 将文章恢复到指定版本，恢复前先保存当前版本
+     * 已删除的分类与标签不会被恢复
+     */
+    pub async fn restore(
+        blog_id: i64,
+        revision_id: i64,
+        db: &DatabaseConnection,
+    ) -> Result<(), DataBaseError> {
+        let revision = Self::find_revision(blog_id, revision_id, db).await?;
+        let blog = blog::Entity::find_by_id(blog_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DataBaseError::Validation("没有检索到文章".to_string()))?;
+        db.transaction(|conn| {
+            Box::pin(async move {
+                Self::save_revision(&blog, conn).await?;
+                let category_exist = category::Entity::find_by_id(revision.category_id)
+                    .one(conn)
+                    .await?
+                    .is_some();
+                let mut active = blog::ActiveModel::from(blog);
+                active.title = Set(revision.title);
+                active.description = Set(revision.description);
+                active.content = Set(revision.content);
+                if category_exist {
+                    active.category_id = Set(revision.category_id);
+                }
+                active.update_time = Set(Local::now().naive_local());
+                active.update(conn).await?;
+
+                let tag_ids: Vec<i64> = tag::Entity::find()
+                    .select_only()
+                    .column(tag::Column::Id)
+                    .filter(tag::Column::Id.is_in(Self::parse_tag_ids(&revision.tag_ids)))
+                    .into_tuple()
+                    .all(conn)
+                    .await?;
+                blog_tag::Entity::delete_many()
+                    .filter(blog_tag::Column::BlogId.eq(blog_id))
+                    .exec(conn)
+                    .await?;
+                if !tag_ids.is_empty() {
+                    let models = tag_ids.into_iter().map(|tag_id| blog_tag::ActiveModel {
+                        id: NotSet,
+                        blog_id: Set(blog_id),
+                        tag_id: Set(tag_id),
+                    });
+                    blog_tag::Entity::insert_many(models).exec(conn).await?;
+                }
+                Ok::<(), DbErr>(())
+            })
+        })
+        .await?;
+        RedisService::evict_cache(&RedisKeyConstant::BLOG_CACHE_KEYS).await;
+        SearchService::sync_blog(blog_id, db).await;
+        Ok(())
+    }
+
+    async fn find_revision(
+        blog_id: i64,
+        revision_id: i64,
+        db: &DatabaseConnection,
+    ) -> Result<blog_revision::Model, DataBaseError> {
+        blog_revision::Entity::find_by_id(revision_id)
+            .filter(blog_revision::Column::BlogId.eq(blog_id))
+            .one(db)
+            .await?
+            .ok_or_else(|| DataBaseError::Validation("没有检索到历史版本".to_string()))
+    }
+
+    /**
+     * 文章当前版本，id为0
+     */
+    async fn current_revision(
+        blog_id: i64,
+        db: &DatabaseConnection,
+    ) -> Result<blog_revision::Model, DataBaseError> {
+        let blog = blog::Entity::find_by_id(blog_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DataBaseError::Validation("没有检索到文章".to_string()))?;
+        Ok(blog_revision::Model {
+            id: 0,
+            blog_id,
+            tag_ids: Self::join_tag_ids(&Self::find_tag_ids(blog_id, db).await?),
+            title: blog.title,
+            description: blog.description,
+            content: blog.content,
+            category_id: blog.category_id,
+            create_time: blog.update_time,
+        })
+    }
+
+    async fn find_tag_ids<C: ConnectionTrait>(blog_id: i64, conn: &C) -> Result<Vec<i64>, DbErr> {
+        blog_tag::Entity::find()
+            .select_only()
+            .column(blog_tag::Column::TagId)
+            .filter(blog_tag::Column::BlogId.eq(blog_id))
+            .order_by_asc(blog_tag::Column::TagId)
+            .into_tuple()
+            .all(conn)
+            .await
+    }
+
+    fn to_revision(model: &blog_revision::Model) -> BlogRevision {
+        BlogRevision {
+            id: Some(model.id).filter(|id| *id > 0),
+            blog_id: model.blog_id,
+            title: model.title.clone(),
+            description: model.description.clone(),
+            category_id: model.category_id,
+            tag_ids: Self::parse_tag_ids(&model.tag_ids),
+            create_time: model.create_time,
+        }
+    }
+
+    fn join_tag_ids(tag_ids: &[i64]) -> String {
+        tag_ids
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    fn parse_tag_ids(tag_ids: &str) -> Vec<i64> {
+        tag_ids
+            .split(',')
+            .filter_map(|id| id.trim().parse::<i64>().ok())
+            .collect()
+    }
+}
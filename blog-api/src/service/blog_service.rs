@@ -2,26 +2,27 @@ use crate::constant::BlogInfoConstant;
 use crate::constant::RedisKeyConstant;
 use crate::entity::{
     blog::{self},
-    blog_tag, category, tag,
+    blog_revision, blog_tag, category,
+    sea_orm_active_enums::BlogStatus,
+    tag,
 };
 
 use crate::common::MarkdownParser;
 use crate::common::TypeValue;
 use crate::error::DataBaseError;
-use crate::model::{
-    BlogArchive, BlogDetail, BlogInfo, BlogVO, BlogVisibility, SearchBlog, SearchRequest,
-};
+use crate::model::{BlogArchive, BlogDetail, BlogInfo, BlogVO, BlogVisibility, SearchRequest};
 use crate::model::{BlogDTO, BlogIdAndTitle};
-use crate::service::RedisService;
-use chrono::{Datelike, NaiveDate};
+use crate::service::{BlogPasswordService, BlogRevisionService, RedisService, SearchService};
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime};
 use rand::Rng;
 use rbs::value;
 use rbs::value::map::ValueMap;
 use rbs::Value;
+use sea_orm::sea_query::Expr;
 use sea_orm::{
     ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait,
-    FromQueryResult, ModelTrait, PaginatorTrait, QueryFilter, QueryOrder, QueryTrait, Statement,
-    TransactionTrait,
+    FromQueryResult, ModelTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, QueryTrait,
+    Statement, TransactionTrait,
 };
 use std::collections::HashMap;
 use std::ops::Index;
@@ -41,19 +42,23 @@ impl BlogService {
         )
         .await;
         //2.缓存不未Null则返回缓存数据
-        if let Ok(redis_cache) = redis_cache {
+        if let Ok(mut redis_cache) = redis_cache {
             log::info!(
                 "reids KEY:{} 当前页：{} 获取缓存数据成功",
                 RedisKeyConstant::HOME_BLOG_INFO_LIST,
                 page_num
             );
+            //缓存中为数据库浏览量，叠加Redis中未同步的浏览量
+            if let Some(list) = redis_cache.get_mut("list") {
+                *list = Self::apply_pending_views(list, &Self::find_pending_views().await);
+            }
             return Ok(redis_cache);
         }
         //3.查询数据库
         let mut map: HashMap<String, Value> = HashMap::new();
 
         let page = blog::Entity::find()
-            .filter(blog::Column::IsPublished.eq(true))
+            .filter(blog::Column::Status.eq(BlogStatus::Published))
             .order_by_desc(blog::Column::CreateTime)
             .paginate(db, BlogInfoConstant::PAGE_SIZE);
         let list = match page.fetch_page(page_num - 1).await {
@@ -88,6 +93,9 @@ impl BlogService {
             "redis KEY:{} 缓存数据成功",
             RedisKeyConstant::HOME_BLOG_INFO_LIST
         );
+        if let Some(list) = map.get_mut("list") {
+            *list = Self::apply_pending_views(list, &Self::find_pending_views().await);
+        }
         Ok(map)
     }
     /**
@@ -113,7 +121,7 @@ impl BlogService {
         //2.查询数据库
 
         let blog_models = blog::Entity::find()
-            .filter(blog::Column::IsPublished.eq(true))
+            .filter(blog::Column::Status.eq(BlogStatus::Published))
             .all(db)
             .await
             .unwrap_or_default();
@@ -181,7 +189,7 @@ impl BlogService {
         }
         //2.查询数据库
         let blog_models = blog::Entity::find()
-            .filter(blog::Column::IsPublished.eq(true))
+            .filter(blog::Column::Status.eq(BlogStatus::Published))
             .all(db)
             .await
             .unwrap_or_default();
@@ -242,7 +250,7 @@ impl BlogService {
 
         let page = category_model
             .find_related(blog::Entity)
-            .filter(blog::Column::IsPublished.eq(true))
+            .filter(blog::Column::Status.eq(BlogStatus::Published))
             .order_by_desc(blog::Column::CreateTime)
             .paginate(db, BlogInfoConstant::PAGE_SIZE);
         let blog_models = page
@@ -254,6 +262,7 @@ impl BlogService {
             blog_info_list.push(BlogInfo::from(item));
         }
         BlogService::bloginfo_handle(&mut blog_info_list, db).await;
+        Self::add_pending_views(&mut blog_info_list).await;
         map.insert("list".to_string(), value!(blog_info_list));
         map.insert(
             "totalPage".to_string(),
@@ -262,10 +271,15 @@ impl BlogService {
         map
     }
 
-    //根据ID查找博文
+    //根据ID查找博文，已归档的文章仍可通过链接访问
     pub(crate) async fn find_id_detail(id: i64, db: &DatabaseConnection) -> Option<BlogDetail> {
-        let blog_model = match blog::Entity::find_by_id(id).one(db).await {
-            Ok(blog) => blog.unwrap_or_default(),
+        let blog_model = match blog::Entity::find_by_id(id)
+            .filter(blog::Column::Status.is_in([BlogStatus::Published, BlogStatus::Archived]))
+            .one(db)
+            .await
+        {
+            Ok(Some(blog)) => blog,
+            Ok(None) => return None,
             Err(e) => {
                 log::error!("{:?}", e);
                 return None;
@@ -273,6 +287,10 @@ impl BlogService {
         };
         let mut blog = BlogDetail::from(blog_model);
         blog.content = MarkdownParser::parser_html(blog.content.clone());
+        //叠加Redis中尚未同步的浏览量
+        if let Some(delta) = Self::find_pending_views().await.get(&id) {
+            blog.views += *delta as i32;
+        }
         Some(blog)
     }
 
@@ -297,7 +315,7 @@ impl BlogService {
         };
         let page = tag_model
             .find_related(blog::Entity)
-            .filter(blog::Column::IsPublished.eq(true))
+            .filter(blog::Column::Status.eq(BlogStatus::Published))
             .order_by_desc(blog::Column::CreateTime)
             .paginate(db, BlogInfoConstant::PAGE_SIZE);
         let blog_models = page
@@ -310,6 +328,7 @@ impl BlogService {
             blog_info_list.push(BlogInfo::from(item));
         }
         BlogService::bloginfo_handle(&mut blog_info_list, db).await;
+        Self::add_pending_views(&mut blog_info_list).await;
         map.insert("list".to_string(), value!(blog_info_list));
         map.insert(
             "totalPage".to_string(),
@@ -318,6 +337,152 @@ impl BlogService {
         map
     }
 
+    /**
+     * 浏览量+1，同一访客在 VIEW_INTERVAL_SECONDS 内重复浏览不计数，返回是否计数
+     */
+    pub async fn increase_views(blog_id: i64, visitor: &str) -> bool {
+        let visitor_key = format!(
+            "{}:{}:{}",
+            RedisKeyConstant::BLOG_VIEW_VISITOR,
+            blog_id,
+            visitor
+        );
+        match RedisService::set_if_absent(visitor_key, BlogInfoConstant::VIEW_INTERVAL_SECONDS)
+            .await
+        {
+            Ok(true) => {
+                match RedisService::increment_hash_field(
+                    RedisKeyConstant::BLOG_VIEWS_MAP.to_string(),
+                    blog_id.to_string(),
+                    1,
+                )
+                .await
+                {
+                    Ok(_) => true,
+                    Err(e) => {
+                        log::error!("博客:{} 浏览量增加失败:{}", blog_id, e);
+                        false
+                    }
+                }
+            }
+            Ok(false) => false,
+            Err(e) => {
+                log::error!("博客:{} 浏览去重失败:{}", blog_id, e);
+                false
+            }
+        }
+    }
+
+    /**
+     * 获取Redis中尚未同步到数据库的浏览量 博客id -> 增量
+     */
+    pub async fn find_pending_views() -> HashMap<i64, i64> {
+        match RedisService::get_hash_i64_map(RedisKeyConstant::BLOG_VIEWS_MAP.to_string()).await {
+            Ok(map) => map
+                .into_iter()
+                .filter_map(|(id, views)| id.parse::<i64>().ok().map(|id| (id, views)))
+                .collect(),
+            Err(e) => {
+                log::error!(
+                    "redis KEY:{} 获取浏览量失败:{}",
+                    RedisKeyConstant::BLOG_VIEWS_MAP,
+                    e
+                );
+                HashMap::new()
+            }
+        }
+    }
+
+    /**
+     * 将Redis中的浏览量增量同步到 blog.views
+     */
+    pub async fn sync_blog_views(db: &DatabaseConnection) -> Result<(), DataBaseError> {
+        let pending =
+            RedisService::get_hash_i64_map(RedisKeyConstant::BLOG_VIEWS_MAP.to_string()).await?;
+        for (id, delta) in pending {
+            let blog_id = match id.parse::<i64>() {
+                Ok(blog_id) => blog_id,
+                Err(_) => {
+                    RedisService::delete_hash_field(
+                        RedisKeyConstant::BLOG_VIEWS_MAP.to_string(),
+                        id,
+                    )
+                    .await?;
+                    continue;
+                }
+            };
+            //先从Redis中扣除待同步的增量，保留同步期间新增的浏览量，避免写库后扣除失败导致重复累加
+            RedisService::take_hash_field(
+                RedisKeyConstant::BLOG_VIEWS_MAP.to_string(),
+                id.clone(),
+                delta,
+            )
+            .await?;
+            if delta <= 0 {
+                continue;
+            }
+            let result = blog::Entity::update_many()
+                .col_expr(
+                    blog::Column::Views,
+                    Expr::col(blog::Column::Views).add(delta),
+                )
+                .filter(blog::Column::Id.eq(blog_id))
+                .exec(db)
+                .await;
+            if let Err(e) = result {
+                //写库失败时归还增量，下次同步重试
+                RedisService::increment_hash_field(
+                    RedisKeyConstant::BLOG_VIEWS_MAP.to_string(),
+                    id,
+                    delta,
+                )
+                .await?;
+                return Err(e.into());
+            }
+            log::info!("博客:{} 同步浏览量:{}", blog_id, delta);
+        }
+        //首页、最新、随机文章缓存的是数据库浏览量，同步后需要刷新
+        RedisService::evict_cache(&RedisKeyConstant::BLOG_VIEWS_CACHE_KEYS).await;
+        Ok(())
+    }
+
+    /**
+     * 为文章列表叠加尚未同步的浏览量
+     */
+    async fn add_pending_views(list: &mut Vec<BlogInfo>) {
+        let pending = Self::find_pending_views().await;
+        for item in list.iter_mut() {
+            if let Some(delta) = item.id.and_then(|id| pending.get(&id)) {
+                item.views += *delta as i32;
+            }
+        }
+    }
+
+    /**
+     * 为缓存中的文章列表(Value)叠加尚未同步的浏览量
+     */
+    fn apply_pending_views(list: &Value, pending: &HashMap<i64, i64>) -> Value {
+        if pending.is_empty() {
+            return list.clone();
+        }
+        let mut json = match serde_json::to_value(list) {
+            Ok(json) => json,
+            Err(_) => return list.clone(),
+        };
+        if let Some(items) = json.as_array_mut() {
+            for item in items.iter_mut() {
+                let delta = item
+                    .get("id")
+                    .and_then(|id| id.as_i64())
+                    .and_then(|id| pending.get(&id));
+                if let (Some(delta), Some(views)) = (delta, item.get_mut("views")) {
+                    *views = serde_json::Value::from(views.as_i64().unwrap_or_default() + delta);
+                }
+            }
+        }
+        serde_json::from_value(json).unwrap_or_else(|_| list.clone())
+    }
+
     //获取归档文章
     pub(crate) async fn find_archives(db: &DatabaseConnection) -> Result<ValueMap, DataBaseError> {
         let redis_cache =
@@ -334,7 +499,7 @@ impl BlogService {
         let mut dates = ValueMap::new();
         //1.获取所有文章的日期
         blog::Entity::find()
-            .filter(blog::Column::IsPublished.eq(true))
+            .filter(blog::Column::Status.eq(BlogStatus::Published))
             .order_by_desc(blog::Column::CreateTime)
             .all(db)
             .await
@@ -355,8 +520,13 @@ impl BlogService {
                 r#"SELECT id,title,CONCAT(DAY(create_time),"日") as `day`,password
             FROM blog
             WHERE YEAR(create_time) = ?
-              AND MONTH(create_time) = ?;"#,
-                [date_time.year().into(), date_time.month().into()],
+              AND MONTH(create_time) = ?
+              AND status = ?;"#,
+                [
+                    date_time.year().into(),
+                    date_time.month().into(),
+                    BlogStatus::Published.into(),
+                ],
             );
             let mut blogs = BlogArchive::find_by_statement(sql)
                 .all(db)
@@ -390,7 +560,7 @@ impl BlogService {
     pub(crate) async fn find_archives_count(db: &DatabaseConnection) -> Option<u64> {
         Some(
             blog::Entity::find()
-                .filter(blog::Column::IsPublished.eq(true))
+                .filter(blog::Column::Status.eq(BlogStatus::Published))
                 .count(db)
                 .await
                 .unwrap_or_default(),
@@ -433,7 +603,11 @@ impl BlogService {
     /**
      * 获取所有文章，用于首页展示，每页10条数据，并返回总页数，用于分页展示。 -后台
      */
-    pub async fn find_all_page(mut search: SearchRequest, db: &DatabaseConnection) -> ValueMap {
+    pub async fn find_all_page(
+        mut search: SearchRequest,
+        owner: Option<i64>,
+        db: &DatabaseConnection,
+    ) -> ValueMap {
         if search.get_title().unwrap_or_default() == "" {
             let _ = &search.set_title(None);
         }
@@ -445,6 +619,9 @@ impl BlogService {
             .apply_if(search.get_category_id(), |query, value| {
                 query.filter(blog::Column::CategoryId.eq(value))
             })
+            .apply_if(owner, |query, value| {
+                query.filter(blog::Column::UserId.eq(value))
+            })
             .paginate(db, search.get_page_size() as u64);
 
         let mut map: ValueMap = ValueMap::new();
@@ -488,32 +665,122 @@ impl BlogService {
             .one(db)
             .await?;
         match blog_model {
-            Some(mut blog) => {
-                if v.get_appreciation().is_some() {
-                    blog.is_appreciation = v.get_appreciation().unwrap_or_default();
+            Some(blog) => {
+                let blog_id = blog.id;
+                let password = match v.get_password() {
+                    Some(password) => Some(BlogPasswordService::hash_password(
+                        &password,
+                        blog.password.as_deref(),
+                    )?),
+                    None => None,
+                };
+                let mut active = blog::ActiveModel::from(blog);
+                if let Some(appreciation) = v.get_appreciation() {
+                    active.is_appreciation = ActiveValue::set(appreciation);
                 }
-                if v.get_published().is_some() {
-                    blog.is_published = v.get_published().unwrap_or_default();
+                if let Some((status, publish_at)) = Self::resolve_status(
+                    v.get_status(),
+                    v.get_publish_at(),
+                    v.get_published(),
+                    Local::now().naive_local(),
+                )? {
+                    active.status = ActiveValue::set(status);
+                    active.publish_at = ActiveValue::set(publish_at);
+                    active.is_published = ActiveValue::set(status == BlogStatus::Published);
                 }
-                if v.get_top().is_some() {
-                    blog.is_top = v.get_top().unwrap_or_default();
+                if let Some(top) = v.get_top() {
+                    active.is_top = ActiveValue::set(top);
                 }
-                if v.get_password().is_some() {
-                    blog.password = v.get_password();
+                if let Some(password) = password {
+                    active.password = ActiveValue::set(password);
                 }
-                if v.get_recommend().is_some() {
-                    blog.is_recommend = v.get_recommend().unwrap_or_default();
+                if let Some(recommend) = v.get_recommend() {
+                    active.is_recommend = ActiveValue::set(recommend);
                 }
-                if v.get_comment_enabled().is_some() {
-                    blog.is_comment_enabled = v.get_comment_enabled().unwrap_or_default();
+                if let Some(comment_enabled) = v.get_comment_enabled() {
+                    active.is_comment_enabled = ActiveValue::set(comment_enabled);
                 }
-                blog::ActiveModel::from(blog).update(db).await?;
+                active.update(db).await?;
+                RedisService::evict_cache(&RedisKeyConstant::BLOG_CACHE_KEYS).await;
+                SearchService::sync_blog(blog_id, db).await;
                 return Ok(());
             }
             None => {
-                return Err(DataBaseError::Custom("没有检索到文章".to_string()));
+                return Err(DataBaseError::Validation("没有检索到文章".to_string()));
+            }
+        }
+    }
+
+    /**
+     * 确定文章状态与定时发布时间，未指定status时按published开关处理，两者都未指定时返回None
+     * 定时发布必须指定发布时间，发布时间已过则直接发布
+     */
+    pub(crate) fn resolve_status(
+        status: Option<BlogStatus>,
+        publish_at: Option<NaiveDateTime>,
+        published: Option<bool>,
+        now: NaiveDateTime,
+    ) -> Result<Option<(BlogStatus, Option<NaiveDateTime>)>, DataBaseError> {
+        let status = match (status, published) {
+            (Some(status), _) => status,
+            (None, Some(true)) => BlogStatus::Published,
+            (None, Some(false)) => BlogStatus::Draft,
+            (None, None) => return Ok(None),
+        };
+        match status {
+            BlogStatus::Scheduled => match publish_at {
+                Some(publish_at) if publish_at <= now => {
+                    Ok(Some((BlogStatus::Published, Some(publish_at))))
+                }
+                Some(publish_at) => Ok(Some((BlogStatus::Scheduled, Some(publish_at)))),
+                None => Err(DataBaseError::Validation(
+                    "定时发布需要指定发布时间".to_string(),
+                )),
+            },
+            status => Ok(Some((status, None))),
+        }
+    }
+
+    /**
+     * 发布已到发布时间的定时文章，保留文章的创建时间
+     */
+    pub async fn publish_scheduled_blogs(db: &DatabaseConnection) -> Result<(), DataBaseError> {
+        let now = Local::now().naive_local();
+        let blogs: Vec<(i64, Option<NaiveDateTime>)> = blog::Entity::find()
+            .select_only()
+            .columns([blog::Column::Id, blog::Column::PublishAt])
+            .filter(blog::Column::Status.eq(BlogStatus::Scheduled))
+            .filter(blog::Column::PublishAt.lte(now))
+            .into_tuple()
+            .all(db)
+            .await?;
+        let mut published_ids = vec![];
+        for (blog_id, publish_at) in blogs {
+            //仅更新仍处于定时状态的文章，避免覆盖后台的修改
+            let result = blog::Entity::update_many()
+                .col_expr(blog::Column::Status, Expr::value(BlogStatus::Published))
+                .col_expr(blog::Column::IsPublished, Expr::value(true))
+                .col_expr(
+                    blog::Column::PublishAt,
+                    Expr::value(publish_at.unwrap_or(now)),
+                )
+                .filter(blog::Column::Id.eq(blog_id))
+                .filter(blog::Column::Status.eq(BlogStatus::Scheduled))
+                .exec(db)
+                .await?;
+            if result.rows_affected > 0 {
+                published_ids.push(blog_id);
             }
         }
+        if published_ids.is_empty() {
+            return Ok(());
+        }
+        RedisService::evict_cache(&RedisKeyConstant::BLOG_CACHE_KEYS).await;
+        for blog_id in &published_ids {
+            SearchService::sync_blog(*blog_id, db).await;
+        }
+        log::info!("定时发布文章:{:?}", published_ids);
+        Ok(())
     }
     /**
      * 获取id的文章 -后台
@@ -537,10 +804,39 @@ impl BlogService {
      * 添加或者更新文章
      */
     pub(crate) async fn update_blog(
-        blog_vo: BlogVO,
+        mut blog_vo: BlogVO,
         db: &DatabaseConnection,
     ) -> Result<(), DataBaseError> {
-        let ok = db
+        let now = Local::now().naive_local();
+        let stored = match blog_vo.get_id() == 0 {
+            true => None,
+            false => blog::Entity::find_by_id(blog_vo.get_id()).one(db).await?,
+        };
+        //未指定状态且发布开关未变化时保留原状态，避免定时或归档的文章被改为草稿
+        let published = match &stored {
+            Some(model) if blog_vo.status.is_none() && model.is_published == blog_vo.published => {
+                None
+            }
+            _ => Some(blog_vo.published),
+        };
+        let (status, publish_at) =
+            match Self::resolve_status(blog_vo.status, blog_vo.publish_at, published, now)? {
+                Some(resolved) => resolved,
+                None => stored
+                    .as_ref()
+                    .map(|model| (model.status, model.publish_at))
+                    .unwrap_or_default(),
+            };
+        blog_vo.status = Some(status);
+        blog_vo.publish_at = publish_at;
+        blog_vo.published = status == BlogStatus::Published;
+        //文章密码哈希存储，表单中的密码与库中哈希相同表示未修改
+        let stored_password = stored.and_then(|model| model.password);
+        blog_vo.password = BlogPasswordService::hash_password(
+            &blog_vo.password.clone().unwrap_or_default(),
+            stored_password.as_deref(),
+        )?;
+        let blog_id = db
             .transaction(|conn| {
                 Box::pin(async move {
                     let tag_list = blog_vo.get_tag_list().unwrap_or_default();
@@ -571,7 +867,7 @@ impl BlogService {
                             }
                         }
                     }
-                    let blog_model = blog::Model::from(blog_vo.clone()).into();
+                    let blog_model: blog::ActiveModel = blog::Model::from(blog_vo.clone()).into();
                     match blog_vo.get_id() == 0 {
                         true => {
                             let model = blog::ActiveModel::insert(blog_model, conn).await?;
@@ -589,9 +885,23 @@ impl BlogService {
                                     .exec(conn)
                                     .await?;
                             }
-                            Ok(())
+                            Ok(model.id)
                         }
                         false => {
+                            //修改前保存历史版本
+                            if let Some(old_model) =
+                                blog::Entity::find_by_id(blog_vo.get_id()).one(conn).await?
+                            {
+                                BlogRevisionService::save_revision(&old_model, conn).await?;
+                            }
+                            //Model转换的字段均为Unchanged，需要标记为Set才会更新
+                            let mut blog_model = blog_model.reset_all();
+                            blog_model.update_time = ActiveValue::set(now);
+                            //浏览量与作者以数据库为准，不使用表单中的值
+                            blog_model.views = ActiveValue::NotSet;
+                            blog_model.user_id = ActiveValue::NotSet;
+                            //slug由Markdown导入维护，表单中没有该字段
+                            blog_model.slug = ActiveValue::NotSet;
                             let model = blog::ActiveModel::update(blog_model, conn).await?;
 
                             //1.查询旧的标签
@@ -645,13 +955,15 @@ impl BlogService {
                                         .await?;
                                 }
                             }
-                            Ok(())
+                            Ok(model.id)
                         }
                     }
                 })
             })
             .await?;
-        Ok(ok)
+        RedisService::evict_cache(&RedisKeyConstant::BLOG_CACHE_KEYS).await;
+        SearchService::sync_blog(blog_id, db).await;
+        Ok(())
     }
 
     //比对数组差异并返回
@@ -673,6 +985,27 @@ impl BlogService {
         (add_result, delete_result)
     }
 
+    /**
+     * 校验文章作者，owner为None时不限制(非编辑角色)
+     */
+    pub async fn check_owner(
+        blog_id: i64,
+        owner: Option<i64>,
+        db: &DatabaseConnection,
+    ) -> Result<(), DataBaseError> {
+        let Some(owner) = owner else {
+            return Ok(());
+        };
+        let blog = blog::Entity::find_by_id(blog_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DataBaseError::Validation("没有检索到文章".to_string()))?;
+        if blog.user_id != Some(owner) {
+            return Err(DataBaseError::Validation("只能管理自己的文章".to_string()));
+        }
+        Ok(())
+    }
+
     //删除Blog
     pub async fn delete_by_id(id: i64, db: &DatabaseConnection) -> Result<(), DataBaseError> {
         let result = db
@@ -683,59 +1016,19 @@ impl BlogService {
                         .filter(blog_tag::Column::BlogId.eq(id))
                         .exec(conn)
                         .await?;
+                    blog_revision::Entity::delete_many()
+                        .filter(blog_revision::Column::BlogId.eq(id))
+                        .exec(conn)
+                        .await?;
                     Ok(())
                 })
             })
             .await?;
-
+        RedisService::evict_cache(&RedisKeyConstant::BLOG_CACHE_KEYS).await;
+        SearchService::remove_blog(id);
         Ok(result)
     }
 
-    /**
-     * 搜索博文
-     */
-    pub async fn search_content(
-        mut content: String,
-        db: &DatabaseConnection,
-    ) -> Result<Vec<SearchBlog>, DataBaseError> {
-        let mut find_str = content.clone();
-        find_str.insert_str(0, r"[\u4E00-\u9FA5A-Za-z0-9_,，。\n\s*\r\t]{0,10}");
-        find_str.insert_str(
-            find_str.len(),
-            r"[\u4E00-\u9FA5A-Za-z0-9_,，。\n\s*\r\t]{0,10}",
-        );
-        content.insert_str(0, "%");
-        content.insert_str(content.len(), "%");
-        //构建正则表达式,出现异常则返回异常
-        let regex_builder = regex::RegexBuilder::new(&find_str)
-            .case_insensitive(true)
-            .build()?;
-        let mut models = blog::Entity::find()
-            .filter(blog::Column::IsPublished.eq(true))
-            .filter(blog::Column::Content.contains(content))
-            .all(db)
-            .await?;
-        let mut search_blogs = vec![];
-        for item in models.iter_mut() {
-            let mut search_blog = SearchBlog::from(item.clone());
-            match regex_builder.find(&item.content) {
-                Some(find) => {
-                    search_blog.set_content(
-                        item.content
-                            .get(find.start()..find.end())
-                            .unwrap_or_default()
-                            .to_string(),
-                    );
-                    search_blogs.push(search_blog);
-                }
-                None => {
-                    log::info!("search_blog 未找到关键词:{:?}", find_str);
-                }
-            }
-        }
-        Ok(search_blogs)
-    }
-
     pub async fn check_category_exist_blog(
         category_id: i64,
         db: &DatabaseConnection,
@@ -772,6 +1065,26 @@ impl BlogService {
         Ok(blog_list)
     }
 
+    /**
+     * 批量查询文章ID与标题
+     */
+    pub(crate) async fn find_blog_id_and_titles(
+        db: &DatabaseConnection,
+        blog_ids: Vec<i64>,
+    ) -> Result<Vec<BlogIdAndTitle>, DataBaseError> {
+        if blog_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let blogs = blog::Entity::find()
+            .select_only()
+            .columns([blog::Column::Id, blog::Column::Title])
+            .filter(blog::Column::Id.is_in(blog_ids))
+            .into_model::<BlogIdAndTitle>()
+            .all(db)
+            .await?;
+        Ok(blogs)
+    }
+
     pub(crate) async fn find_blog_id_and_title(
         db: &DatabaseConnection,
         blog_id: i64,
@@ -785,15 +1098,16 @@ impl BlogService {
             Ok(blog_info)
         } else {
             log::error!("没有检索到文章");
-            Err(DataBaseError::Custom("没有检索到文章".to_string()))
+            Err(DataBaseError::Validation("没有检索到文章".to_string()))
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::entity::sea_orm_active_enums::BlogStatus;
     use crate::{constant::BlogInfoConstant, service::BlogService};
-    use chrono::Local;
+    use chrono::{Duration, Local, NaiveDate};
     use rand::Rng;
     // use sea_orm::{DbBackend, EntityTrait, FromQueryResult, Statement};
     // use regex::Regex;
@@ -815,6 +1129,35 @@ mod tests {
         //assert_eq!(delete, vec![]);
     }
 
+    #[test]
+    fn test_resolve_status() {
+        let now = NaiveDate::from_ymd_opt(2024, 5, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        let later = now + Duration::hours(1);
+        assert_eq!(
+            BlogService::resolve_status(None, None, Some(true), now).unwrap(),
+            Some((BlogStatus::Published, None))
+        );
+        assert_eq!(
+            BlogService::resolve_status(None, None, None, now).unwrap(),
+            None
+        );
+        assert_eq!(
+            BlogService::resolve_status(Some(BlogStatus::Scheduled), Some(later), Some(true), now)
+                .unwrap(),
+            Some((BlogStatus::Scheduled, Some(later)))
+        );
+        //发布时间已过则直接发布
+        assert_eq!(
+            BlogService::resolve_status(Some(BlogStatus::Scheduled), Some(now), None, later)
+                .unwrap(),
+            Some((BlogStatus::Published, Some(now)))
+        );
+        assert!(BlogService::resolve_status(Some(BlogStatus::Scheduled), None, None, now).is_err());
+    }
+
     //stdout add : [6], delete : []
     //stdout ["a", "d", "e", "f"]
     //stdout add : [], delete : [1, 2, 3, 4, 5, 6]
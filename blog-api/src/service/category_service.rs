@@ -149,6 +149,7 @@ impl CategoryService {
         }
         .insert(db)
         .await?;
+        RedisService::evict_cache(&RedisKeyConstant::CATEGORY_CACHE_KEYS).await;
         Ok(())
     }
 
@@ -162,6 +163,7 @@ impl CategoryService {
         }
         .update(db)
         .await?;
+        RedisService::evict_cache(&RedisKeyConstant::CATEGORY_CACHE_KEYS).await;
         Ok(())
     }
 
@@ -191,6 +193,7 @@ impl CategoryService {
             return Err(DbErr::Custom("分类下有文章，不能删除".to_string()));
         }
         let result = category::Entity::delete_by_id(id).exec(db).await?;
+        RedisService::evict_cache(&RedisKeyConstant::CATEGORY_CACHE_KEYS).await;
         Ok(result.rows_affected)
     }
 }
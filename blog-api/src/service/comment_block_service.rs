@@ -0,0 +1,128 @@
+use chrono::Local;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, Condition, DatabaseConnection, EntityTrait,
+    QueryFilter, QueryOrder, Set,
+};
+
+use crate::entity::{comment, comment_block};
+use crate::error::DataBaseError;
+use crate::model::CommentBlock;
+
+pub struct CommentBlockService;
+
+impl CommentBlockService {
+    /// 按IP屏蔽
+    pub const TYPE_IP: &'static str = "ip";
+    /// 按邮箱屏蔽
+    pub const TYPE_EMAIL: &'static str = "email";
+
+    /**
+     * 所有黑名单，按创建时间倒序
+     */
+    pub async fn find_blocks(db: &DatabaseConnection) -> Result<Vec<CommentBlock>, DataBaseError> {
+        let models = comment_block::Entity::find()
+            .order_by_desc(comment_block::Column::Id)
+            .all(db)
+            .await?;
+        Ok(models.into_iter().map(CommentBlock::from).collect())
+    }
+
+    /**
+     * 加入黑名单，已存在时返回原记录
+     */
+    pub async fn add_block(
+        block_type: &str,
+        value: &str,
+        reason: Option<String>,
+        db: &DatabaseConnection,
+    ) -> Result<CommentBlock, DataBaseError> {
+        if block_type != Self::TYPE_IP && block_type != Self::TYPE_EMAIL {
+            return Err(DataBaseError::Validation(format!(
+                "黑名单类型不存在:{}",
+                block_type
+            )));
+        }
+        let value = Self::normalize(block_type, value);
+        if value.is_empty() {
+            return Err(DataBaseError::Validation(
+                "屏蔽的IP或邮箱不能为空".to_string(),
+            ));
+        }
+        let exist = comment_block::Entity::find()
+            .filter(comment_block::Column::BlockType.eq(block_type))
+            .filter(comment_block::Column::Value.eq(value.as_str()))
+            .one(db)
+            .await?;
+        if let Some(model) = exist {
+            return Ok(CommentBlock::from(model));
+        }
+        let model = comment_block::ActiveModel {
+            id: NotSet,
+            block_type: Set(block_type.to_string()),
+            value: Set(value),
+            reason: Set(reason.filter(|reason| !reason.trim().is_empty())),
+            create_time: Set(Local::now().naive_local()),
+        }
+        .insert(db)
+        .await?;
+        Ok(CommentBlock::from(model))
+    }
+
+    /**
+     * 评论的IP或邮箱，用于屏蔽该评论的作者
+     */
+    pub async fn find_comment_value(
+        comment_id: i64,
+        block_type: &str,
+        db: &DatabaseConnection,
+    ) -> Result<String, DataBaseError> {
+        let Some(model) = comment::Entity::find_by_id(comment_id).one(db).await? else {
+            return Err(DataBaseError::Validation("评论不存在".to_string()));
+        };
+        Ok(match block_type {
+            Self::TYPE_IP => model.ip.unwrap_or_default(),
+            _ => model.email,
+        })
+    }
+
+    pub async fn delete_block(id: i64, db: &DatabaseConnection) -> Result<u64, DataBaseError> {
+        let result = comment_block::Entity::delete_by_id(id).exec(db).await?;
+        Ok(result.rows_affected)
+    }
+
+    /**
+     * IP或邮箱是否在黑名单中
+     */
+    pub async fn is_blocked(
+        ip: &str,
+        email: &str,
+        db: &DatabaseConnection,
+    ) -> Result<bool, DataBaseError> {
+        let condition = Condition::any()
+            .add(
+                Condition::all()
+                    .add(comment_block::Column::BlockType.eq(Self::TYPE_IP))
+                    .add(comment_block::Column::Value.eq(Self::normalize(Self::TYPE_IP, ip))),
+            )
+            .add(
+                Condition::all()
+                    .add(comment_block::Column::BlockType.eq(Self::TYPE_EMAIL))
+                    .add(comment_block::Column::Value.eq(Self::normalize(Self::TYPE_EMAIL, email))),
+            );
+        let model = comment_block::Entity::find()
+            .filter(condition)
+            .one(db)
+            .await?;
+        Ok(model.is_some())
+    }
+
+    /**
+     * 邮箱不区分大小写
+     */
+    fn normalize(block_type: &str, value: &str) -> String {
+        match block_type {
+            Self::TYPE_EMAIL => value.trim().to_lowercase(),
+            _ => value.trim().to_string(),
+        }
+    }
+}
@@ -0,0 +1,531 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use sea_orm::{DatabaseConnection, EntityTrait};
+
+use crate::app::{CommentConfig, CONFIG};
+use crate::constant::RedisKeyConstant;
+use crate::entity::{blog, comment, sea_orm_active_enums::BlogStatus};
+use crate::error::DataBaseError;
+use crate::model::CommentForm;
+use crate::service::{BlogPasswordService, CommentBlockService, RedisService};
+
+/// 昵称最大长度
+const MAX_NICKNAME_LENGTH: usize = 64;
+/// 评论内容最大长度，与数据库字段长度一致
+const MAX_CONTENT_LENGTH: usize = 255;
+/// 邮箱、网址最大长度
+const MAX_FIELD_LENGTH: usize = 255;
+
+static EMAIL_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[^\s@]+@[^\s@]+\.[^\s@]+$").unwrap());
+static QQ_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[1-9][0-9]{4,10}$").unwrap());
+static LINK_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(https?://|www\.)[a-z0-9\-._~:/?#\[\]@!$&'()*+,;=%]+").unwrap()
+});
+
+/// 按配置文件构建的默认过滤链
+pub static COMMENT_FILTER_CHAIN: LazyLock<CommentFilterChain> =
+    LazyLock::new(|| CommentFilterChain::with_config(&CONFIG.get_comment_config()));
+
+/**
+ * 待过滤的评论及提交者信息
+ */
+pub struct CommentSubmission {
+    pub form: CommentForm,
+    pub ip: String,
+    //访客标识码
+    pub visitor: Option<String>,
+    //加密文章的解锁token
+    pub unlock_token: Option<String>,
+}
+
+/**
+ * 过滤结果：Hold 表示保存但需审核后才公开，Reject 表示拒绝保存，均附带原因
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterResult {
+    Pass,
+    Hold(String),
+    Reject(String),
+}
+
+pub type FilterFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<FilterResult, DataBaseError>> + Send + 'a>>;
+
+/**
+ * 评论过滤器，实现该trait并加入过滤链即可扩展新的检查
+ */
+pub trait CommentFilter: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn check<'a>(
+        &'a self,
+        submission: &'a CommentSubmission,
+        db: &'a DatabaseConnection,
+    ) -> FilterFuture<'a>;
+}
+
+/**
+ * 评论过滤链，按顺序执行：遇到 Reject 立即返回，Hold 会继续执行后续过滤器
+ */
+#[derive(Default)]
+pub struct CommentFilterChain {
+    filters: Vec<Box<dyn CommentFilter>>,
+}
+
+impl CommentFilterChain {
+    pub fn new() -> Self {
+        Self { filters: vec![] }
+    }
+
+    /**
+     * 默认过滤链：蜜罐、黑名单、频率限制、字段校验、屏蔽词、链接数量、人工审核
+     */
+    pub fn with_config(config: &CommentConfig) -> Self {
+        Self::new()
+            .add(HoneypotFilter)
+            .add(BlockedSenderFilter)
+            .add(RateLimitFilter {
+                window: config.rate_window,
+                ip_limit: config.ip_limit,
+                visitor_limit: config.visitor_limit,
+            })
+            .add(FieldFilter)
+            .add(BlocklistFilter::new(
+                &config.blocked_words,
+                &config.blocked_patterns,
+            ))
+            .add(LinkFilter {
+                moderate_links: config.moderate_links,
+                max_links: config.max_links,
+            })
+            .add(ModerationFilter {
+                enabled: config.moderation,
+            })
+    }
+
+    pub fn add(mut self, filter: impl CommentFilter + 'static) -> Self {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    pub async fn run(
+        &self,
+        submission: &CommentSubmission,
+        db: &DatabaseConnection,
+    ) -> Result<FilterResult, DataBaseError> {
+        let mut result = FilterResult::Pass;
+        for filter in self.filters.iter() {
+            match filter.check(submission, db).await? {
+                FilterResult::Pass => {}
+                FilterResult::Hold(reason) => {
+                    log::info!(
+                        "评论需要审核[{}]:{} ip:{}",
+                        filter.name(),
+                        reason,
+                        submission.ip
+                    );
+                    if result == FilterResult::Pass {
+                        result = FilterResult::Hold(reason);
+                    }
+                }
+                FilterResult::Reject(reason) => {
+                    log::warn!(
+                        "评论被拒绝[{}]:{} ip:{}",
+                        filter.name(),
+                        reason,
+                        submission.ip
+                    );
+                    return Ok(FilterResult::Reject(reason));
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+/**
+ * 蜜罐：隐藏字段被填写时视为机器人
+ */
+pub struct HoneypotFilter;
+
+impl CommentFilter for HoneypotFilter {
+    fn name(&self) -> &'static str {
+        "honeypot"
+    }
+
+    fn check<'a>(
+        &'a self,
+        submission: &'a CommentSubmission,
+        _: &'a DatabaseConnection,
+    ) -> FilterFuture<'a> {
+        Box::pin(async move {
+            Ok(match submission.form.homepage.is_empty() {
+                true => FilterResult::Pass,
+                false => FilterResult::Reject("评论失败".to_string()),
+            })
+        })
+    }
+}
+
+/**
+ * 后台加入黑名单的IP或邮箱不能评论
+ */
+pub struct BlockedSenderFilter;
+
+impl CommentFilter for BlockedSenderFilter {
+    fn name(&self) -> &'static str {
+        "blocked_sender"
+    }
+
+    fn check<'a>(
+        &'a self,
+        submission: &'a CommentSubmission,
+        db: &'a DatabaseConnection,
+    ) -> FilterFuture<'a> {
+        Box::pin(async move {
+            let blocked =
+                CommentBlockService::is_blocked(&submission.ip, &submission.form.email, db).await?;
+            Ok(match blocked {
+                true => FilterResult::Reject("你已被禁止评论".to_string()),
+                false => FilterResult::Pass,
+            })
+        })
+    }
+}
+
+/**
+ * 同一IP、同一访客在统计窗口内的评论次数限制，Redis异常时不限制
+ */
+pub struct RateLimitFilter {
+    window: u64,
+    ip_limit: i64,
+    visitor_limit: i64,
+}
+
+impl CommentFilter for RateLimitFilter {
+    fn name(&self) -> &'static str {
+        "rate_limit"
+    }
+
+    fn check<'a>(
+        &'a self,
+        submission: &'a CommentSubmission,
+        _: &'a DatabaseConnection,
+    ) -> FilterFuture<'a> {
+        Box::pin(async move {
+            let mut targets = vec![("ip", submission.ip.as_str(), self.ip_limit)];
+            if let Some(visitor) = submission.visitor.as_deref() {
+                targets.push(("visitor", visitor, self.visitor_limit));
+            }
+            for (kind, value, limit) in targets {
+                let key = format!(
+                    "{}:{}:{}",
+                    RedisKeyConstant::COMMENT_RATE_LIMIT,
+                    kind,
+                    value
+                );
+                match RedisService::increment_with_expire(key, self.window).await {
+                    Ok(count) if count > limit => {
+                        return Ok(FilterResult::Reject("评论过于频繁，请稍后再试".to_string()))
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::error!("记录评论频率失败:{}", e),
+                }
+            }
+            Ok(FilterResult::Pass)
+        })
+    }
+}
+
+/**
+ * 字段校验：格式与长度，以及评论的文章、父评论是否存在且允许评论
+ */
+pub struct FieldFilter;
+
+impl FieldFilter {
+    fn validate(form: &CommentForm) -> Result<(), String> {
+        let nickname = form.nickname.trim();
+        if nickname.is_empty() || nickname.chars().count() > MAX_NICKNAME_LENGTH {
+            return Err(format!(
+                "昵称不能为空且不能超过{}个字符",
+                MAX_NICKNAME_LENGTH
+            ));
+        }
+        let content = form.content.trim();
+        if content.is_empty() || content.chars().count() > MAX_CONTENT_LENGTH {
+            return Err(format!(
+                "评论内容不能为空且不能超过{}个字符",
+                MAX_CONTENT_LENGTH
+            ));
+        }
+        if form.email.len() > MAX_FIELD_LENGTH || !EMAIL_REGEX.is_match(form.email.trim()) {
+            return Err("邮箱格式不正确".to_string());
+        }
+        if let Some(website) = form
+            .website
+            .as_deref()
+            .filter(|website| !website.is_empty())
+        {
+            let website = website.to_lowercase();
+            if website.len() > MAX_FIELD_LENGTH
+                || !(website.starts_with("http://") || website.starts_with("https://"))
+            {
+                return Err("网址格式不正确".to_string());
+            }
+        }
+        if let Some(qq) = form.qq.as_deref().filter(|qq| !qq.is_empty()) {
+            if !QQ_REGEX.is_match(qq) {
+                return Err("QQ号格式不正确".to_string());
+            }
+        }
+        if !(0..=2).contains(&form.page) {
+            return Err("评论页面不正确".to_string());
+        }
+        if form.page == 0 && form.blog_id.is_none() {
+            return Err("文章ID不能为空".to_string());
+        }
+        Ok(())
+    }
+
+    async fn check_target(
+        submission: &CommentSubmission,
+        db: &DatabaseConnection,
+    ) -> Result<Result<(), String>, DataBaseError> {
+        let form = &submission.form;
+        let blog_id = match form.page {
+            0 => form.blog_id,
+            _ => None,
+        };
+        if let Some(blog_id) = blog_id {
+            let Some(blog) = blog::Entity::find_by_id(blog_id).one(db).await? else {
+                return Ok(Err("文章不存在".to_string()));
+            };
+            if !matches!(blog.status, BlogStatus::Published | BlogStatus::Archived) {
+                return Ok(Err("文章不存在".to_string()));
+            }
+            if !blog.is_comment_enabled {
+                return Ok(Err("该文章已关闭评论".to_string()));
+            }
+            if !BlogPasswordService::is_unlocked(
+                blog_id,
+                blog.password.as_deref(),
+                submission.unlock_token.as_deref(),
+            ) {
+                return Ok(Err("文章受密码保护，请验证密码！".to_string()));
+            }
+        }
+        if let Some(parent_id) = form.parent_comment_id.filter(|id| *id != -1) {
+            let parent = comment::Entity::find_by_id(parent_id).one(db).await?;
+            match parent {
+                Some(parent)
+                    if parent.is_published
+                        && parent.page == form.page
+                        && parent.blog_id == blog_id => {}
+                _ => return Ok(Err("回复的评论不存在".to_string())),
+            }
+        }
+        Ok(Ok(()))
+    }
+}
+
+impl CommentFilter for FieldFilter {
+    fn name(&self) -> &'static str {
+        "field"
+    }
+
+    fn check<'a>(
+        &'a self,
+        submission: &'a CommentSubmission,
+        db: &'a DatabaseConnection,
+    ) -> FilterFuture<'a> {
+        Box::pin(async move {
+            if let Err(reason) = Self::validate(&submission.form) {
+                return Ok(FilterResult::Reject(reason));
+            }
+            Ok(match Self::check_target(submission, db).await? {
+                Ok(()) => FilterResult::Pass,
+                Err(reason) => FilterResult::Reject(reason),
+            })
+        })
+    }
+}
+
+/**
+ * 屏蔽词与屏蔽正则，检查昵称、邮箱、网址与评论内容
+ */
+pub struct BlocklistFilter {
+    words: Vec<String>,
+    patterns: Vec<Regex>,
+}
+
+impl BlocklistFilter {
+    /**
+     * 无效的正则只记录日志并忽略
+     */
+    pub fn new(words: &[String], patterns: &[String]) -> Self {
+        Self {
+            words: words
+                .iter()
+                .map(|word| word.trim().to_lowercase())
+                .filter(|word| !word.is_empty())
+                .collect(),
+            patterns: patterns
+                .iter()
+                .filter_map(|pattern| match Regex::new(pattern) {
+                    Ok(regex) => Some(regex),
+                    Err(e) => {
+                        log::error!("评论屏蔽正则:{} 无效:{}", pattern, e);
+                        None
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    fn is_blocked(&self, form: &CommentForm) -> bool {
+        let fields = [
+            form.nickname.as_str(),
+            form.email.as_str(),
+            form.website.as_deref().unwrap_or_default(),
+            form.content.as_str(),
+        ];
+        fields.iter().any(|field| {
+            let lower = field.to_lowercase();
+            self.words.iter().any(|word| lower.contains(word))
+                || self.patterns.iter().any(|pattern| pattern.is_match(field))
+        })
+    }
+}
+
+impl CommentFilter for BlocklistFilter {
+    fn name(&self) -> &'static str {
+        "blocklist"
+    }
+
+    fn check<'a>(
+        &'a self,
+        submission: &'a CommentSubmission,
+        _: &'a DatabaseConnection,
+    ) -> FilterFuture<'a> {
+        Box::pin(async move {
+            Ok(match self.is_blocked(&submission.form) {
+                true => FilterResult::Reject("评论包含屏蔽内容".to_string()),
+                false => FilterResult::Pass,
+            })
+        })
+    }
+}
+
+/**
+ * 评论内容中的链接数量：达到 moderate_links 需要审核，超过 max_links 拒绝
+ */
+pub struct LinkFilter {
+    moderate_links: usize,
+    max_links: usize,
+}
+
+impl LinkFilter {
+    fn count_links(content: &str) -> usize {
+        LINK_REGEX.find_iter(content).count()
+    }
+}
+
+impl CommentFilter for LinkFilter {
+    fn name(&self) -> &'static str {
+        "link"
+    }
+
+    fn check<'a>(
+        &'a self,
+        submission: &'a CommentSubmission,
+        _: &'a DatabaseConnection,
+    ) -> FilterFuture<'a> {
+        Box::pin(async move {
+            let links = Self::count_links(&submission.form.content);
+            Ok(if links > self.max_links {
+                FilterResult::Reject("评论包含的链接过多".to_string())
+            } else if links > 0 && links >= self.moderate_links {
+                FilterResult::Hold(format!("评论包含{}个链接", links))
+            } else {
+                FilterResult::Pass
+            })
+        })
+    }
+}
+
+/**
+ * 开启人工审核时，所有新评论都需要审核
+ */
+pub struct ModerationFilter {
+    enabled: bool,
+}
+
+impl CommentFilter for ModerationFilter {
+    fn name(&self) -> &'static str {
+        "moderation"
+    }
+
+    fn check<'a>(
+        &'a self,
+        _: &'a CommentSubmission,
+        _: &'a DatabaseConnection,
+    ) -> FilterFuture<'a> {
+        Box::pin(async move {
+            Ok(match self.enabled {
+                true => FilterResult::Hold("评论需要人工审核".to_string()),
+                false => FilterResult::Pass,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlocklistFilter, FieldFilter, LinkFilter};
+    use crate::model::CommentForm;
+
+    fn form() -> CommentForm {
+        CommentForm {
+            nickname: "访客".to_string(),
+            email: "guest@example.com".to_string(),
+            content: "写得很好".to_string(),
+            blog_id: Some(1),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_comment_filter() {
+        assert!(FieldFilter::validate(&form()).is_ok());
+        let mut invalid = form();
+        invalid.email = "guest".to_string();
+        assert!(FieldFilter::validate(&invalid).is_err());
+        let mut invalid = form();
+        invalid.website = Some("javascript:alert(1)".to_string());
+        assert!(FieldFilter::validate(&invalid).is_err());
+        let mut invalid = form();
+        invalid.blog_id = None;
+        assert!(FieldFilter::validate(&invalid).is_err());
+
+        assert_eq!(
+            LinkFilter::count_links("见 https://a.com 和 www.b.com，还有http://c.cn/x"),
+            3
+        );
+        assert_eq!(LinkFilter::count_links("没有链接"), 0);
+
+        let blocklist = BlocklistFilter::new(
+            &["Casino".to_string()],
+            &[r"\d{11}".to_string(), "(".to_string()],
+        );
+        let mut blocked = form();
+        blocked.content = "来玩CASINO".to_string();
+        assert!(blocklist.is_blocked(&blocked));
+        blocked.content = "电话13800000000".to_string();
+        assert!(blocklist.is_blocked(&blocked));
+        assert!(!blocklist.is_blocked(&form()));
+    }
+}
@@ -1,94 +1,237 @@
+use crate::app::CONFIG;
+use crate::common::MarkdownParser;
 use crate::entity::comment;
 use crate::error::DataBaseError;
-use crate::model::{CommentDTO, CommentVO};
+use crate::model::{BlogIdAndTitle, CommentDTO, CommentForm, CommentQuery, CommentVO};
 use crate::service::BlogService;
-use chrono::Local;
+use chrono::{Local, NaiveTime};
 use rbs::value;
 use rbs::value::map::ValueMap;
+use sea_orm::sea_query::Expr;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait,
-    IntoActiveModel, PaginatorTrait, QueryFilter, TransactionTrait,
+    ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, Condition, DatabaseConnection, DbErr,
+    EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+    TransactionTrait,
 };
+use std::collections::HashMap;
 //每页显示5条博客简介
 const PAGE_SIZE: u64 = 5;
 
 pub struct CommentService;
 
 impl CommentService {
-    //分页评论
+    /**
+     * 分页评论：一次查询取出文章/页面的全部公开评论，在内存中组装评论树后按根评论分页
+     */
     pub(crate) async fn find_by_id_comments(
         page_num: u64,
         blog_id: i64,
         page: u8,
+        sort: Option<String>,
         db: &DatabaseConnection,
     ) -> Result<ValueMap, DataBaseError> {
         let mut map = ValueMap::new();
         let select_sql = comment::Entity::find()
             .filter(comment::Column::IsPublished.eq(true))
-            .filter(comment::Column::ParentCommentId.eq(-1))
             .filter(comment::Column::Page.eq(page));
-
-        let page_list = {
-            match page == 0 {
-                true => select_sql
-                    .filter(comment::Column::BlogId.eq(blog_id))
-                    .paginate(db, PAGE_SIZE),
-                false => select_sql.paginate(db, PAGE_SIZE),
-            }
-        };
-        let models = page_list.fetch_page(page_num - 1).await?;
-        let mut comments = vec![];
-        for model in models.into_iter() {
-            let id = model.id;
-            let mut comment = CommentVO::from(model);
-            comment.reply_comments = Some(Self::find_comment_vo_by_id(id, db).await?);
-            comments.push(comment);
+        let models = match page == 0 {
+            true => select_sql.filter(comment::Column::BlogId.eq(blog_id)),
+            false => select_sql,
         }
+        .order_by_asc(comment::Column::Id)
+        .all(db)
+        .await?;
+
+        let config = CONFIG.get_comment_config();
+        let sort = CommentSort::from(sort.as_deref().unwrap_or(&config.sort));
+        let comments = Self::build_comment_tree(models, sort, config.max_depth);
+        let total_page = (comments.len() as u64).div_ceil(PAGE_SIZE);
+        let comments: Vec<CommentVO> = comments
+            .into_iter()
+            .skip((page_num.saturating_sub(1) * PAGE_SIZE) as usize)
+            .take(PAGE_SIZE as usize)
+            .collect();
         map.insert("list".into(), value!(comments));
-        map.insert(
-            "totalPage".into(),
-            rbs::Value::U64(page_list.num_pages().await?),
-        );
+        map.insert("totalPage".into(), rbs::Value::U64(total_page));
 
         Ok(map)
     }
 
-    //分页评论
+    /**
+     * 由评论列表组装评论树，返回排好序的根评论
+     * 回复始终按时间先后排列，超过max_depth层的回复平铺到第max_depth层
+     */
+    fn build_comment_tree(
+        models: Vec<comment::Model>,
+        sort: CommentSort,
+        max_depth: usize,
+    ) -> Vec<CommentVO> {
+        let nicknames: HashMap<i64, String> = models
+            .iter()
+            .map(|model| (model.id, model.nickname.clone()))
+            .collect();
+        let mut children: HashMap<i64, Vec<comment::Model>> = HashMap::new();
+        for model in models.into_iter() {
+            children
+                .entry(model.parent_comment_id)
+                .or_default()
+                .push(model);
+        }
+        for replies in children.values_mut() {
+            replies.sort_by(|a, b| a.create_time.cmp(&b.create_time).then(a.id.cmp(&b.id)));
+        }
+
+        let mut roots: Vec<(CommentVO, usize)> = children
+            .remove(&-1)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|model| {
+                let id = model.id;
+                let mut comment = CommentVO::from(model);
+                let replies =
+                    Self::collect_replies(id, 1, max_depth.max(1), &mut children, &nicknames);
+                let count = Self::count_replies(&replies);
+                comment.reply_comments = Some(replies);
+                (comment, count)
+            })
+            .collect();
+        match sort {
+            CommentSort::Oldest => {}
+            CommentSort::Newest => roots.reverse(),
+            //回复数相同时较新的评论在前
+            CommentSort::MostReplied => {
+                roots.reverse();
+                roots.sort_by_key(|root| std::cmp::Reverse(root.1));
+            }
+        }
+        roots.into_iter().map(|(comment, _)| comment).collect()
+    }
+
+    /**
+     * 收集某条评论的回复，depth为回复所在层数，达到max_depth后不再嵌套
+     */
+    fn collect_replies(
+        id: i64,
+        depth: usize,
+        max_depth: usize,
+        children: &mut HashMap<i64, Vec<comment::Model>>,
+        nicknames: &HashMap<i64, String>,
+    ) -> Vec<CommentVO> {
+        let mut replies = vec![];
+        for model in children.remove(&id).unwrap_or_default() {
+            let reply_id = model.id;
+            let mut reply = CommentVO::from(model);
+            reply.parent_comment_name = nicknames.get(&id).cloned();
+            if depth < max_depth {
+                reply.reply_comments = Some(Self::collect_replies(
+                    reply_id,
+                    depth + 1,
+                    max_depth,
+                    children,
+                    nicknames,
+                ));
+                replies.push(reply);
+            } else {
+                replies.push(reply);
+                replies.append(&mut Self::collect_replies(
+                    reply_id, depth, max_depth, children, nicknames,
+                ));
+            }
+        }
+        replies
+    }
+
+    fn count_replies(replies: &[CommentVO]) -> usize {
+        replies
+            .iter()
+            .map(|reply| {
+                1 + Self::count_replies(reply.reply_comments.as_deref().unwrap_or_default())
+            })
+            .sum()
+    }
+
+    /**
+     * 后台分页评论，按筛选条件查询，oldest_first为true时最早的评论在前(审核队列)
+     */
     pub(crate) async fn find_comment_dto(
-        page_num: u64,
-        page_size: u64,
+        query: &CommentQuery,
+        oldest_first: bool,
         db: &DatabaseConnection,
     ) -> Result<ValueMap, DataBaseError> {
         let mut map = ValueMap::new();
-        let page = comment::Entity::find().paginate(db, page_size);
+        let page_num = query.get_page_num();
+        let page_size = query.get_page_size();
+        let select = comment::Entity::find().filter(Self::query_condition(query));
+        let select = match oldest_first {
+            true => select.order_by_asc(comment::Column::Id),
+            false => select.order_by_desc(comment::Column::Id),
+        };
+        let page = select.paginate(db, page_size);
         let models = page.fetch_page(page_num - 1).await?;
-        let mut comments = vec![];
-        for model in models.into_iter() {
-            let blog_id = model.blog_id.unwrap_or_default();
 
-            let mut comment = CommentDTO::from(model);
-            comment.blog_id_and_title =
-                Some(BlogService::find_blog_id_and_title(db, blog_id).await?);
-            comments.push(comment);
-        }
-        map.insert(
-            value!("pageNum"),
-            value!(page.num_pages().await.unwrap_or_default()),
-        );
-        map.insert(value!("pageSize"), value!(PAGE_SIZE));
-        map.insert(
-            value!("pages"),
-            value!(page.num_pages().await.unwrap_or_default()),
-        );
-        map.insert(
-            value!("total"),
-            value!(page.num_items().await.unwrap_or_default()),
-        );
+        let blog_ids: Vec<i64> = models.iter().filter_map(|model| model.blog_id).collect();
+        let blogs: HashMap<i64, BlogIdAndTitle> =
+            BlogService::find_blog_id_and_titles(db, blog_ids)
+                .await?
+                .into_iter()
+                .map(|blog| (blog.id, blog))
+                .collect();
+        let comments: Vec<CommentDTO> = models
+            .into_iter()
+            .map(|model| {
+                let blog = model
+                    .blog_id
+                    .and_then(|blog_id| blogs.get(&blog_id).cloned());
+                let mut comment = CommentDTO::from(model);
+                comment.blog_id_and_title = blog;
+                comment
+            })
+            .collect();
+        let pages = page.num_pages().await?;
+        map.insert(value!("pageNum"), value!(page_num));
+        map.insert(value!("pageSize"), value!(page_size));
+        map.insert(value!("pages"), value!(pages));
+        map.insert(value!("total"), value!(page.num_items().await?));
         map.insert("list".into(), value!(comments));
 
         Ok(map)
     }
 
+    fn query_condition(query: &CommentQuery) -> Condition {
+        let mut condition = Condition::all();
+        if let Some(page) = query.page {
+            condition = condition.add(comment::Column::Page.eq(page));
+        }
+        if let Some(blog_id) = query.blog_id {
+            condition = condition.add(comment::Column::BlogId.eq(blog_id));
+        }
+        if let Some(published) = query.published {
+            condition = condition.add(comment::Column::IsPublished.eq(published));
+        }
+        if let Some(keyword) = query.keyword.as_deref().filter(|s| !s.is_empty()) {
+            condition = condition.add(
+                Condition::any()
+                    .add(comment::Column::Nickname.contains(keyword))
+                    .add(comment::Column::Content.contains(keyword)),
+            );
+        }
+        if let Some(email) = query.email.as_deref().filter(|s| !s.is_empty()) {
+            condition = condition.add(comment::Column::Email.contains(email));
+        }
+        if let Some(ip) = query.ip.as_deref().filter(|s| !s.is_empty()) {
+            condition = condition.add(comment::Column::Ip.contains(ip));
+        }
+        if let Some(start_date) = query.start_date {
+            condition =
+                condition.add(comment::Column::CreateTime.gte(start_date.and_time(NaiveTime::MIN)));
+        }
+        if let Some(end_date) = query.end_date.and_then(|date| date.succ_opt()) {
+            condition =
+                condition.add(comment::Column::CreateTime.lt(end_date.and_time(NaiveTime::MIN)));
+        }
+        condition
+    }
+
     // pub(crate) async fn _find_comment_by_id(
     //     id: i64,
     //     db: &DatabaseConnection,
@@ -130,47 +273,6 @@ impl CommentService {
     //     Ok(reply_comments)
     // }
 
-    pub(crate) async fn find_comment_vo_by_id(
-        id: i64,
-        db: &DatabaseConnection,
-    ) -> Result<Vec<CommentVO>, DataBaseError> {
-        let models = comment::Entity::find()
-            .filter(comment::Column::ParentCommentId.eq(id))
-            .filter(comment::Column::IsPublished.eq(true))
-            .all(db)
-            .await?;
-
-        let mut futures = Vec::new();
-        let mut comments = vec![];
-        for item in models.into_iter() {
-            // 使用 Box::pin 来递归调用 get_comments，允许存在递归
-            let future = Box::pin(Self::find_comment_vo_by_id(item.id, db));
-            futures.push(future);
-            comments.push(CommentVO::from(item));
-        }
-        let mut reply_comments = vec![];
-        // 处理子评论
-        for (item, future) in comments.iter_mut().zip(futures) {
-            if let Ok(future) = future.await.as_mut() {
-                match item.parent_comment_id {
-                    Some(parent_comment_id) => {
-                        let parent_comment = comment::Entity::find_by_id(parent_comment_id)
-                            .one(db)
-                            .await?;
-                        if let Some(parent_comment) = parent_comment {
-                            item.parent_comment_name = Some(parent_comment.nickname);
-                        }
-                    }
-                    None => {}
-                }
-
-                reply_comments.push(item.to_owned());
-                reply_comments.append(future);
-            }
-        }
-        Ok(reply_comments)
-    }
-
     pub(crate) async fn get_all_count(
         blog_id: i64,
         page: u8,
@@ -217,6 +319,7 @@ impl CommentService {
         comment_dto.create_time = Local::now().naive_local(); // 设置创建时间
         if let Some(mut model) = option_model {
             model.avatar = comment_dto.avatar;
+            model.content_html = Some(MarkdownParser::parser_comment_html(&comment_dto.content));
             model.content = comment_dto.content;
             model.email = comment_dto.email.unwrap_or_default();
             model.ip = comment_dto.ip;
@@ -242,67 +345,230 @@ impl CommentService {
         Ok(())
     }
 
-    /// 在事务内部删除评论的辅助方法
-    async fn delete_comment_in_transaction<'a>(
-        id: i64,
-        conn: &'a DatabaseTransaction,
-    ) -> Result<u64, DbErr> {
-        let mut total_deleted = 0u64;
-
-        // 删除当前评论
-        let count = comment::Entity::delete_many()
-            .filter(comment::Column::Id.eq(id))
-            .exec(conn)
-            .await?;
-        total_deleted += count.rows_affected;
-
-        // 查找所有直接子评论
-        let child_comments = comment::Entity::find()
-            .filter(comment::Column::ParentCommentId.eq(id))
-            .all(conn)
-            .await?;
+    /**
+     * 前台新增评论，held为true时需要审核后才公开
+     */
+    pub async fn create_comment(
+        form: CommentForm,
+        ip: String,
+        held: bool,
+        db: &DatabaseConnection,
+    ) -> Result<comment::Model, DataBaseError> {
+        let qq = form.qq.filter(|qq| !qq.is_empty());
+        let avatar = match &qq {
+            Some(qq) => format!("http://q.qlogo.cn/headimg_dl?dst_uin={}&spec=640", qq),
+            None => String::new(),
+        };
+        let model = comment::ActiveModel {
+            id: NotSet,
+            nickname: Set(form.nickname.trim().to_string()),
+            email: Set(form.email.trim().to_string()),
+            content_html: Set(Some(MarkdownParser::parser_comment_html(
+                form.content.trim(),
+            ))),
+            content: Set(form.content.trim().to_string()),
+            avatar: Set(avatar),
+            create_time: Set(Some(Local::now().naive_local())),
+            ip: Set(Some(ip)),
+            is_published: Set(!held),
+            is_admin_comment: Set(false),
+            page: Set(form.page),
+            is_notice: Set(form.notice),
+            blog_id: Set(form.blog_id.filter(|_| form.page == 0)),
+            parent_comment_id: Set(form.parent_comment_id.unwrap_or(-1)),
+            website: Set(form.website.filter(|website| !website.is_empty())),
+            qq: Set(qq),
+        }
+        .insert(db)
+        .await?;
+        Ok(model)
+    }
 
-        // 递归删除每个子评论
-        for child in child_comments {
-            // 创建一个新的异步块，确保它可以被发送到其他线程
-            let child_id = child.id;
-            let child_count = async {
-                // 使用事务的克隆而不是直接引用
-                let tx = conn.begin().await?;
-                let result = Box::pin(Self::delete_comment_in_transaction(child_id, &tx)).await;
-                tx.commit().await?;
-                result
+    /**
+     * 批量公开或隐藏评论，返回由待审核变为公开的评论(用于发送回复通知)
+     */
+    pub async fn update_published(
+        ids: Vec<i64>,
+        published: bool,
+        db: &DatabaseConnection,
+    ) -> Result<Vec<comment::Model>, DataBaseError> {
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let approved = match published {
+            true => {
+                comment::Entity::find()
+                    .filter(comment::Column::Id.is_in(ids.clone()))
+                    .filter(comment::Column::IsPublished.eq(false))
+                    .all(db)
+                    .await?
             }
+            false => vec![],
+        };
+        comment::Entity::update_many()
+            .col_expr(comment::Column::IsPublished, Expr::value(published))
+            .filter(comment::Column::Id.is_in(ids))
+            .exec(db)
             .await?;
-            total_deleted += child_count;
-        }
-
-        Ok(total_deleted)
+        Ok(approved
+            .into_iter()
+            .map(|mut model| {
+                model.is_published = true;
+                model
+            })
+            .collect())
     }
 
+    /**
+     * 删除评论及其所有回复
+     */
     pub async fn delete_comment_recursive(
         id: i64,
         db: &DatabaseConnection,
     ) -> Result<u64, DataBaseError> {
-        // 在一个事务中删除所有评论
+        Self::delete_comments_recursive(vec![id], db).await
+    }
+
+    /**
+     * 批量删除评论及其所有回复：一次查询取出评论的父子关系，在内存中找出全部后代后批量删除
+     */
+    pub async fn delete_comments_recursive(
+        ids: Vec<i64>,
+        db: &DatabaseConnection,
+    ) -> Result<u64, DataBaseError> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
         let result = db
             .transaction(|conn| {
                 Box::pin(async move {
-                    let mut total_deleted = 0u64;
-
+                    let relations: Vec<(i64, i64)> = comment::Entity::find()
+                        .select_only()
+                        .columns([comment::Column::Id, comment::Column::ParentCommentId])
+                        .into_tuple()
+                        .all(conn)
+                        .await?;
+                    let ids = Self::collect_descendants(&ids, &relations);
                     let count = comment::Entity::delete_many()
-                        .filter(comment::Column::Id.eq(id))
+                        .filter(comment::Column::Id.is_in(ids))
                         .exec(conn)
                         .await?;
-                    total_deleted += count.rows_affected;
-
-                    // 查找所有直接子评论
-                    let tal = Self::delete_comment_in_transaction(id, conn).await?;
-
-                    Ok(total_deleted + tal)
+                    Ok::<u64, DbErr>(count.rows_affected)
                 })
             })
             .await?;
         Ok(result)
     }
+
+    /**
+     * 根据(评论ID, 父评论ID)列表找出评论本身及其所有后代
+     */
+    fn collect_descendants(ids: &[i64], relations: &[(i64, i64)]) -> Vec<i64> {
+        let mut children: HashMap<i64, Vec<i64>> = HashMap::new();
+        for (child, parent) in relations {
+            children.entry(*parent).or_default().push(*child);
+        }
+        let mut ids = ids.to_vec();
+        let mut index = 0;
+        while index < ids.len() {
+            if let Some(replies) = children.remove(&ids[index]) {
+                ids.extend(replies);
+            }
+            index += 1;
+        }
+        ids
+    }
+}
+
+/**
+ * 前台评论排序方式
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CommentSort {
+    //最早
+    Oldest,
+    //最新
+    Newest,
+    //回复最多
+    MostReplied,
+}
+
+impl From<&str> for CommentSort {
+    fn from(value: &str) -> Self {
+        match value {
+            "newest" => Self::Newest,
+            "replies" => Self::MostReplied,
+            _ => Self::Oldest,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommentService, CommentSort};
+    use crate::entity::comment;
+    use chrono::DateTime;
+
+    fn comment(id: i64, parent_comment_id: i64) -> comment::Model {
+        comment::Model {
+            id,
+            nickname: format!("访客{}", id),
+            email: String::new(),
+            content: String::new(),
+            content_html: Some(String::new()),
+            avatar: String::new(),
+            create_time: DateTime::from_timestamp(id, 0).map(|time| time.naive_utc()),
+            ip: None,
+            is_published: true,
+            is_admin_comment: false,
+            page: 0,
+            is_notice: false,
+            blog_id: Some(1),
+            parent_comment_id,
+            website: None,
+            qq: None,
+        }
+    }
+
+    #[test]
+    fn test_build_comment_tree() {
+        //1 <- 3 <- 4 <- 5，2 <- 6，7的父评论未公开
+        let models = vec![
+            comment(1, -1),
+            comment(2, -1),
+            comment(3, 1),
+            comment(4, 3),
+            comment(5, 4),
+            comment(6, 2),
+            comment(7, 99),
+        ];
+        let roots = CommentService::build_comment_tree(models.clone(), CommentSort::Oldest, 1);
+        let ids: Vec<i64> = roots.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+        let replies = roots[0].reply_comments.as_ref().unwrap();
+        let ids: Vec<i64> = replies.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![3, 4, 5]);
+        assert_eq!(replies[2].parent_comment_name.as_deref(), Some("访客4"));
+        assert!(replies[2].reply_comments.is_none());
+
+        let roots = CommentService::build_comment_tree(models.clone(), CommentSort::Newest, 2);
+        assert_eq!(roots[0].id, 2);
+        let replies = roots[1].reply_comments.as_ref().unwrap();
+        let nested: Vec<i64> = replies[0]
+            .reply_comments
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|c| c.id)
+            .collect();
+        assert_eq!(nested, vec![4, 5]);
+
+        let roots = CommentService::build_comment_tree(models, CommentSort::MostReplied, 1);
+        assert_eq!(roots[0].id, 1);
+
+        let relations = vec![(1, -1), (3, 1), (4, 3), (5, 4), (6, 2)];
+        let mut ids = CommentService::collect_descendants(&[3, 6], &relations);
+        ids.sort();
+        assert_eq!(ids, vec![3, 4, 5, 6]);
+    }
 }
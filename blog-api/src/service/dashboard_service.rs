@@ -1,8 +1,18 @@
+use chrono::NaiveDate;
+use rbs::value;
 use rbs::value::map::ValueMap;
-use sea_orm::{DatabaseConnection, EntityTrait, PaginatorTrait};
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+use std::collections::HashMap;
 
-use crate::entity::{blog, comment};
-use crate::service::{CategoryService, TagService};
+use crate::entity::{blog, city_visitor, comment, visit_record};
+use crate::model::{CityVisitor, VisitRecord};
+use crate::service::{BlogService, CategoryService, TagService};
+
+//仪表盘展示最近的访问记录天数
+const VISIT_RECORD_DAYS: u64 = 30;
 /**
  * DashboardService 仪表盘
  */
@@ -21,6 +31,55 @@ impl DashboardService {
     pub async fn get_comment_count(db: &DatabaseConnection) -> u64 {
         comment::Entity::find().count(db).await.unwrap_or_default()
     }
+    /**
+     * 获取待审核评论总数
+     */
+    pub async fn get_pending_comment_count(db: &DatabaseConnection) -> u64 {
+        comment::Entity::find()
+            .filter(comment::Column::IsPublished.eq(false))
+            .count(db)
+            .await
+            .unwrap_or_default()
+    }
+
+    /**
+     * 获取各文章待审核评论数量 [{blogId, title, count}]，按数量倒序
+     */
+    pub async fn get_blog_pending_comments(db: &DatabaseConnection) -> Vec<ValueMap> {
+        let counts: Vec<(i64, i64)> = comment::Entity::find()
+            .select_only()
+            .column(comment::Column::BlogId)
+            .column_as(comment::Column::Id.count(), "count")
+            .filter(comment::Column::IsPublished.eq(false))
+            .filter(comment::Column::Page.eq(0))
+            .filter(comment::Column::BlogId.is_not_null())
+            .group_by(comment::Column::BlogId)
+            .order_by_desc(comment::Column::Id.count())
+            .into_tuple()
+            .all(db)
+            .await
+            .unwrap_or_default();
+        let blog_ids = counts.iter().map(|(blog_id, _)| *blog_id).collect();
+        let titles: HashMap<i64, String> = BlogService::find_blog_id_and_titles(db, blog_ids)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|blog| (blog.id, blog.title))
+            .collect();
+        counts
+            .into_iter()
+            .map(|(blog_id, count)| {
+                let mut map = ValueMap::new();
+                map.insert(value!("blogId"), value!(blog_id));
+                map.insert(
+                    value!("title"),
+                    value!(titles.get(&blog_id).cloned().unwrap_or_default()),
+                );
+                map.insert(value!("count"), value!(count));
+                map
+            })
+            .collect()
+    }
     /**
      * 获取分类博文数量
      */
@@ -36,4 +95,52 @@ impl DashboardService {
         //获取分类博文数量
         TagService::get_tags_count(db).await
     }
+
+    /**
+     * 获取最近的访问记录 {date:[], pv:[], uv:[]}
+     */
+    pub async fn get_visit_record(db: &DatabaseConnection) -> ValueMap {
+        let mut records: Vec<VisitRecord> = visit_record::Entity::find()
+            .order_by_desc(visit_record::Column::Date)
+            .limit(VISIT_RECORD_DAYS)
+            .all(db)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(VisitRecord::from)
+            .collect();
+        records.reverse();
+        let mut date = vec![];
+        let mut pv = vec![];
+        let mut uv = vec![];
+        for record in records {
+            //按完整日期保存，图表只展示月-日
+            date.push(
+                NaiveDate::parse_from_str(&record.date, "%Y-%m-%d")
+                    .map(|day| day.format("%m-%d").to_string())
+                    .unwrap_or(record.date),
+            );
+            pv.push(record.pv);
+            uv.push(record.uv);
+        }
+        let mut map = ValueMap::new();
+        map.insert(value!("date"), value!(date));
+        map.insert(value!("pv"), value!(pv));
+        map.insert(value!("uv"), value!(uv));
+        map
+    }
+
+    /**
+     * 获取城市访客数量
+     */
+    pub async fn get_city_visitor(db: &DatabaseConnection) -> Vec<CityVisitor> {
+        city_visitor::Entity::find()
+            .order_by_desc(city_visitor::Column::Uv)
+            .all(db)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(CityVisitor::from)
+            .collect()
+    }
 }
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+
+use actix_web::HttpRequest;
+use chrono::{Local, NaiveDateTime};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, DatabaseConnection, EntityTrait,
+    FromQueryResult, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+};
+
+use crate::common::{IpRegion, UserAgent};
+use crate::entity::exception_log;
+use crate::error::DataBaseError;
+use crate::model::{ExceptionLog, ExceptionLogGroup};
+
+/**
+ * 按异常指纹聚合的统计
+ */
+#[derive(Debug, FromQueryResult)]
+struct FingerprintStat {
+    fingerprint: String,
+    count: i64,
+    latest_id: i64,
+    first_time: NaiveDateTime,
+    last_time: NaiveDateTime,
+}
+
+pub struct ExceptionLogService;
+
+impl ExceptionLogService {
+    /**
+     * 保存异常日志，保存失败只记录日志
+     */
+    pub async fn save_exception_log(
+        req: &HttpRequest,
+        param: Option<String>,
+        description: &str,
+        error: &str,
+        db: &DatabaseConnection,
+    ) {
+        let method = req.method().to_string();
+        let uri = req.path().to_string();
+        let pattern = req.match_pattern().unwrap_or_else(|| uri.clone());
+        let ip = IpRegion::get_request_ip(req);
+        let user_agent = req
+            .headers()
+            .get("User-Agent")
+            .and_then(|h| h.to_str().ok())
+            .unwrap_or("");
+        let user_agent = UserAgent::parse_user_agent(user_agent).await;
+        let exception_log = exception_log::ActiveModel {
+            id: NotSet,
+            fingerprint: Set(Self::fingerprint(&method, &pattern, error)),
+            uri: Set(uri),
+            method: Set(method),
+            param: Set(param),
+            description: Set(Some(description.to_string())),
+            error: Set(Some(error.to_string())),
+            ip_source: Set(Some(
+                IpRegion::search_by_ip(ip.as_str()).unwrap_or_default(),
+            )),
+            ip: Set(Some(ip)),
+            os: Set(Some(user_agent.os.name)),
+            browser: Set(Some(user_agent.browser.name)),
+            create_time: Set(Local::now().naive_local()),
+            user_agent: Set(Some(user_agent.user_agent)),
+        };
+        if let Err(e) = exception_log.insert(db).await {
+            log::error!("保存异常日志失败: {}", e);
+        }
+    }
+
+    /**
+     * 按异常指纹分组分页查询，按最近发生时间倒序
+     */
+    pub async fn find_groups(
+        page_num: u64,
+        page_size: u64,
+        db: &DatabaseConnection,
+    ) -> Result<(u64, Vec<ExceptionLogGroup>), DataBaseError> {
+        let paginator = exception_log::Entity::find()
+            .select_only()
+            .column(exception_log::Column::Fingerprint)
+            .column_as(Expr::col(exception_log::Column::Id).count(), "count")
+            .column_as(Expr::col(exception_log::Column::Id).max(), "latest_id")
+            .column_as(
+                Expr::col(exception_log::Column::CreateTime).min(),
+                "first_time",
+            )
+            .column_as(
+                Expr::col(exception_log::Column::CreateTime).max(),
+                "last_time",
+            )
+            .group_by(exception_log::Column::Fingerprint)
+            .order_by_desc(Expr::col(exception_log::Column::Id).max())
+            .into_model::<FingerprintStat>()
+            .paginate(db, page_size);
+        let total = paginator.num_items().await?;
+        let stats = paginator.fetch_page(page_num.saturating_sub(1)).await?;
+
+        let latest_ids: Vec<i64> = stats.iter().map(|stat| stat.latest_id).collect();
+        let mut latest: HashMap<i64, ExceptionLog> = exception_log::Entity::find()
+            .filter(exception_log::Column::Id.is_in(latest_ids))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|item| (item.id, ExceptionLog::from(item)))
+            .collect();
+        let groups = stats
+            .into_iter()
+            .map(|stat| ExceptionLogGroup {
+                latest: latest.remove(&stat.latest_id),
+                fingerprint: stat.fingerprint,
+                count: stat.count,
+                first_time: stat.first_time,
+                last_time: stat.last_time,
+            })
+            .collect();
+        Ok((total, groups))
+    }
+
+    /**
+     * 分页查询同一指纹的异常日志
+     */
+    pub async fn find_by_fingerprint(
+        fingerprint: &str,
+        page_num: u64,
+        page_size: u64,
+        db: &DatabaseConnection,
+    ) -> Result<(u64, Vec<ExceptionLog>), DataBaseError> {
+        let paginator = exception_log::Entity::find()
+            .filter(exception_log::Column::Fingerprint.eq(fingerprint))
+            .order_by_desc(exception_log::Column::Id)
+            .paginate(db, page_size);
+        let total = paginator.num_items().await?;
+        let logs = paginator
+            .fetch_page(page_num.saturating_sub(1))
+            .await?
+            .into_iter()
+            .map(ExceptionLog::from)
+            .collect();
+        Ok((total, logs))
+    }
+
+    /**
+     * 删除同一指纹的全部异常日志
+     */
+    pub async fn delete_by_fingerprint(
+        fingerprint: &str,
+        db: &DatabaseConnection,
+    ) -> Result<u64, DataBaseError> {
+        let result = exception_log::Entity::delete_many()
+            .filter(exception_log::Column::Fingerprint.eq(fingerprint))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /**
+     * 异常及其全部原因，每个原因一行
+     */
+    pub fn error_chain(error: &dyn std::error::Error) -> String {
+        let mut chain = error.to_string();
+        let mut source = error.source();
+        while let Some(cause) = source {
+            chain.push_str("\nCaused by: ");
+            chain.push_str(&cause.to_string());
+            source = cause.source();
+        }
+        chain
+    }
+
+    /**
+     * 异常指纹：请求方式 + 路由 + 异常首行(数字替换为#)的 FNV-1a 哈希，
+     * 同一接口因不同id等参数产生的同类异常指纹相同
+     */
+    pub fn fingerprint(method: &str, pattern: &str, error: &str) -> String {
+        let first_line = error.lines().next().unwrap_or("");
+        let mut normalized = String::with_capacity(first_line.len());
+        for c in first_line.chars() {
+            if c.is_ascii_digit() {
+                if !normalized.ends_with('#') {
+                    normalized.push('#');
+                }
+            } else {
+                normalized.push(c);
+            }
+        }
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in [method, pattern, normalized.as_str()].join("\n").bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        format!("{:016x}", hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExceptionLogService;
+
+    #[test]
+    fn test_fingerprint() {
+        let a = ExceptionLogService::fingerprint(
+            "GET",
+            "/blog",
+            "MySQL 异常原因：blog 12 not found\nCaused by: x",
+        );
+        let b =
+            ExceptionLogService::fingerprint("GET", "/blog", "MySQL 异常原因：blog 345 not found");
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 16);
+        assert_ne!(
+            a,
+            ExceptionLogService::fingerprint("POST", "/blog", "MySQL 异常原因：blog 1 not found")
+        );
+    }
+}
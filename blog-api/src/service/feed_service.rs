@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+
+use crate::app::CONFIG;
+use crate::common::{Feed, FeedItem, MarkdownParser};
+use crate::entity::sea_orm_active_enums::BlogStatus;
+use crate::entity::{blog, blog_tag, category, tag};
+use crate::error::DataBaseError;
+use crate::service::{MomentService, SiteSettingService};
+
+/// 订阅源条目数量
+const FEED_SIZE: u64 = 20;
+/// 动态标题截取长度
+const MOMENT_TITLE_LEN: usize = 30;
+
+pub struct FeedService;
+
+impl FeedService {
+    /**
+     * 文章订阅源，可按分类名或标签名筛选；分类或标签不存在时返回None
+     */
+    pub async fn find_blog_feed(
+        category_name: Option<String>,
+        tag_name: Option<String>,
+        feed_path: String,
+        db: &DatabaseConnection,
+    ) -> Result<Option<Feed>, DataBaseError> {
+        let (blog_name, author) = Self::find_site_title(db).await?;
+        let front = Self::front_address();
+        let mut feed = Feed {
+            title: blog_name.clone(),
+            description: blog_name.clone(),
+            author,
+            link: front.clone(),
+            feed_url: format!("{}{}", front, feed_path),
+            items: vec![],
+        };
+
+        let mut query = blog::Entity::find().filter(blog::Column::Status.eq(BlogStatus::Published));
+        if let Some(category_name) = category_name {
+            let category_model = category::Entity::find()
+                .filter(category::Column::CategoryName.eq(&category_name))
+                .one(db)
+                .await?;
+            match category_model {
+                Some(category_model) => {
+                    query = query.filter(blog::Column::CategoryId.eq(category_model.id));
+                }
+                None => return Ok(None),
+            }
+            feed.title = format!("{} - 分类:{}", blog_name, category_name);
+            feed.link = format!("{}/category/{}", front, category_name);
+        }
+        if let Some(tag_name) = tag_name {
+            let tag_model = tag::Entity::find()
+                .filter(tag::Column::TagName.eq(&tag_name))
+                .one(db)
+                .await?;
+            match tag_model {
+                Some(tag_model) => {
+                    let blog_ids: Vec<i64> = blog_tag::Entity::find()
+                        .filter(blog_tag::Column::TagId.eq(tag_model.id))
+                        .all(db)
+                        .await?
+                        .into_iter()
+                        .map(|item| item.blog_id)
+                        .collect();
+                    query = query.filter(blog::Column::Id.is_in(blog_ids));
+                }
+                None => return Ok(None),
+            }
+            feed.title = format!("{} - 标签:{}", blog_name, tag_name);
+            feed.link = format!("{}/tag/{}", front, tag_name);
+        }
+        let blogs = query
+            .order_by_desc(blog::Column::CreateTime)
+            .limit(FEED_SIZE)
+            .all(db)
+            .await?;
+
+        //文章的分类与标签
+        let categories: HashMap<i64, String> = category::Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|item| (item.id, item.category_name))
+            .collect();
+        let tags: HashMap<i64, String> = tag::Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|item| (item.id, item.tag_name))
+            .collect();
+        let mut blog_tags: HashMap<i64, Vec<String>> = HashMap::new();
+        let blog_tag_models = blog_tag::Entity::find()
+            .filter(blog_tag::Column::BlogId.is_in(blogs.iter().map(|blog| blog.id)))
+            .all(db)
+            .await?;
+        for item in blog_tag_models {
+            if let Some(tag_name) = tags.get(&item.tag_id) {
+                blog_tags
+                    .entry(item.blog_id)
+                    .or_default()
+                    .push(tag_name.clone());
+            }
+        }
+
+        for blog in blogs {
+            let mut item_categories = vec![];
+            if let Some(category_name) = categories.get(&blog.category_id) {
+                item_categories.push(category_name.clone());
+            }
+            item_categories.extend(blog_tags.remove(&blog.id).unwrap_or_default());
+            let summary = MarkdownParser::parser_html(blog.description.clone());
+            //加密文章仅输出描述
+            let content = if blog.password.clone().unwrap_or_default().is_empty() {
+                MarkdownParser::parser_html(blog.content)
+            } else {
+                summary.clone()
+            };
+            feed.items.push(FeedItem {
+                title: blog.title,
+                link: format!("{}/blog/{}", front, blog.id),
+                summary: Some(summary),
+                content,
+                categories: item_categories,
+                published: Self::to_local(blog.create_time),
+                updated: Self::to_local(blog.update_time),
+            });
+        }
+        Ok(Some(feed))
+    }
+
+    /**
+     * 动态订阅源
+     */
+    pub async fn find_moment_feed(
+        feed_path: String,
+        db: &DatabaseConnection,
+    ) -> Result<Feed, DataBaseError> {
+        let (blog_name, author) = Self::find_site_title(db).await?;
+        let front = Self::front_address();
+        let moments = MomentService::find_latest_public_moments(FEED_SIZE, db).await?;
+        let items = moments
+            .into_iter()
+            .map(|moment| {
+                let text = MarkdownParser::parser_text(&moment.content);
+                let mut title: String = text.chars().take(MOMENT_TITLE_LEN).collect();
+                if title.is_empty() {
+                    title = "动态".to_string();
+                } else if text.chars().count() > MOMENT_TITLE_LEN {
+                    title.push_str("...");
+                }
+                let time = Self::to_local(moment.create_time);
+                FeedItem {
+                    title,
+                    link: format!("{}/moments#{}", front, moment.id),
+                    summary: None,
+                    content: MarkdownParser::parser_html(moment.content),
+                    categories: vec![],
+                    published: time,
+                    updated: time,
+                }
+            })
+            .collect();
+        Ok(Feed {
+            title: format!("{} - 动态", blog_name),
+            description: format!("{} - 动态", blog_name),
+            author,
+            link: format!("{}/moments", front),
+            feed_url: format!("{}{}", front, feed_path),
+            items,
+        })
+    }
+
+    /**
+     * 站点名称与博主昵称
+     */
+    async fn find_site_title(db: &DatabaseConnection) -> Result<(String, String), DataBaseError> {
+        let site_info = serde_json::to_value(SiteSettingService::find_site_info(db).await?)?;
+        let blog_name = site_info["siteInfo"]["blogName"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let author = site_info["introduction"]["name"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        Ok((blog_name, author))
+    }
+
+    fn front_address() -> String {
+        CONFIG
+            .get_server_config()
+            .front_adderss
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    fn to_local(time: NaiveDateTime) -> DateTime<Local> {
+        Local
+            .from_local_datetime(&time)
+            .earliest()
+            .unwrap_or_else(|| Local.from_utc_datetime(&time))
+    }
+}
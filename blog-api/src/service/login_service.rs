@@ -0,0 +1,206 @@
+use chrono::Local;
+use sea_orm::{ActiveModelTrait, ActiveValue::NotSet, DatabaseConnection, Set};
+
+use crate::common::{IpRegion, UserAgent};
+use crate::constant::{LoginConstant, RedisKeyConstant};
+use crate::entity::login_log;
+use crate::error::DataBaseError;
+use crate::service::RedisService;
+
+pub struct LoginService;
+
+impl LoginService {
+    /**
+     * 保存登录日志，保存失败只记录日志
+     */
+    pub async fn save_login_log(
+        username: &str,
+        ip: &str,
+        user_agent: &str,
+        status: bool,
+        description: &str,
+        db: &DatabaseConnection,
+    ) {
+        let user_agent = UserAgent::parse_user_agent(user_agent).await;
+        let login_log = login_log::ActiveModel {
+            id: NotSet,
+            username: Set(username.to_string()),
+            ip: Set(Some(ip.to_string())),
+            ip_source: Set(Some(IpRegion::search_by_ip(ip).unwrap_or_default())),
+            os: Set(Some(user_agent.os.name)),
+            browser: Set(Some(user_agent.browser.name)),
+            status: Set(Some(status)),
+            description: Set(Some(description.to_string())),
+            create_time: Set(Local::now().naive_local()),
+            user_agent: Set(Some(user_agent.user_agent)),
+        };
+        if let Err(e) = login_log.insert(db).await {
+            log::error!("保存登录日志失败: {}", e);
+        }
+    }
+
+    /**
+     * 用户名与IP、IP或用户名处于锁定状态时返回剩余锁定秒数，Redis异常时不限制登录
+     * 从登录成功过的IP登录时不受用户名锁定限制，避免他人恶意锁定账号
+     */
+    pub async fn find_lock_seconds(username: &str, ip: &str) -> Option<u64> {
+        let trusted = Self::is_trusted_ip(username, ip).await;
+        let mut remain = 0;
+        for (kind, value, _) in Self::targets(username, ip, !trusted) {
+            match RedisService::get_ttl(Self::key(RedisKeyConstant::LOGIN_LOCK, kind, &value)).await
+            {
+                Ok(ttl) if ttl > 0 => remain = remain.max(ttl as u64),
+                Ok(_) => {}
+                Err(e) => log::error!("查询登录锁定状态失败:{}", e),
+            }
+        }
+        (remain > 0).then_some(remain)
+    }
+
+    /**
+     * 登录失败：用户名与IP、IP的失败次数分别加1，用户名存在时累计用户名的失败次数，
+     * 达到上限时锁定，返回本次触发的锁定秒数
+     */
+    pub async fn record_failure(username: &str, ip: &str, user_exists: bool) -> Option<u64> {
+        let mut locked = None;
+        for (kind, value, max_failures) in Self::targets(username, ip, user_exists) {
+            match Self::increase_failure(kind, &value, max_failures).await {
+                Ok(Some(seconds)) => locked = Some(locked.unwrap_or(0).max(seconds)),
+                Ok(None) => {}
+                Err(e) => log::error!("记录登录失败次数失败:{}", e),
+            }
+        }
+        locked
+    }
+
+    /**
+     * 登录成功：清除失败次数和锁定次数，并记录该IP为登录成功过的IP
+     */
+    pub async fn clear_failures(username: &str, ip: &str) {
+        for (kind, value, _) in Self::targets(username, ip, true) {
+            for prefix in [
+                RedisKeyConstant::LOGIN_FAIL_COUNT,
+                RedisKeyConstant::LOGIN_LOCK_LEVEL,
+            ] {
+                if let Err(e) = RedisService::delete_key(Self::key(prefix, kind, &value)).await {
+                    log::error!("清除登录失败次数失败:{}", e);
+                }
+            }
+        }
+        if let Err(e) = RedisService::set_with_expire(
+            Self::key(RedisKeyConstant::LOGIN_TRUSTED_IP, username, ip),
+            LoginConstant::TRUSTED_IP_SECONDS,
+        )
+        .await
+        {
+            log::error!("记录登录IP失败:{}", e);
+        }
+    }
+
+    /**
+     * 第 level 次锁定的时长：从 BASE_LOCK_SECONDS 开始每次翻倍，不超过 MAX_LOCK_SECONDS
+     */
+    pub fn lock_seconds(level: i64) -> u64 {
+        let shift = (level.max(1) - 1).min(32) as u32;
+        LoginConstant::BASE_LOCK_SECONDS
+            .saturating_mul(1 << shift)
+            .min(LoginConstant::MAX_LOCK_SECONDS)
+    }
+
+    async fn increase_failure(
+        kind: &str,
+        value: &str,
+        max_failures: i64,
+    ) -> Result<Option<u64>, DataBaseError> {
+        let fail_key = Self::key(RedisKeyConstant::LOGIN_FAIL_COUNT, kind, value);
+        let count = RedisService::increment_with_expire(
+            fail_key.clone(),
+            LoginConstant::FAIL_WINDOW_SECONDS,
+        )
+        .await?;
+        if count < max_failures {
+            return Ok(None);
+        }
+        let level = RedisService::increment_with_expire(
+            Self::key(RedisKeyConstant::LOGIN_LOCK_LEVEL, kind, value),
+            LoginConstant::LOCK_LEVEL_SECONDS,
+        )
+        .await?;
+        let seconds = Self::lock_seconds(level);
+        RedisService::set_with_expire(
+            Self::key(RedisKeyConstant::LOGIN_LOCK, kind, value),
+            seconds,
+        )
+        .await?;
+        RedisService::delete_key(fail_key).await?;
+        log::warn!(
+            "{}:{} 登录失败{}次，第{}次锁定{}秒",
+            kind,
+            value,
+            count,
+            level,
+            seconds
+        );
+        Ok(Some(seconds))
+    }
+
+    async fn is_trusted_ip(username: &str, ip: &str) -> bool {
+        match RedisService::get_ttl(Self::key(RedisKeyConstant::LOGIN_TRUSTED_IP, username, ip))
+            .await
+        {
+            Ok(ttl) => ttl > 0,
+            Err(e) => {
+                log::error!("查询登录IP失败:{}", e);
+                false
+            }
+        }
+    }
+
+    /**
+     * 登录限制的维度：(类型, 值, 失败次数上限)，with_username 为 false 时不包含用户名维度
+     */
+    fn targets(username: &str, ip: &str, with_username: bool) -> Vec<(&'static str, String, i64)> {
+        let mut targets = vec![
+            (
+                "userIp",
+                format!("{}@{}", username, ip),
+                LoginConstant::MAX_USERNAME_IP_FAILURES,
+            ),
+            ("ip", ip.to_string(), LoginConstant::MAX_IP_FAILURES),
+        ];
+        if with_username {
+            targets.push((
+                "user",
+                username.to_string(),
+                LoginConstant::MAX_USERNAME_FAILURES,
+            ));
+        }
+        targets
+    }
+
+    fn key(prefix: &str, kind: &str, value: &str) -> String {
+        format!("{}:{}:{}", prefix, kind, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LoginService;
+    use crate::constant::LoginConstant;
+
+    #[test]
+    fn test_lock_seconds() {
+        assert_eq!(
+            LoginService::lock_seconds(1),
+            LoginConstant::BASE_LOCK_SECONDS
+        );
+        assert_eq!(
+            LoginService::lock_seconds(3),
+            LoginConstant::BASE_LOCK_SECONDS * 4
+        );
+        assert_eq!(
+            LoginService::lock_seconds(100),
+            LoginConstant::MAX_LOCK_SECONDS
+        );
+    }
+}
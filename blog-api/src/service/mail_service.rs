@@ -0,0 +1,444 @@
+use std::fs;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::LazyLock;
+
+use chrono::Local;
+use hmac::{Hmac, Mac};
+use lettre::message::header::{ContentType, HeaderName, HeaderValue};
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use rand::{rngs::OsRng, RngCore};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, DatabaseConnection, EntityTrait,
+    QueryFilter, Set,
+};
+use sha2::Sha256;
+
+use crate::app::{MailConfig, CONFIG};
+use crate::common::MailTemplate;
+use crate::entity::{blog, comment, mail_unsubscribe};
+use crate::error::DataBaseError;
+
+/// 按配置文件创建的邮件发送方式，未配置邮件时为None
+static MAIL_TRANSPORT: LazyLock<Option<Box<dyn MailTransport>>> = LazyLock::new(|| {
+    let config = CONFIG.get_mail_config()?;
+    let transport: Box<dyn MailTransport> = match config.transport.as_str() {
+        "smtp" => match SmtpMailTransport::new(&config) {
+            Ok(transport) => Box::new(transport),
+            Err(e) => {
+                log::error!("SMTP配置错误，邮件通知不可用:{}", e);
+                return None;
+            }
+        },
+        "file" => Box::new(FileMailTransport {
+            dir: config.file_dir.clone(),
+        }),
+        _ => Box::new(LogMailTransport),
+    };
+    Some(transport)
+});
+
+/// 退订链接签名密钥，未配置时每次启动随机生成
+static UNSUBSCRIBE_SECRET: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let secret = CONFIG
+        .get_mail_config()
+        .map(|config| config.secret)
+        .unwrap_or_default();
+    if !secret.is_empty() {
+        return secret.into_bytes();
+    }
+    log::warn!("未配置 mail.secret，服务重启后已发送的退订链接将失效");
+    let mut secret = vec![0u8; 32];
+    OsRng.fill_bytes(&mut secret);
+    secret
+});
+
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub html: String,
+    /// 退订链接，SMTP发送时写入 List-Unsubscribe 头
+    pub unsubscribe_url: Option<String>,
+}
+
+pub type MailFuture<'a> = Pin<Box<dyn Future<Output = Result<(), DataBaseError>> + Send + 'a>>;
+
+/**
+ * 邮件发送方式，实现该trait即可接入新的发送渠道
+ */
+pub trait MailTransport: Send + Sync {
+    fn send<'a>(&'a self, mail: &'a Mail) -> MailFuture<'a>;
+}
+
+/**
+ * 通过SMTP发送
+ */
+pub struct SmtpMailTransport {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailTransport {
+    pub fn new(config: &MailConfig) -> Result<Self, DataBaseError> {
+        let builder = match config.security.as_str() {
+            "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host),
+            "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host),
+            _ => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+                &config.host,
+            )),
+        }
+        .map_err(|e| DataBaseError::Custom(format!("SMTP配置错误:{}", e)))?
+        .port(config.port);
+        let builder = match config.username.is_empty() {
+            true => builder,
+            false => builder.credentials(Credentials::new(
+                config.username.clone(),
+                config.password.clone(),
+            )),
+        };
+        let from = config
+            .from
+            .parse::<Mailbox>()
+            .map_err(|e| DataBaseError::Custom(format!("发件人格式错误:{}", e)))?;
+        Ok(Self {
+            transport: builder.build(),
+            from,
+        })
+    }
+}
+
+impl MailTransport for SmtpMailTransport {
+    fn send<'a>(&'a self, mail: &'a Mail) -> MailFuture<'a> {
+        Box::pin(async move {
+            let to = mail
+                .to
+                .parse::<Mailbox>()
+                .map_err(|e| DataBaseError::Custom(format!("收件人格式错误:{}", e)))?;
+            let mut builder = Message::builder()
+                .from(self.from.clone())
+                .to(to)
+                .subject(mail.subject.clone())
+                .header(ContentType::TEXT_HTML);
+            //RFC 8058 一键退订：邮件客户端向退订链接发送POST请求
+            if let Some(url) = &mail.unsubscribe_url {
+                builder = builder
+                    .raw_header(HeaderValue::new(
+                        HeaderName::new_from_ascii_str("List-Unsubscribe"),
+                        format!("<{}>", url),
+                    ))
+                    .raw_header(HeaderValue::new(
+                        HeaderName::new_from_ascii_str("List-Unsubscribe-Post"),
+                        "List-Unsubscribe=One-Click".to_string(),
+                    ));
+            }
+            let message = builder
+                .body(mail.html.clone())
+                .map_err(|e| DataBaseError::Custom(format!("邮件内容错误:{}", e)))?;
+            self.transport
+                .send(message)
+                .await
+                .map_err(|e| DataBaseError::Custom(format!("邮件发送失败:{}", e)))?;
+            Ok(())
+        })
+    }
+}
+
+/**
+ * 写入目录，每封邮件一个文件，用于本地测试
+ */
+pub struct FileMailTransport {
+    dir: String,
+}
+
+impl MailTransport for FileMailTransport {
+    fn send<'a>(&'a self, mail: &'a Mail) -> MailFuture<'a> {
+        Box::pin(async move {
+            let name = format!(
+                "{}-{}.eml",
+                Local::now().format("%Y%m%d%H%M%S%f"),
+                mail.to.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+            );
+            let unsubscribe = match &mail.unsubscribe_url {
+                Some(url) => format!(
+                    "List-Unsubscribe: <{}>\r\nList-Unsubscribe-Post: List-Unsubscribe=One-Click\r\n",
+                    url
+                ),
+                None => String::new(),
+            };
+            let content = format!(
+                "To: {}\r\nSubject: {}\r\n{}Content-Type: text/html; charset=utf-8\r\n\r\n{}",
+                mail.to, mail.subject, unsubscribe, mail.html
+            );
+            fs::create_dir_all(&self.dir)
+                .and_then(|_| fs::write(Path::new(&self.dir).join(name), content))
+                .map_err(|e| DataBaseError::Custom(format!("邮件写入失败:{}", e)))
+        })
+    }
+}
+
+/**
+ * 只记录日志
+ */
+pub struct LogMailTransport;
+
+impl MailTransport for LogMailTransport {
+    fn send<'a>(&'a self, mail: &'a Mail) -> MailFuture<'a> {
+        Box::pin(async move {
+            log::info!(
+                "发送邮件 to:{} subject:{}\n{}",
+                mail.to,
+                mail.subject,
+                mail.html
+            );
+            Ok(())
+        })
+    }
+}
+
+pub struct MailService;
+
+impl MailService {
+    /**
+     * 使用模板发送邮件，自动附加退订链接，已退订的邮箱不发送
+     */
+    pub async fn send_template(
+        to: &str,
+        template: &str,
+        vars: &[(&str, &str)],
+        db: &DatabaseConnection,
+    ) -> Result<(), DataBaseError> {
+        let (Some(transport), Some(config)) = (MAIL_TRANSPORT.as_ref(), CONFIG.get_mail_config())
+        else {
+            return Ok(());
+        };
+        if to.is_empty() || Self::is_unsubscribed(to, db).await? {
+            return Ok(());
+        }
+        let unsubscribe_url = Self::unsubscribe_url(&config, to);
+        let mut vars = vars.to_vec();
+        vars.push(("unsubscribe_url", &unsubscribe_url));
+        let (subject, html) =
+            MailTemplate::render(&MailTemplate::load(&config.template_dir, template), &vars);
+        transport
+            .send(&Mail {
+                to: to.to_string(),
+                subject,
+                html,
+                unsubscribe_url: Some(unsubscribe_url),
+            })
+            .await
+    }
+
+    /**
+     * 新评论的邮件通知，在后台发送，失败只记录日志
+     * 博主收到新评论或待审核通知，评论公开时通知被回复的访客
+     */
+    pub fn notify_comment(comment: comment::Model, db: DatabaseConnection) {
+        if MAIL_TRANSPORT.is_none() {
+            return;
+        }
+        actix_web::rt::spawn(async move {
+            if let Err(e) = Self::send_admin_notice(&comment, &db).await {
+                log::error!("发送新评论通知邮件失败:{}", e);
+            }
+            if comment.is_published {
+                if let Err(e) = Self::send_reply_notice(&comment, &db).await {
+                    log::error!("发送评论回复通知邮件失败:{}", e);
+                }
+            }
+        });
+    }
+
+    /**
+     * 审核通过的评论，在后台通知被回复的访客
+     */
+    pub fn notify_approved(comments: Vec<comment::Model>, db: DatabaseConnection) {
+        if MAIL_TRANSPORT.is_none() || comments.is_empty() {
+            return;
+        }
+        actix_web::rt::spawn(async move {
+            for comment in comments.iter() {
+                if let Err(e) = Self::send_reply_notice(comment, &db).await {
+                    log::error!("发送评论回复通知邮件失败:{}", e);
+                }
+            }
+        });
+    }
+
+    async fn send_admin_notice(
+        comment: &comment::Model,
+        db: &DatabaseConnection,
+    ) -> Result<(), DataBaseError> {
+        let admin_email = CONFIG
+            .get_mail_config()
+            .map(|config| config.admin_email)
+            .unwrap_or_default();
+        if admin_email.is_empty() || comment.email.eq_ignore_ascii_case(&admin_email) {
+            return Ok(());
+        }
+        let (title, url) = Self::comment_target(comment, db).await?;
+        let template = match comment.is_published {
+            true => MailTemplate::COMMENT,
+            false => MailTemplate::MODERATION,
+        };
+        Self::send_template(
+            &admin_email,
+            template,
+            &[
+                ("title", &title),
+                ("url", &url),
+                ("nickname", &comment.nickname),
+                ("email", &comment.email),
+                ("ip", comment.ip.as_deref().unwrap_or_default()),
+                (
+                    "content",
+                    comment.content_html.as_deref().unwrap_or_default(),
+                ),
+            ],
+            db,
+        )
+        .await
+    }
+
+    /**
+     * 通知被回复的评论作者，对方未开启邮件提醒或回复自己时不发送
+     */
+    pub async fn send_reply_notice(
+        comment: &comment::Model,
+        db: &DatabaseConnection,
+    ) -> Result<(), DataBaseError> {
+        if comment.parent_comment_id == -1 {
+            return Ok(());
+        }
+        let Some(parent) = comment::Entity::find_by_id(comment.parent_comment_id)
+            .one(db)
+            .await?
+        else {
+            return Ok(());
+        };
+        if !parent.is_notice || parent.email.eq_ignore_ascii_case(&comment.email) {
+            return Ok(());
+        }
+        let (title, url) = Self::comment_target(comment, db).await?;
+        Self::send_template(
+            &parent.email,
+            MailTemplate::REPLY,
+            &[
+                ("title", &title),
+                ("url", &url),
+                ("nickname", &parent.nickname),
+                ("reply_nickname", &comment.nickname),
+                (
+                    "parent_content",
+                    parent.content_html.as_deref().unwrap_or_default(),
+                ),
+                (
+                    "content",
+                    comment.content_html.as_deref().unwrap_or_default(),
+                ),
+            ],
+            db,
+        )
+        .await
+    }
+
+    /**
+     * 退订：校验签名后记录退订邮箱，并关闭该邮箱所有评论的邮件提醒
+     */
+    pub async fn unsubscribe(
+        email: &str,
+        token: &str,
+        db: &DatabaseConnection,
+    ) -> Result<bool, DataBaseError> {
+        if !Self::verify_unsubscribe(email, token) {
+            return Ok(false);
+        }
+        let email = email.trim().to_lowercase();
+        if !Self::is_unsubscribed(&email, db).await? {
+            mail_unsubscribe::ActiveModel {
+                id: NotSet,
+                email: Set(email.clone()),
+                create_time: Set(Local::now().naive_local()),
+            }
+            .insert(db)
+            .await?;
+        }
+        comment::Entity::update_many()
+            .col_expr(comment::Column::IsNotice, false.into())
+            .filter(comment::Column::Email.eq(&email))
+            .exec(db)
+            .await?;
+        Ok(true)
+    }
+
+    /**
+     * 校验退订链接的签名
+     */
+    pub fn verify_unsubscribe(email: &str, token: &str) -> bool {
+        let email = email.trim().to_lowercase();
+        !email.is_empty() && Self::unsubscribe_token(&email) == token
+    }
+
+    async fn is_unsubscribed(email: &str, db: &DatabaseConnection) -> Result<bool, DataBaseError> {
+        Ok(mail_unsubscribe::Entity::find()
+            .filter(mail_unsubscribe::Column::Email.eq(email.trim().to_lowercase()))
+            .one(db)
+            .await?
+            .is_some())
+    }
+
+    /**
+     * 评论所在页面的标题与前端链接
+     */
+    async fn comment_target(
+        comment: &comment::Model,
+        db: &DatabaseConnection,
+    ) -> Result<(String, String), DataBaseError> {
+        let front = CONFIG.get_server_config().front_adderss;
+        let front = front.trim_end_matches('/');
+        Ok(match (comment.page, comment.blog_id) {
+            (1, _) => ("关于我".to_string(), format!("{}/about", front)),
+            (2, _) => ("友人帐".to_string(), format!("{}/friends", front)),
+            (_, Some(blog_id)) => {
+                let title = blog::Entity::find_by_id(blog_id)
+                    .one(db)
+                    .await?
+                    .map(|blog| blog.title)
+                    .unwrap_or_default();
+                (title, format!("{}/blog/{}", front, blog_id))
+            }
+            _ => (String::new(), front.to_string()),
+        })
+    }
+
+    fn unsubscribe_url(config: &MailConfig, email: &str) -> String {
+        let api_address = match config.api_address.is_empty() {
+            true => {
+                let server = CONFIG.get_server_config();
+                format!("http://{}:{}", server.host, server.port)
+            }
+            false => config.api_address.trim_end_matches('/').to_string(),
+        };
+        let email = email.trim().to_lowercase();
+        format!(
+            "{}/unsubscribe?email={}&token={}",
+            api_address,
+            urlencoding::encode(&email),
+            Self::unsubscribe_token(&email)
+        )
+    }
+
+    fn unsubscribe_token(email: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&UNSUBSCRIBE_SECRET)
+            .expect("HMAC可以接受任意长度的密钥");
+        mac.update(email.as_bytes());
+        mac.finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
@@ -0,0 +1,584 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use actix_web::web;
+use chrono::Local;
+use image::{DynamicImage, ImageFormat};
+use rbs::value;
+use rbs::value::map::ValueMap;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, Condition, DatabaseConnection, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set, SqlErr,
+};
+use sha2::{Digest, Sha256};
+
+use super::media_storage::MEDIA_STORAGE;
+use crate::app::CONFIG;
+use crate::entity::{blog, friend, media, moment, site_setting, user};
+use crate::error::DataBaseError;
+use crate::model::{Media, MediaQuery, MediaReference};
+
+/// 未声明或无法识别的文件类型
+const OCTET_STREAM: &str = "application/octet-stream";
+/// 动态没有标题，引用位置显示内容的前几个字
+const MOMENT_TITLE_LENGTH: usize = 30;
+
+/**
+ * 图片尺寸及生成的缩略图、WebP版本
+ */
+struct ImageVariants {
+    width: u32,
+    height: u32,
+    //缩略图内容及类型，图片不超过缩略图尺寸时为None
+    thumbnail: Option<(Vec<u8>, &'static str)>,
+    webp: Option<Vec<u8>>,
+}
+
+pub struct MediaService;
+
+impl MediaService {
+    /**
+     * 上传文件：按文件内容识别类型，内容相同的文件直接返回已有记录，图片生成缩略图与WebP版本
+     */
+    pub async fn upload(
+        file_name: &str,
+        content_type: Option<&str>,
+        bytes: Vec<u8>,
+        user_id: i64,
+        db: &DatabaseConnection,
+    ) -> Result<Media, DataBaseError> {
+        let config = CONFIG.get_media_config();
+        if bytes.is_empty() {
+            return Err(DataBaseError::Validation("文件不能为空".to_string()));
+        }
+        let mime_type = Self::detect_mime(&bytes)
+            .filter(|mime_type| config.allowed_types.iter().any(|t| t == mime_type))
+            .ok_or_else(|| DataBaseError::Validation("不支持的文件类型".to_string()))?;
+        let declared = content_type.map(Self::normalize_mime);
+        if declared.is_some_and(|declared| declared != OCTET_STREAM && declared != mime_type) {
+            return Err(DataBaseError::Validation(
+                "文件类型与文件内容不符".to_string(),
+            ));
+        }
+
+        let hash = format!("{:x}", Sha256::digest(&bytes));
+        let exist = media::Entity::find()
+            .filter(media::Column::Hash.eq(hash.as_str()))
+            .one(db)
+            .await?;
+        if let Some(model) = exist {
+            return Ok(Self::to_medias(vec![model], db).await?.remove(0));
+        }
+
+        let variants = match Self::image_format(mime_type) {
+            Some(format) => {
+                let data = bytes.clone();
+                let size = config.thumbnail_size;
+                //图片解码与编码比较耗时，在线程池中执行
+                let variants = web::block(move || Self::image_variants(&data, format, size))
+                    .await
+                    .map_err(|e| DataBaseError::Custom(format!("图片处理失败:{}", e)))??;
+                Some(variants)
+            }
+            None => None,
+        };
+
+        let storage = &MEDIA_STORAGE;
+        let dir = Local::now().format("%Y/%m").to_string();
+        let file_key = format!("{}/{}.{}", dir, hash, Self::extension(mime_type));
+        storage.put(&file_key, &bytes, mime_type).await?;
+        let (mut thumbnail_key, mut webp_key) = (None, None);
+        if let Some(variants) = &variants {
+            if let Some((thumbnail, thumbnail_type)) = &variants.thumbnail {
+                let key = format!("{}/{}_thumb.{}", dir, hash, Self::extension(thumbnail_type));
+                storage.put(&key, thumbnail, thumbnail_type).await?;
+                thumbnail_key = Some(key);
+            }
+            if let Some(webp) = &variants.webp {
+                let key = format!("{}/{}.webp", dir, hash);
+                storage.put(&key, webp, "image/webp").await?;
+                webp_key = Some(key);
+            }
+        }
+
+        let keys = [
+            Some(file_key.clone()),
+            thumbnail_key.clone(),
+            webp_key.clone(),
+        ];
+        let result = media::ActiveModel {
+            id: NotSet,
+            file_name: Set(Self::clean_file_name(file_name)),
+            file_key: Set(file_key.clone()),
+            thumbnail_key: Set(thumbnail_key),
+            webp_key: Set(webp_key),
+            storage: Set(storage.name().to_string()),
+            mime_type: Set(mime_type.to_string()),
+            size: Set(bytes.len() as i64),
+            width: Set(variants.as_ref().map(|variants| variants.width as i32)),
+            height: Set(variants.as_ref().map(|variants| variants.height as i32)),
+            hash: Set(hash.clone()),
+            user_id: Set(user_id),
+            create_time: Set(Local::now().naive_local()),
+        }
+        .insert(db)
+        .await;
+        let model = match result {
+            Ok(model) => model,
+            //同时上传相同内容时，唯一索引冲突的一方返回先写入的记录
+            Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+                let exist = media::Entity::find()
+                    .filter(media::Column::Hash.eq(hash.as_str()))
+                    .one(db)
+                    .await?
+                    .ok_or(e)?;
+                //跨月上传时文件路径不同，删除本次写入的文件
+                if exist.file_key != file_key {
+                    for key in keys.into_iter().flatten() {
+                        if let Err(e) = storage.delete(&key).await {
+                            log::error!("删除媒体文件{}失败:{}", key, e);
+                        }
+                    }
+                }
+                exist
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self::to_medias(vec![model], db).await?.remove(0))
+    }
+
+    /**
+     * 分页查询媒体文件，按上传时间倒序
+     */
+    pub async fn find_media(
+        query: &MediaQuery,
+        db: &DatabaseConnection,
+    ) -> Result<ValueMap, DataBaseError> {
+        let mut map = ValueMap::new();
+        let page_num = query.get_page_num();
+        let page_size = query.get_page_size();
+        let mut condition = Condition::all();
+        if let Some(keyword) = query.keyword.as_deref().filter(|s| !s.is_empty()) {
+            condition = condition.add(media::Column::FileName.contains(keyword));
+        }
+        if let Some(mime_type) = query.mime_type.as_deref().filter(|s| !s.is_empty()) {
+            condition = condition.add(media::Column::MimeType.starts_with(mime_type));
+        }
+        if let Some(user_id) = query.user_id {
+            condition = condition.add(media::Column::UserId.eq(user_id));
+        }
+        let page = media::Entity::find()
+            .filter(condition)
+            .order_by_desc(media::Column::Id)
+            .paginate(db, page_size);
+        let models = page.fetch_page(page_num - 1).await?;
+        let medias = Self::to_medias(models, db).await?;
+        map.insert(value!("pageNum"), value!(page_num));
+        map.insert(value!("pageSize"), value!(page_size));
+        map.insert(value!("pages"), value!(page.num_pages().await?));
+        map.insert(value!("total"), value!(page.num_items().await?));
+        map.insert("list".into(), value!(medias));
+        Ok(map)
+    }
+
+    /**
+     * 删除文件，仍被文章、动态等引用的文件不能删除，owner 不为空时只能删除自己上传的文件
+     */
+    pub async fn delete_media(
+        id: i64,
+        owner: Option<i64>,
+        db: &DatabaseConnection,
+    ) -> Result<(), DataBaseError> {
+        let Some(model) = media::Entity::find_by_id(id).one(db).await? else {
+            return Err(DataBaseError::Validation("文件不存在".to_string()));
+        };
+        if owner.is_some_and(|owner| owner != model.user_id) {
+            return Err(DataBaseError::Validation(
+                "只能删除自己上传的文件".to_string(),
+            ));
+        }
+        let references = Self::find_references(std::slice::from_ref(&model.hash), db)
+            .await?
+            .remove(&model.hash)
+            .unwrap_or_default();
+        if !references.is_empty() {
+            let names: Vec<String> = references
+                .iter()
+                .map(|reference| {
+                    format!(
+                        "{}《{}》",
+                        Self::reference_name(&reference.reference_type),
+                        reference.title
+                    )
+                })
+                .collect();
+            return Err(DataBaseError::Validation(format!(
+                "文件正在被使用，无法删除:{}",
+                names.join("、")
+            )));
+        }
+        media::Entity::delete_by_id(id).exec(db).await?;
+
+        //记录已删除，文件删除失败只记录日志
+        if model.storage != MEDIA_STORAGE.name() {
+            log::warn!(
+                "媒体文件{}保存在{}中，与当前存储方式不同，请手动删除",
+                model.file_key,
+                model.storage
+            );
+            return Ok(());
+        }
+        let keys = [Some(model.file_key), model.thumbnail_key, model.webp_key];
+        for key in keys.into_iter().flatten() {
+            if let Err(e) = MEDIA_STORAGE.delete(&key).await {
+                log::error!("删除媒体文件{}失败:{}", key, e);
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * 本地存储的文件路径，路径不合法或不是本地存储时返回None
+     */
+    pub fn local_path(key: &str) -> Option<PathBuf> {
+        if !Self::is_valid_key(key) {
+            return None;
+        }
+        MEDIA_STORAGE.local_path(key)
+    }
+
+    /**
+     * 生成访问地址，并查询上传者昵称与引用位置
+     */
+    async fn to_medias(
+        models: Vec<media::Model>,
+        db: &DatabaseConnection,
+    ) -> Result<Vec<Media>, DataBaseError> {
+        let user_ids: Vec<i64> = models.iter().map(|model| model.user_id).collect();
+        let uploaders: HashMap<i64, String> = user::Entity::find()
+            .select_only()
+            .columns([user::Column::Id, user::Column::Nickname])
+            .filter(user::Column::Id.is_in(user_ids))
+            .into_tuple::<(i64, String)>()
+            .all(db)
+            .await?
+            .into_iter()
+            .collect();
+        let hashes: Vec<String> = models.iter().map(|model| model.hash.clone()).collect();
+        let mut references = Self::find_references(&hashes, db).await?;
+        let medias = models
+            .into_iter()
+            .map(|model| Media {
+                id: model.id,
+                file_name: model.file_name,
+                url: MEDIA_STORAGE.url(&model.file_key),
+                thumbnail_url: model.thumbnail_key.map(|key| MEDIA_STORAGE.url(&key)),
+                webp_url: model.webp_key.map(|key| MEDIA_STORAGE.url(&key)),
+                mime_type: model.mime_type,
+                size: model.size,
+                width: model.width,
+                height: model.height,
+                uploader: uploaders.get(&model.user_id).cloned(),
+                user_id: model.user_id,
+                create_time: model.create_time,
+                references: references.remove(&model.hash).unwrap_or_default(),
+                hash: model.hash,
+            })
+            .collect();
+        Ok(medias)
+    }
+
+    /**
+     * 按文件哈希查找引用位置，文件名中包含哈希，因此原图、缩略图、WebP版本的引用都能找到
+     */
+    async fn find_references(
+        hashes: &[String],
+        db: &DatabaseConnection,
+    ) -> Result<HashMap<String, Vec<MediaReference>>, DataBaseError> {
+        let mut references: HashMap<String, Vec<MediaReference>> = HashMap::new();
+        if hashes.is_empty() {
+            return Ok(references);
+        }
+        //(类型, ID, 标题, 引用文件的内容)
+        let mut rows: Vec<(&str, i64, String, String)> = vec![];
+        let blogs = blog::Entity::find()
+            .select_only()
+            .columns([
+                blog::Column::Id,
+                blog::Column::Title,
+                blog::Column::FirstPicture,
+                blog::Column::Content,
+            ])
+            .filter(Self::contains_any(
+                &[blog::Column::FirstPicture, blog::Column::Content],
+                hashes,
+            ))
+            .into_tuple::<(i64, String, String, String)>()
+            .all(db)
+            .await?;
+        for (id, title, first_picture, content) in blogs {
+            rows.push(("blog", id, title, first_picture + &content));
+        }
+        let moments = moment::Entity::find()
+            .select_only()
+            .columns([moment::Column::Id, moment::Column::Content])
+            .filter(Self::contains_any(&[moment::Column::Content], hashes))
+            .into_tuple::<(i64, String)>()
+            .all(db)
+            .await?;
+        for (id, content) in moments {
+            let title = content.chars().take(MOMENT_TITLE_LENGTH).collect();
+            rows.push(("moment", id, title, content));
+        }
+        let friends = friend::Entity::find()
+            .select_only()
+            .columns([
+                friend::Column::Id,
+                friend::Column::Nickname,
+                friend::Column::Avatar,
+            ])
+            .filter(Self::contains_any(&[friend::Column::Avatar], hashes))
+            .into_tuple::<(i64, String, String)>()
+            .all(db)
+            .await?;
+        for (id, nickname, avatar) in friends {
+            rows.push(("friend", id, nickname, avatar));
+        }
+        let users = user::Entity::find()
+            .select_only()
+            .columns([
+                user::Column::Id,
+                user::Column::Nickname,
+                user::Column::Avatar,
+            ])
+            .filter(Self::contains_any(&[user::Column::Avatar], hashes))
+            .into_tuple::<(i64, String, String)>()
+            .all(db)
+            .await?;
+        for (id, nickname, avatar) in users {
+            rows.push(("user", id, nickname, avatar));
+        }
+        let settings = site_setting::Entity::find()
+            .select_only()
+            .columns([
+                site_setting::Column::Id,
+                site_setting::Column::NameZh,
+                site_setting::Column::Value,
+            ])
+            .filter(Self::contains_any(&[site_setting::Column::Value], hashes))
+            .into_tuple::<(i64, Option<String>, Option<String>)>()
+            .all(db)
+            .await?;
+        for (id, name, value) in settings {
+            rows.push((
+                "setting",
+                id,
+                name.unwrap_or_default(),
+                value.unwrap_or_default(),
+            ));
+        }
+
+        for (reference_type, id, title, text) in rows {
+            for hash in hashes.iter().filter(|hash| text.contains(hash.as_str())) {
+                references
+                    .entry(hash.clone())
+                    .or_default()
+                    .push(MediaReference {
+                        reference_type: reference_type.to_string(),
+                        id,
+                        title: title.clone(),
+                    });
+            }
+        }
+        Ok(references)
+    }
+
+    fn contains_any<C: ColumnTrait>(columns: &[C], hashes: &[String]) -> Condition {
+        columns.iter().fold(Condition::any(), |condition, column| {
+            hashes.iter().fold(condition, |condition, hash| {
+                condition.add(column.contains(hash))
+            })
+        })
+    }
+
+    fn reference_name(reference_type: &str) -> &'static str {
+        match reference_type {
+            "blog" => "文章",
+            "moment" => "动态",
+            "friend" => "友链",
+            "user" => "用户头像",
+            _ => "站点设置",
+        }
+    }
+
+    /**
+     * 读取图片尺寸并生成缩略图与WebP版本
+     */
+    fn image_variants(
+        bytes: &[u8],
+        format: ImageFormat,
+        thumbnail_size: u32,
+    ) -> Result<ImageVariants, DataBaseError> {
+        let image = image::load_from_memory_with_format(bytes, format)
+            .map_err(|e| DataBaseError::Validation(format!("图片解析失败:{}", e)))?;
+        let thumbnail = match image.width() > thumbnail_size || image.height() > thumbnail_size {
+            true => {
+                //GIF缩略图只保留第一帧，保存为PNG
+                let thumbnail_type = match format {
+                    ImageFormat::Jpeg => "image/jpeg",
+                    ImageFormat::WebP => "image/webp",
+                    _ => "image/png",
+                };
+                let thumbnail = image.thumbnail(thumbnail_size, thumbnail_size);
+                Some((Self::encode(&thumbnail, thumbnail_type)?, thumbnail_type))
+            }
+            false => None,
+        };
+        //GIF转换后会丢失动画，WebP版本比原图大时也不保存
+        let webp = match format {
+            ImageFormat::Jpeg | ImageFormat::Png => Some(Self::encode(&image, "image/webp")?),
+            _ => None,
+        }
+        .filter(|webp| webp.len() < bytes.len());
+        Ok(ImageVariants {
+            width: image.width(),
+            height: image.height(),
+            thumbnail,
+            webp,
+        })
+    }
+
+    fn encode(image: &DynamicImage, mime_type: &str) -> Result<Vec<u8>, DataBaseError> {
+        //JPEG不支持透明通道，WebP只支持8位RGB(A)
+        let (image, format) = match mime_type {
+            "image/jpeg" => (DynamicImage::from(image.to_rgb8()), ImageFormat::Jpeg),
+            "image/webp" => (DynamicImage::from(image.to_rgba8()), ImageFormat::WebP),
+            _ => (DynamicImage::from(image.to_rgba8()), ImageFormat::Png),
+        };
+        let mut bytes = Cursor::new(vec![]);
+        image
+            .write_to(&mut bytes, format)
+            .map_err(|e| DataBaseError::Custom(format!("图片转换失败:{}", e)))?;
+        Ok(bytes.into_inner())
+    }
+
+    /**
+     * 按文件头识别文件类型，不信任客户端声明的类型与扩展名
+     */
+    fn detect_mime(bytes: &[u8]) -> Option<&'static str> {
+        match bytes {
+            [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+            [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Some("image/png"),
+            [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some("image/gif"),
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+            [b'%', b'P', b'D', b'F', b'-', ..] => Some("application/pdf"),
+            _ => None,
+        }
+    }
+
+    fn normalize_mime(content_type: &str) -> &str {
+        let mime_type = content_type.split(';').next().unwrap_or_default().trim();
+        match mime_type {
+            "image/jpg" | "image/pjpeg" => "image/jpeg",
+            _ => mime_type,
+        }
+    }
+
+    fn image_format(mime_type: &str) -> Option<ImageFormat> {
+        match mime_type {
+            "image/jpeg" => Some(ImageFormat::Jpeg),
+            "image/png" => Some(ImageFormat::Png),
+            "image/gif" => Some(ImageFormat::Gif),
+            "image/webp" => Some(ImageFormat::WebP),
+            _ => None,
+        }
+    }
+
+    fn extension(mime_type: &str) -> &'static str {
+        match mime_type {
+            "image/jpeg" => "jpg",
+            "image/png" => "png",
+            "image/gif" => "gif",
+            "image/webp" => "webp",
+            "application/pdf" => "pdf",
+            _ => "bin",
+        }
+    }
+
+    /**
+     * 只保留文件名，去掉客户端传入的路径
+     */
+    fn clean_file_name(file_name: &str) -> String {
+        let name = file_name
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or_default()
+            .trim();
+        match name.is_empty() {
+            true => "untitled".to_string(),
+            false => name.chars().take(255).collect(),
+        }
+    }
+
+    /**
+     * 存储路径只能包含字母、数字与 . _ -，防止读取存储目录以外的文件
+     */
+    fn is_valid_key(key: &str) -> bool {
+        key.split('/').all(|segment| {
+            !segment.is_empty()
+                && !segment.starts_with('.')
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MediaService;
+
+    #[test]
+    fn test_detect_mime() {
+        assert_eq!(
+            MediaService::detect_mime(&[0xFF, 0xD8, 0xFF, 0xE0, 0x00]),
+            Some("image/jpeg")
+        );
+        assert_eq!(
+            MediaService::detect_mime(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+            Some("image/png")
+        );
+        assert_eq!(
+            MediaService::detect_mime(b"GIF89a\x01\0"),
+            Some("image/gif")
+        );
+        assert_eq!(
+            MediaService::detect_mime(b"RIFF\x24\0\0\0WEBPVP8 "),
+            Some("image/webp")
+        );
+        assert_eq!(
+            MediaService::detect_mime(b"%PDF-1.7"),
+            Some("application/pdf")
+        );
+        //SVG可能包含脚本，不允许上传
+        assert_eq!(MediaService::detect_mime(b"<svg xmlns="), None);
+        assert_eq!(MediaService::detect_mime(b"RIFF"), None);
+
+        assert_eq!(MediaService::normalize_mime("image/jpg"), "image/jpeg");
+        assert_eq!(
+            MediaService::normalize_mime("image/png; charset=binary"),
+            "image/png"
+        );
+        assert_eq!(
+            MediaService::clean_file_name("C:\\Users\\a\\photo.png"),
+            "photo.png"
+        );
+
+        assert!(MediaService::is_valid_key("2026/10/0a1b_thumb.webp"));
+        assert!(!MediaService::is_valid_key("../config/server_config.yaml"));
+        assert!(!MediaService::is_valid_key("2026//a.png"));
+        assert!(!MediaService::is_valid_key("2026/.env"));
+        assert!(!MediaService::is_valid_key("2026/a b.png"));
+    }
+}
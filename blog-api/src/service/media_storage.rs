@@ -0,0 +1,300 @@
+use std::fs;
+use std::future::Future;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::LazyLock;
+
+use actix_web::web;
+
+use crate::app::{MediaConfig, CONFIG};
+use crate::error::DataBaseError;
+
+/// 按配置文件创建的媒体文件存储方式
+pub static MEDIA_STORAGE: LazyLock<Box<dyn MediaStorage>> = LazyLock::new(|| {
+    let config = CONFIG.get_media_config();
+    match config.storage.as_str() {
+        #[cfg(feature = "s3")]
+        "s3" => Box::new(s3::S3MediaStorage::new(&config)),
+        #[cfg(not(feature = "s3"))]
+        "s3" => {
+            log::error!("未启用 s3 feature，媒体库使用本地存储");
+            Box::new(LocalMediaStorage::new(&config))
+        }
+        _ => Box::new(LocalMediaStorage::new(&config)),
+    }
+});
+
+//S3客户端不是Send的，存储操作只在请求处理线程中执行
+pub type StorageFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, DataBaseError>> + 'a>>;
+
+/**
+ * 媒体文件存储方式，实现该trait即可接入新的存储服务，key 为 年/月/文件名
+ */
+pub trait MediaStorage: Send + Sync {
+    /// 存储方式名称，记录在 media.storage 中
+    fn name(&self) -> &'static str;
+
+    /// 文件的访问地址
+    fn url(&self, key: &str) -> String;
+
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        bytes: &'a [u8],
+        content_type: &'a str,
+    ) -> StorageFuture<'a, ()>;
+
+    /// 删除文件，文件不存在时不报错
+    fn delete<'a>(&'a self, key: &'a str) -> StorageFuture<'a, ()>;
+
+    /// 文件的本地路径，由服务端提供访问的存储方式才需要实现
+    fn local_path(&self, _key: &str) -> Option<PathBuf> {
+        None
+    }
+}
+
+/**
+ * 保存到本地目录，通过 /upload 路由访问
+ */
+pub struct LocalMediaStorage {
+    dir: PathBuf,
+    public_url: String,
+}
+
+impl LocalMediaStorage {
+    pub fn new(config: &MediaConfig) -> Self {
+        Self {
+            dir: PathBuf::from(&config.local_dir),
+            public_url: config.public_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+impl MediaStorage for LocalMediaStorage {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/{}", self.public_url, key)
+    }
+
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        bytes: &'a [u8],
+        _content_type: &'a str,
+    ) -> StorageFuture<'a, ()> {
+        let path = self.dir.join(key);
+        let bytes = bytes.to_vec();
+        //文件读写在线程池中执行，避免阻塞请求处理线程
+        Box::pin(async move {
+            web::block(move || {
+                path.parent()
+                    .map_or(Ok(()), fs::create_dir_all)
+                    .and_then(|_| fs::write(&path, bytes))
+            })
+            .await
+            .map_err(|e| DataBaseError::Custom(format!("文件保存失败:{}", e)))?
+            .map_err(|e| DataBaseError::Custom(format!("文件保存失败:{}", e)))
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> StorageFuture<'a, ()> {
+        let path = self.dir.join(key);
+        Box::pin(async move {
+            let result = web::block(move || fs::remove_file(path))
+                .await
+                .map_err(|e| DataBaseError::Custom(format!("文件删除失败:{}", e)))?;
+            match result {
+                Err(e) if e.kind() != ErrorKind::NotFound => {
+                    Err(DataBaseError::Custom(format!("文件删除失败:{}", e)))
+                }
+                _ => Ok(()),
+            }
+        })
+    }
+
+    fn local_path(&self, key: &str) -> Option<PathBuf> {
+        Some(self.dir.join(key))
+    }
+}
+
+/**
+ * S3兼容存储(AWS S3、MinIO、R2等)，使用路径风格地址与 AWS Signature V4 签名
+ */
+#[cfg(feature = "s3")]
+mod s3 {
+    use chrono::Utc;
+    use hmac::{Hmac, Mac};
+    use sha2::{Digest, Sha256};
+
+    use super::{MediaStorage, StorageFuture};
+    use crate::app::MediaConfig;
+    use crate::error::DataBaseError;
+
+    pub struct S3MediaStorage {
+        endpoint: String,
+        host: String,
+        region: String,
+        bucket: String,
+        access_key: String,
+        secret_key: String,
+        public_url: String,
+    }
+
+    impl S3MediaStorage {
+        pub fn new(config: &MediaConfig) -> Self {
+            let endpoint = config.s3_endpoint.trim_end_matches('/').to_string();
+            let host = endpoint
+                .split_once("://")
+                .map_or(endpoint.as_str(), |(_, host)| host)
+                .to_string();
+            //未配置CDN地址时直接访问存储桶
+            let public_url = match config.public_url.starts_with("http") {
+                true => config.public_url.trim_end_matches('/').to_string(),
+                false => format!("{}/{}", endpoint, config.s3_bucket),
+            };
+            Self {
+                endpoint,
+                host,
+                region: config.s3_region.clone(),
+                bucket: config.s3_bucket.clone(),
+                access_key: config.s3_access_key.clone(),
+                secret_key: config.s3_secret_key.clone(),
+                public_url,
+            }
+        }
+
+        fn object_path(&self, key: &str) -> String {
+            let key: Vec<String> = key
+                .split('/')
+                .map(|segment| urlencoding::encode(segment).into_owned())
+                .collect();
+            format!("/{}/{}", self.bucket, key.join("/"))
+        }
+
+        /**
+         * 发送签名后的请求，只签名 host 与 x-amz-* 请求头
+         */
+        async fn send(
+            &self,
+            method: &str,
+            key: &str,
+            body: Vec<u8>,
+            content_type: Option<&str>,
+        ) -> Result<(), DataBaseError> {
+            let path = self.object_path(key);
+            let now = Utc::now();
+            let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+            let date = now.format("%Y%m%d").to_string();
+            let payload_hash = format!("{:x}", Sha256::digest(&body));
+            let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+            let canonical_request = format!(
+                "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+                method, path, self.host, payload_hash, amz_date, signed_headers, payload_hash
+            );
+            let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+            let string_to_sign = format!(
+                "AWS4-HMAC-SHA256\n{}\n{}\n{:x}",
+                amz_date,
+                scope,
+                Sha256::digest(canonical_request.as_bytes())
+            );
+            let signing_key = signing_key(&self.secret_key, &date, &self.region, "s3");
+            let signature = hex(&hmac(&signing_key, &string_to_sign));
+            let authorization = format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                self.access_key, scope, signed_headers, signature
+            );
+
+            let client = awc::Client::default();
+            let url = format!("{}{}", self.endpoint, path);
+            let request = match method {
+                "PUT" => client.put(url),
+                _ => client.delete(url),
+            }
+            .insert_header(("x-amz-date", amz_date))
+            .insert_header(("x-amz-content-sha256", payload_hash))
+            .insert_header(("authorization", authorization));
+            let request = match content_type {
+                Some(content_type) => request.insert_header(("content-type", content_type)),
+                None => request,
+            };
+            let response = request
+                .send_body(body)
+                .await
+                .map_err(|e| DataBaseError::Custom(format!("S3请求失败:{}", e)))?;
+            match response.status().is_success() {
+                true => Ok(()),
+                false => Err(DataBaseError::Custom(format!(
+                    "S3请求失败:{} {}",
+                    method,
+                    response.status()
+                ))),
+            }
+        }
+    }
+
+    impl MediaStorage for S3MediaStorage {
+        fn name(&self) -> &'static str {
+            "s3"
+        }
+
+        fn url(&self, key: &str) -> String {
+            format!("{}/{}", self.public_url, key)
+        }
+
+        fn put<'a>(
+            &'a self,
+            key: &'a str,
+            bytes: &'a [u8],
+            content_type: &'a str,
+        ) -> StorageFuture<'a, ()> {
+            Box::pin(self.send("PUT", key, bytes.to_vec(), Some(content_type)))
+        }
+
+        fn delete<'a>(&'a self, key: &'a str) -> StorageFuture<'a, ()> {
+            Box::pin(self.send("DELETE", key, vec![], None))
+        }
+    }
+
+    fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC可以使用任意长度的密钥");
+        mac.update(data.as_bytes());
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    fn signing_key(secret_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+        [date, region, service, "aws4_request"]
+            .iter()
+            .fold(format!("AWS4{}", secret_key).into_bytes(), |key, data| {
+                hmac(&key, data)
+            })
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{hex, signing_key};
+
+        #[test]
+        fn test_signing_key() {
+            //AWS文档中的示例
+            let key = signing_key(
+                "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+                "20120215",
+                "us-east-1",
+                "iam",
+            );
+            assert_eq!(
+                hex(&key),
+                "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+            );
+        }
+    }
+}
@@ -1,24 +1,59 @@
 mod about_service;
+mod api_token_service;
+mod blog_markdown_service;
+mod blog_password_service;
+mod blog_revision_service;
 mod blog_service;
 mod category_service;
+mod comment_block_service;
+mod comment_filter;
 mod comments_service;
 mod dashboard_service;
+mod exception_log_service;
+mod feed_service;
 mod friend_service;
+mod login_service;
+mod mail_service;
+mod media_service;
+mod media_storage;
 mod moment_service;
+mod operation_log_service;
 mod redis_service;
+mod schedule_job_service;
+mod search_service;
+mod session_service;
 mod site_setting_service;
+mod sitemap_service;
 mod tag_service;
+mod totp_service;
 mod user_service;
 mod visit_service;
 pub use about_service::AboutService;
+pub use api_token_service::ApiTokenService;
+pub use blog_markdown_service::BlogMarkdownService;
+pub use blog_password_service::{BlogPasswordResult, BlogPasswordService};
+pub use blog_revision_service::BlogRevisionService;
 pub use blog_service::BlogService;
 pub use category_service::CategoryService;
+pub use comment_block_service::CommentBlockService;
+pub use comment_filter::{CommentSubmission, FilterResult, COMMENT_FILTER_CHAIN};
 pub use comments_service::CommentService;
 pub use dashboard_service::DashboardService;
+pub use exception_log_service::ExceptionLogService;
+pub use feed_service::FeedService;
 pub use friend_service::FriendService;
+pub use login_service::LoginService;
+pub use mail_service::MailService;
+pub use media_service::MediaService;
 pub use moment_service::MomentService;
+pub use operation_log_service::OperationLogService;
 pub use redis_service::RedisService;
+pub use schedule_job_service::ScheduleJobService;
+pub use search_service::SearchService;
+pub use session_service::SessionService;
 pub use site_setting_service::SiteSettingService;
+pub use sitemap_service::SitemapService;
 pub use tag_service::TagService;
+pub use totp_service::TotpService;
 pub use user_service::UserService;
 pub use visit_service::VisitService;
@@ -1,11 +1,14 @@
 use crate::common::MarkdownParser;
+use crate::constant::RedisKeyConstant;
 use crate::entity::moment;
 use crate::error::DataBaseError;
 use crate::model::Moment;
 use crate::model::MomentDTO;
+use crate::service::RedisService;
 use rbs::{value, value::map::ValueMap};
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect,
 };
 pub struct MomentService;
 
@@ -53,6 +56,8 @@ impl MomentService {
                     .await?;
             }
         }
+        //站点地图中动态页的更新时间取最新的动态
+        RedisService::evict_cache(&[RedisKeyConstant::SITEMAP_XML]).await;
         Ok(())
     }
 
@@ -81,6 +86,22 @@ impl MomentService {
         Ok(value_map)
     }
 
+    /**
+     * 获取最新的公开动态(订阅源)
+     */
+    pub(crate) async fn find_latest_public_moments(
+        limit: u64,
+        db: &DatabaseConnection,
+    ) -> Result<Vec<moment::Model>, DataBaseError> {
+        let models = moment::Entity::find()
+            .filter(moment::Column::IsPublished.eq(true))
+            .order_by_desc(moment::Column::CreateTime)
+            .limit(limit)
+            .all(db)
+            .await?;
+        Ok(models)
+    }
+
     /**
      * 更新动态的发布状态
      */
@@ -100,6 +121,7 @@ impl MomentService {
                 return Err(DataBaseError::Custom(format!("动态 id:{} 没有检索到", id)));
             }
         }
+        RedisService::evict_cache(&[RedisKeyConstant::SITEMAP_XML]).await;
         Ok(())
     }
 
@@ -119,6 +141,7 @@ impl MomentService {
                 return Err(DataBaseError::Custom(format!("动态 id:{} 没有检索到 ", id)));
             }
         }
+        RedisService::evict_cache(&[RedisKeyConstant::SITEMAP_XML]).await;
         Ok(())
     }
 
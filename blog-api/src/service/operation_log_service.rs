@@ -0,0 +1,226 @@
+use std::pin::Pin;
+
+use actix_web::dev::{Payload, ServiceRequest};
+use actix_web::error::PayloadError;
+use actix_web::http::header::CONTENT_TYPE;
+use actix_web::web::Bytes;
+use actix_web::{HttpMessage, HttpRequest};
+use chrono::Local;
+use futures_util::{stream, Stream, StreamExt};
+use sea_orm::{ActiveModelTrait, ActiveValue::NotSet, DatabaseConnection, Set};
+use serde_json::{Map, Value};
+
+use crate::common::{IpRegion, UserAgent};
+use crate::constant::OperationConstant;
+use crate::entity::operation_log;
+
+pub struct OperationLogService;
+
+impl OperationLogService {
+    /**
+     * 保存操作日志，操作描述由请求方式与匹配到的路由得到，保存失败只记录日志
+     */
+    pub async fn save_operation_log(
+        username: &str,
+        req: &HttpRequest,
+        param: Option<String>,
+        times: i32,
+        db: &DatabaseConnection,
+    ) {
+        let method = req.method().to_string();
+        let uri = req.path().to_string();
+        let pattern = req.match_pattern().unwrap_or_else(|| uri.clone());
+        let ip = IpRegion::get_request_ip(req);
+        let user_agent = req
+            .headers()
+            .get("User-Agent")
+            .and_then(|h| h.to_str().ok())
+            .unwrap_or("");
+        let user_agent = UserAgent::parse_user_agent(user_agent).await;
+        let operation_log = operation_log::ActiveModel {
+            id: NotSet,
+            username: Set(username.to_string()),
+            description: Set(Some(OperationConstant::description(&method, &pattern))),
+            uri: Set(uri),
+            method: Set(method),
+            param: Set(param),
+            ip_source: Set(Some(
+                IpRegion::search_by_ip(ip.as_str()).unwrap_or_default(),
+            )),
+            ip: Set(Some(ip)),
+            os: Set(Some(user_agent.os.name)),
+            browser: Set(Some(user_agent.browser.name)),
+            times: Set(times),
+            create_time: Set(Local::now().naive_local()),
+            user_agent: Set(Some(user_agent.user_agent)),
+        };
+        if let Err(e) = operation_log.insert(db).await {
+            log::error!("保存操作日志失败: {}", e);
+        }
+    }
+
+    /**
+     * 读取请求参数：只读取JSON与表单请求体的前 MAX_BODY_LENGTH 字节，读取的部分放回请求，
+     * 其余部分不经缓冲直接交给接口；其它类型(文件上传等)、超出长度或导入接口的请求体只记录查询参数
+     */
+    pub async fn read_param(req: &mut ServiceRequest) -> Option<String> {
+        let content_type = req
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|h| h.to_str().ok())
+            .unwrap_or("")
+            .to_string();
+        let loggable =
+            Self::is_loggable_body(&content_type) && OperationConstant::is_body_logged(req.path());
+        let body = match loggable {
+            true => Self::read_body_prefix(req).await,
+            false => None,
+        };
+        Self::format_param(
+            req.query_string(),
+            body.as_deref().unwrap_or_default(),
+            &content_type,
+        )
+    }
+
+    /**
+     * 是否记录该类型的请求体
+     */
+    fn is_loggable_body(content_type: &str) -> bool {
+        content_type.contains("json")
+            || content_type.starts_with("application/x-www-form-urlencoded")
+    }
+
+    /**
+     * 读取请求体的前 MAX_BODY_LENGTH 字节，请求体完整读取时返回
+     */
+    async fn read_body_prefix(req: &mut ServiceRequest) -> Option<Vec<u8>> {
+        let mut payload = req.take_payload();
+        let mut chunks = vec![];
+        let mut body = vec![];
+        let mut complete = false;
+        while body.len() <= OperationConstant::MAX_BODY_LENGTH {
+            match payload.next().await {
+                Some(Ok(chunk)) => {
+                    body.extend_from_slice(&chunk);
+                    chunks.push(Ok(chunk));
+                }
+                Some(Err(e)) => {
+                    chunks.push(Err(e));
+                    break;
+                }
+                None => {
+                    complete = true;
+                    break;
+                }
+            }
+        }
+        let stream: Pin<Box<dyn Stream<Item = Result<Bytes, PayloadError>>>> =
+            Box::pin(stream::iter(chunks).chain(payload));
+        req.set_payload(Payload::from(stream));
+        complete.then_some(body)
+    }
+
+    /**
+     * 请求参数：有请求体时取请求体，否则取查询参数；密码等敏感参数脱敏，超出长度时截断
+     */
+    pub fn format_param(query: &str, body: &[u8], content_type: &str) -> Option<String> {
+        let param = if body.is_empty() {
+            Self::redact_form(query)?
+        } else if content_type.contains("json") {
+            match serde_json::from_slice::<Value>(body) {
+                Ok(mut value) => {
+                    Self::redact_json(&mut value);
+                    value.to_string()
+                }
+                Err(_) => String::from_utf8_lossy(body).to_string(),
+            }
+        } else if content_type.starts_with("application/x-www-form-urlencoded") {
+            Self::redact_form(&String::from_utf8_lossy(body))?
+        } else {
+            String::from_utf8_lossy(body).to_string()
+        };
+        Some(
+            param
+                .chars()
+                .take(OperationConstant::MAX_PARAM_LENGTH)
+                .collect(),
+        )
+    }
+
+    fn redact_json(value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if OperationConstant::is_sensitive(key) && !value.is_null() {
+                        *value = Value::String(OperationConstant::MASK.to_string());
+                    } else {
+                        Self::redact_json(value);
+                    }
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(Self::redact_json),
+            _ => {}
+        }
+    }
+
+    /**
+     * 表单或查询参数转为JSON字符串，没有参数时返回None
+     */
+    fn redact_form(form: &str) -> Option<String> {
+        let pairs = actix_web::web::Query::<Vec<(String, String)>>::from_query(form).ok()?;
+        if pairs.is_empty() {
+            return None;
+        }
+        let map: Map<String, Value> = pairs
+            .into_inner()
+            .into_iter()
+            .map(|(key, value)| {
+                let value = match OperationConstant::is_sensitive(&key) {
+                    true => OperationConstant::MASK.to_string(),
+                    false => value,
+                };
+                (key, Value::String(value))
+            })
+            .collect();
+        Some(Value::Object(map).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OperationLogService;
+    use crate::constant::OperationConstant;
+
+    #[test]
+    fn test_format_param() {
+        let body = br#"{"username":"admin","password":"123456","job":{"secretKey":"abc"}}"#;
+        let param = OperationLogService::format_param("", body, "application/json").unwrap();
+        assert!(!param.contains("123456"));
+        assert!(!param.contains("abc"));
+        assert!(param.contains("admin"));
+
+        let param = OperationLogService::format_param("id=1&token=xyz", b"", "").unwrap();
+        assert!(param.contains("\"id\":\"1\""));
+        assert!(!param.contains("xyz"));
+        assert!(OperationLogService::format_param("", b"", "").is_none());
+        assert!(OperationLogService::is_loggable_body(
+            "application/json; charset=utf-8"
+        ));
+        assert!(!OperationLogService::is_loggable_body("text/markdown"));
+        assert!(!OperationConstant::is_body_logged("/admin/blogs/import"));
+        assert!(OperationConstant::is_body_logged("/admin/blog"));
+        assert!(!OperationLogService::is_loggable_body(
+            "multipart/form-data; boundary=x"
+        ));
+
+        assert_eq!(
+            OperationConstant::description("PUT", "/admin/blog/{blog_id}/visibility"),
+            "更新文章可见性"
+        );
+        assert_eq!(
+            OperationConstant::description("POST", "/admin//unknown"),
+            "POST /unknown"
+        );
+    }
+}
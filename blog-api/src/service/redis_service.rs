@@ -181,6 +181,269 @@ impl RedisService {
         }
     }
 
+    /**
+     * 获取Hash `key` 中字段 `field` 的字符串
+     */
+    pub async fn get_hash_string(
+        key: String,
+        field: String,
+    ) -> Result<Option<String>, DataBaseError> {
+        let mut connection = RedisClient::get_connection().await?;
+        let value = connection
+            .hget::<String, String, Option<String>>(key, field)
+            .await?;
+        Ok(value)
+    }
+
+    /**
+     * 保存Hash `key` 中字段 `field` 的字符串
+     */
+    pub async fn set_hash_string(
+        key: String,
+        field: String,
+        value: String,
+    ) -> Result<(), DataBaseError> {
+        let mut connection = RedisClient::get_connection().await?;
+        connection
+            .hset::<String, String, String, i64>(key.clone(), field, value)
+            .await?;
+        RedisService::set_expire(key).await?;
+        Ok(())
+    }
+
+    /**
+     * 保存Hash `key` 中字段 `field` 的字符串，并重新设置整个Hash的过期时间(秒)
+     */
+    pub async fn set_hash_string_with_expire(
+        key: String,
+        field: String,
+        value: String,
+        seconds: u64,
+    ) -> Result<(), DataBaseError> {
+        let mut connection = RedisClient::get_connection().await?;
+        connection
+            .hset::<String, String, String, i64>(key.clone(), field, value)
+            .await?;
+        let _: i64 = deadpool_redis::redis::cmd("EXPIRE")
+            .arg(&key)
+            .arg(seconds)
+            .query_async(&mut connection)
+            .await?;
+        Ok(())
+    }
+
+    /**
+     * 获取Hash `key` 中的所有字符串字段
+     */
+    pub async fn get_hash_string_map(
+        key: String,
+    ) -> Result<HashMap<String, String>, DataBaseError> {
+        let mut connection = RedisClient::get_connection().await?;
+        let map = connection
+            .hgetall::<String, HashMap<String, String>>(key)
+            .await?;
+        Ok(map)
+    }
+
+    /**
+     * Hash `key` 中字段 `field` 的值增加 `delta`，返回增加后的值(不设置过期时间)
+     */
+    pub async fn increment_hash_field(
+        key: String,
+        field: String,
+        delta: i64,
+    ) -> Result<i64, DataBaseError> {
+        let mut connection = RedisClient::get_connection().await?;
+        let value = connection
+            .hincr::<String, String, i64, i64>(key, field, delta)
+            .await?;
+        Ok(value)
+    }
+
+    /**
+     * Hash `key` 中字段 `field` 的值原子地减去 `delta`，减到0及以下时删除该字段，返回剩余的值
+     */
+    pub async fn take_hash_field(
+        key: String,
+        field: String,
+        delta: i64,
+    ) -> Result<i64, DataBaseError> {
+        let mut connection = RedisClient::get_connection().await?;
+        //HINCRBY 与 HDEL 在同一脚本中执行，避免两次调用之间的增量被删除
+        let value: i64 = deadpool_redis::redis::cmd("EVAL")
+            .arg(
+                "local v = redis.call('HINCRBY', KEYS[1], ARGV[1], -tonumber(ARGV[2])) \
+                 if v <= 0 then redis.call('HDEL', KEYS[1], ARGV[1]) end \
+                 return v",
+            )
+            .arg(1)
+            .arg(&key)
+            .arg(&field)
+            .arg(delta)
+            .query_async(&mut connection)
+            .await?;
+        Ok(value)
+    }
+
+    /**
+     * 获取Hash `key` 中的所有整数字段
+     */
+    pub async fn get_hash_i64_map(key: String) -> Result<HashMap<String, i64>, DataBaseError> {
+        let mut connection = RedisClient::get_connection().await?;
+        let map = connection
+            .hgetall::<String, HashMap<String, i64>>(key)
+            .await?;
+        Ok(map)
+    }
+
+    /**
+     * `key` 不存在时设置并指定过期时间(秒)，返回是否设置成功
+     */
+    pub async fn set_if_absent(key: String, seconds: u64) -> Result<bool, DataBaseError> {
+        let mut connection = RedisClient::get_connection().await?;
+        let result: Option<String> = deadpool_redis::redis::cmd("SET")
+            .arg(&key)
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(seconds)
+            .query_async(&mut connection)
+            .await?;
+        Ok(result.is_some())
+    }
+
+    /**
+     * `key` 的值加1，首次创建时设置过期时间(秒)，返回增加后的值
+     */
+    pub async fn increment_with_expire(key: String, seconds: u64) -> Result<i64, DataBaseError> {
+        let mut connection = RedisClient::get_connection().await?;
+        let value = connection.incr::<String, i64, i64>(key.clone(), 1).await?;
+        if value == 1 {
+            let _: i64 = deadpool_redis::redis::cmd("EXPIRE")
+                .arg(&key)
+                .arg(seconds)
+                .query_async(&mut connection)
+                .await?;
+        }
+        Ok(value)
+    }
+
+    /**
+     * 设置`key`并指定过期时间(秒)
+     */
+    pub async fn set_with_expire(key: String, seconds: u64) -> Result<(), DataBaseError> {
+        let mut connection = RedisClient::get_connection().await?;
+        let _: () = deadpool_redis::redis::cmd("SET")
+            .arg(&key)
+            .arg(1)
+            .arg("EX")
+            .arg(seconds)
+            .query_async(&mut connection)
+            .await?;
+        Ok(())
+    }
+
+    /**
+     * 设置字符串`key`并指定过期时间(秒)
+     */
+    pub async fn set_string_with_expire(
+        key: String,
+        value: String,
+        seconds: u64,
+    ) -> Result<(), DataBaseError> {
+        let mut connection = RedisClient::get_connection().await?;
+        let _: () = deadpool_redis::redis::cmd("SET")
+            .arg(&key)
+            .arg(value)
+            .arg("EX")
+            .arg(seconds)
+            .query_async(&mut connection)
+            .await?;
+        Ok(())
+    }
+
+    /**
+     * 获取字符串`key`，不存在时返回None
+     */
+    pub async fn get_string(key: String) -> Result<Option<String>, DataBaseError> {
+        let mut connection = RedisClient::get_connection().await?;
+        let value = connection.get::<String, Option<String>>(key).await?;
+        Ok(value)
+    }
+
+    /**
+     * 获取`key`的剩余过期时间(秒)，key不存在或未设置过期时间时返回负数
+     */
+    pub async fn get_ttl(key: String) -> Result<i64, DataBaseError> {
+        let mut connection = RedisClient::get_connection().await?;
+        let ttl = connection.ttl::<String, i64>(key).await?;
+        Ok(ttl)
+    }
+
+    /**
+     * 删除`key`
+     */
+    pub async fn delete_key(key: String) -> Result<(), DataBaseError> {
+        let mut connection = RedisClient::get_connection().await?;
+        connection.del::<String, i64>(key.clone()).await?;
+        log::info!("redis key: {} 删除成功", key);
+        Ok(())
+    }
+
+    /**
+     * 删除Hash `key` 中的字段 `field`
+     */
+    pub async fn delete_hash_field(key: String, field: String) -> Result<(), DataBaseError> {
+        let mut connection = RedisClient::get_connection().await?;
+        connection
+            .hdel::<String, String, i64>(key.clone(), field.clone())
+            .await?;
+        log::info!("redis key: {} 删除字段 {} 成功", key, field);
+        Ok(())
+    }
+
+    /**
+     * 按通配符删除匹配的key，如 `blog*`，返回删除的数量
+     */
+    pub async fn delete_by_pattern(pattern: String) -> Result<u64, DataBaseError> {
+        let mut connection = RedisClient::get_connection().await?;
+        //使用SCAN代替KEYS，避免阻塞redis
+        let mut keys: Vec<String> = vec![];
+        {
+            let mut iter = connection
+                .scan_match::<String, String>(pattern.clone())
+                .await?;
+            while let Some(key) = iter.next_item().await {
+                keys.push(key);
+            }
+        }
+        if keys.is_empty() {
+            return Ok(0);
+        }
+        let count = connection.del::<Vec<String>, u64>(keys).await?;
+        log::info!("redis 通配符 {} 删除key {} 个", pattern, count);
+        Ok(count)
+    }
+
+    /**
+     * 缓存失效：删除给定的key，包含`*`的按通配符删除
+     * 数据库已经写入成功，缓存删除失败只记录日志
+     */
+    pub async fn evict_cache(keys: &[&str]) {
+        for key in keys {
+            let result = if key.contains('*') {
+                RedisService::delete_by_pattern(key.to_string())
+                    .await
+                    .map(|_| ())
+            } else {
+                RedisService::delete_key(key.to_string()).await
+            };
+            if let Err(e) = result {
+                log::error!("redis key: {} 缓存失效失败:{}", key, e);
+            }
+        }
+    }
+
     /**
      * 设置key的过期时间
      */
@@ -0,0 +1,73 @@
+use chrono::{Duration, Local};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, DatabaseConnection, EntityTrait,
+    QueryFilter, Set,
+};
+
+use crate::entity::{schedule_job, schedule_job_log};
+use crate::error::DataBaseError;
+
+pub struct ScheduleJobService;
+
+impl ScheduleJobService {
+    /**
+     * 查询所有启用状态的定时任务
+     */
+    pub async fn find_enabled_jobs(
+        db: &DatabaseConnection,
+    ) -> Result<Vec<schedule_job::Model>, DataBaseError> {
+        let jobs = schedule_job::Entity::find()
+            .filter(schedule_job::Column::Status.eq(true))
+            .all(db)
+            .await?;
+        Ok(jobs)
+    }
+
+    /**
+     * 根据ID查询定时任务
+     */
+    pub async fn find_by_id(
+        job_id: i64,
+        db: &DatabaseConnection,
+    ) -> Result<Option<schedule_job::Model>, DataBaseError> {
+        Ok(schedule_job::Entity::find_by_id(job_id).one(db).await?)
+    }
+
+    /**
+     * 保存任务执行日志
+     */
+    pub async fn save_job_log(
+        job: &schedule_job::Model,
+        error: Option<String>,
+        times: i32,
+        db: &DatabaseConnection,
+    ) -> Result<schedule_job_log::Model, DataBaseError> {
+        let log = schedule_job_log::ActiveModel {
+            log_id: NotSet,
+            job_id: Set(job.job_id),
+            bean_name: Set(job.bean_name.clone()),
+            method_name: Set(job.method_name.clone()),
+            params: Set(job.params.clone()),
+            status: Set(error.is_none()),
+            error: Set(error),
+            times: Set(times),
+            create_time: Set(Some(Local::now().naive_local())),
+        };
+        Ok(log.insert(db).await?)
+    }
+
+    /**
+     * 删除指定天数之前的任务执行日志
+     */
+    pub async fn delete_job_logs_before(
+        days: i64,
+        db: &DatabaseConnection,
+    ) -> Result<u64, DataBaseError> {
+        let deadline = Local::now().naive_local() - Duration::days(days);
+        let result = schedule_job_log::Entity::delete_many()
+            .filter(schedule_job_log::Column::CreateTime.lt(deadline))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
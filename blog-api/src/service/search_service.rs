@@ -0,0 +1,423 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, MutexGuard};
+
+use rbs::value;
+use rbs::value::map::ValueMap;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, ModelTrait, QueryFilter};
+use tantivy::collector::{Count, TopDocs};
+use tantivy::query::{BooleanQuery, BoostQuery, ConstScoreQuery, Occur, Query, TermQuery};
+use tantivy::schema::{
+    Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, Value, INDEXED, STORED,
+};
+use tantivy::snippet::{Snippet, SnippetGenerator};
+use tantivy::tokenizer::{LowerCaser, RemoveLongFilter, TextAnalyzer};
+use tantivy::{Index, IndexReader, IndexWriter, ReloadPolicy, TantivyDocument, Term};
+
+use crate::common::{JiebaTokenizer, MarkdownParser};
+use crate::entity::sea_orm_active_enums::BlogStatus;
+use crate::entity::{blog, blog_tag, category, tag};
+use crate::error::DataBaseError;
+use crate::model::SearchBlog;
+
+/// 分词器名称
+const TOKENIZER_NAME: &str = "jieba";
+/// 索引写入缓冲区大小
+const WRITER_MEMORY: usize = 15_000_000;
+/// 正文摘要最大字符数
+const SNIPPET_MAX_CHARS: usize = 120;
+/// 每页最大条数
+const MAX_PAGE_SIZE: u64 = 50;
+
+struct SearchFields {
+    id: Field,
+    title: Field,
+    description: Field,
+    content: Field,
+    tags: Field,
+    category: Field,
+    category_id: Field,
+    tag_id: Field,
+}
+
+/**
+ * 文章全文检索索引，保存在内存中，服务启动时从数据库重建
+ */
+struct SearchIndex {
+    reader: IndexReader,
+    writer: Mutex<IndexWriter>,
+    fields: SearchFields,
+}
+
+static SEARCH_INDEX: LazyLock<SearchIndex> =
+    LazyLock::new(|| SearchIndex::new().expect("初始化全文检索索引失败"));
+
+impl SearchIndex {
+    fn new() -> tantivy::Result<Self> {
+        let text_options = TextOptions::default()
+            .set_indexing_options(
+                TextFieldIndexing::default()
+                    .set_tokenizer(TOKENIZER_NAME)
+                    .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+            )
+            .set_stored();
+        let mut builder = Schema::builder();
+        let fields = SearchFields {
+            id: builder.add_i64_field("id", INDEXED | STORED),
+            title: builder.add_text_field("title", text_options.clone()),
+            description: builder.add_text_field("description", text_options.clone()),
+            content: builder.add_text_field("content", text_options.clone()),
+            tags: builder.add_text_field("tags", text_options.clone()),
+            category: builder.add_text_field("category", text_options),
+            category_id: builder.add_i64_field("category_id", INDEXED),
+            tag_id: builder.add_i64_field("tag_id", INDEXED),
+        };
+        let index = Index::create_in_ram(builder.build());
+        index.tokenizers().register(
+            TOKENIZER_NAME,
+            TextAnalyzer::builder(JiebaTokenizer)
+                .filter(RemoveLongFilter::limit(40))
+                .filter(LowerCaser)
+                .build(),
+        );
+        let writer = index.writer_with_num_threads(1, WRITER_MEMORY)?;
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        Ok(Self {
+            reader,
+            writer: Mutex::new(writer),
+            fields,
+        })
+    }
+
+    fn lock_writer(&self) -> Result<MutexGuard<'_, IndexWriter>, DataBaseError> {
+        self.writer
+            .lock()
+            .map_err(|e| DataBaseError::Custom(format!("全文检索索引写入锁异常:{}", e)))
+    }
+
+    /**
+     * 提交索引修改并刷新读取器
+     */
+    fn commit(&self, writer: &mut IndexWriter) -> Result<(), DataBaseError> {
+        writer.commit()?;
+        self.reader.reload()?;
+        Ok(())
+    }
+
+    fn build_document(
+        &self,
+        blog: &blog::Model,
+        category_name: &str,
+        tags: &[tag::Model],
+    ) -> TantivyDocument {
+        let fields = &self.fields;
+        let mut document = TantivyDocument::default();
+        document.add_i64(fields.id, blog.id);
+        document.add_text(fields.title, &blog.title);
+        document.add_text(
+            fields.description,
+            MarkdownParser::parser_text(&blog.description),
+        );
+        //加密文章不索引正文，避免通过搜索摘要泄露内容
+        if blog.password.clone().unwrap_or_default().is_empty() {
+            document.add_text(fields.content, MarkdownParser::parser_text(&blog.content));
+        }
+        document.add_text(fields.category, category_name);
+        document.add_i64(fields.category_id, blog.category_id);
+        for tag in tags {
+            document.add_text(fields.tags, &tag.tag_name);
+            document.add_i64(fields.tag_id, tag.id);
+        }
+        document
+    }
+}
+
+pub struct SearchService;
+
+impl SearchService {
+    /**
+     * 从数据库重建全部已发布文章的索引
+     */
+    pub async fn rebuild_index(db: &DatabaseConnection) -> Result<usize, DataBaseError> {
+        let blogs = blog::Entity::find()
+            .filter(blog::Column::Status.eq(BlogStatus::Published))
+            .all(db)
+            .await?;
+        let categories: HashMap<i64, String> = category::Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|item| (item.id, item.category_name))
+            .collect();
+        let tags: HashMap<i64, tag::Model> = tag::Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|item| (item.id, item))
+            .collect();
+        let mut blog_tags: HashMap<i64, Vec<tag::Model>> = HashMap::new();
+        for item in blog_tag::Entity::find().all(db).await? {
+            if let Some(tag) = tags.get(&item.tag_id) {
+                blog_tags.entry(item.blog_id).or_default().push(tag.clone());
+            }
+        }
+
+        let search_index = &*SEARCH_INDEX;
+        let mut writer = search_index.lock_writer()?;
+        writer.delete_all_documents()?;
+        for blog in &blogs {
+            let category_name = categories
+                .get(&blog.category_id)
+                .map(String::as_str)
+                .unwrap_or_default();
+            let tags = blog_tags
+                .get(&blog.id)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            writer.add_document(search_index.build_document(blog, category_name, tags))?;
+        }
+        search_index.commit(&mut writer)?;
+        log::info!("全文检索索引重建完成, 共{}篇文章", blogs.len());
+        Ok(blogs.len())
+    }
+
+    /**
+     * 文章新增、修改后同步索引，未发布的文章会从索引中移除
+     */
+    pub async fn sync_blog(blog_id: i64, db: &DatabaseConnection) {
+        if let Err(e) = Self::index_blog(blog_id, db).await {
+            log::error!("文章[{}]更新全文检索索引失败:{}", blog_id, e);
+        }
+    }
+
+    /**
+     * 文章删除后移除索引
+     */
+    pub fn remove_blog(blog_id: i64) {
+        let search_index = &*SEARCH_INDEX;
+        let result = search_index.lock_writer().and_then(|mut writer| {
+            writer.delete_term(Term::from_field_i64(search_index.fields.id, blog_id));
+            search_index.commit(&mut writer)
+        });
+        if let Err(e) = result {
+            log::error!("文章[{}]移除全文检索索引失败:{}", blog_id, e);
+        }
+    }
+
+    async fn index_blog(blog_id: i64, db: &DatabaseConnection) -> Result<(), DataBaseError> {
+        let search_index = &*SEARCH_INDEX;
+        let blog_model = blog::Entity::find_by_id(blog_id)
+            .filter(blog::Column::Status.eq(BlogStatus::Published))
+            .one(db)
+            .await?;
+        let document = match blog_model {
+            Some(blog) => {
+                let category_name = category::Entity::find_by_id(blog.category_id)
+                    .one(db)
+                    .await?
+                    .map(|item| item.category_name)
+                    .unwrap_or_default();
+                let tags = blog.find_related(tag::Entity).all(db).await?;
+                Some(search_index.build_document(&blog, &category_name, &tags))
+            }
+            None => None,
+        };
+        let mut writer = search_index.lock_writer()?;
+        writer.delete_term(Term::from_field_i64(search_index.fields.id, blog_id));
+        if let Some(document) = document {
+            writer.add_document(document)?;
+        }
+        search_index.commit(&mut writer)
+    }
+
+    /**
+     * 搜索文章：按相关度排序，返回高亮片段
+     */
+    pub fn search(
+        keyword: &str,
+        category_id: Option<i64>,
+        tag_id: Option<i64>,
+        page_num: u64,
+        page_size: u64,
+    ) -> Result<ValueMap, DataBaseError> {
+        let page_num = page_num.max(1);
+        let page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+        let (total, list) = Self::search_blogs(keyword, category_id, tag_id, page_num, page_size)?;
+        let mut map = ValueMap::new();
+        map.insert(value!("pageNum"), value!(page_num));
+        map.insert(value!("pageSize"), value!(page_size));
+        map.insert(value!("total"), value!(total));
+        map.insert(
+            value!("totalPage"),
+            value!((total as u64).div_ceil(page_size)),
+        );
+        map.insert(value!("list"), value!(list));
+        Ok(map)
+    }
+
+    fn search_blogs(
+        keyword: &str,
+        category_id: Option<i64>,
+        tag_id: Option<i64>,
+        page_num: u64,
+        page_size: u64,
+    ) -> Result<(usize, Vec<SearchBlog>), DataBaseError> {
+        let words = JiebaTokenizer::cut_for_query(keyword);
+        if words.is_empty() {
+            return Ok((0, vec![]));
+        }
+
+        let search_index = &*SEARCH_INDEX;
+        let fields = &search_index.fields;
+        //每个关键词须在任一字段中出现，标题、标签、分类命中权重更高
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = vec![];
+        for word in &words {
+            let field_queries = [
+                (fields.title, 3.0),
+                (fields.tags, 2.0),
+                (fields.category, 2.0),
+                (fields.description, 1.5),
+                (fields.content, 1.0),
+            ]
+            .into_iter()
+            .map(|(field, boost)| {
+                let query = TermQuery::new(
+                    Term::from_field_text(field, word),
+                    IndexRecordOption::WithFreqsAndPositions,
+                );
+                let query: Box<dyn Query> = Box::new(BoostQuery::new(Box::new(query), boost));
+                (Occur::Should, query)
+            })
+            .collect();
+            clauses.push((Occur::Must, Box::new(BooleanQuery::new(field_queries))));
+        }
+        //筛选条件不参与评分
+        let filters = [(fields.category_id, category_id), (fields.tag_id, tag_id)];
+        for (field, id) in filters {
+            if let Some(id) = id {
+                let query =
+                    TermQuery::new(Term::from_field_i64(field, id), IndexRecordOption::Basic);
+                clauses.push((
+                    Occur::Must,
+                    Box::new(ConstScoreQuery::new(Box::new(query), 0.0)),
+                ));
+            }
+        }
+        let query = BooleanQuery::new(clauses);
+
+        let searcher = search_index.reader.searcher();
+        let offset = ((page_num - 1) * page_size) as usize;
+        let (top_docs, total) = searcher.search(
+            &query,
+            &(
+                TopDocs::with_limit(page_size as usize).and_offset(offset),
+                Count,
+            ),
+        )?;
+        let title_generator = SnippetGenerator::create(&searcher, &query, fields.title)?;
+        let mut content_generator = SnippetGenerator::create(&searcher, &query, fields.content)?;
+        content_generator.set_max_num_chars(SNIPPET_MAX_CHARS);
+        let mut description_generator =
+            SnippetGenerator::create(&searcher, &query, fields.description)?;
+        description_generator.set_max_num_chars(SNIPPET_MAX_CHARS);
+
+        let mut list = vec![];
+        for (_, address) in top_docs {
+            let document: TantivyDocument = searcher.doc(address)?;
+            let id = document
+                .get_first(fields.id)
+                .and_then(|value| value.as_i64())
+                .unwrap_or_default();
+            let title_text = Self::field_text(&document, fields.title);
+            let title = Self::highlight(title_generator.snippet_from_doc(&document))
+                .unwrap_or_else(|| Self::escape_html(&title_text));
+            //优先展示正文命中片段，其次为描述，都未命中则截取开头
+            let content = Self::highlight(content_generator.snippet_from_doc(&document))
+                .or_else(|| Self::highlight(description_generator.snippet_from_doc(&document)))
+                .unwrap_or_else(|| {
+                    let mut text = Self::field_text(&document, fields.description);
+                    if text.is_empty() {
+                        text = Self::field_text(&document, fields.content);
+                    }
+                    Self::escape_html(&text.chars().take(SNIPPET_MAX_CHARS).collect::<String>())
+                });
+            list.push(SearchBlog::new(id, title, content));
+        }
+        Ok((total, list))
+    }
+
+    fn highlight(mut snippet: Snippet) -> Option<String> {
+        if snippet.is_empty() {
+            return None;
+        }
+        snippet.set_snippet_prefix_postfix("<mark>", "</mark>");
+        Some(snippet.to_html())
+    }
+
+    fn field_text(document: &TantivyDocument, field: Field) -> String {
+        document
+            .get_first(field)
+            .and_then(|value| value.as_str())
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    fn escape_html(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&#39;")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SearchService, SEARCH_INDEX};
+    use crate::entity::{blog, tag};
+
+    #[test]
+    fn test_search() {
+        let search_index = &*SEARCH_INDEX;
+        let blog = blog::Model {
+            id: 1,
+            title: "Rust异步编程".to_string(),
+            description: "介绍tokio运行时".to_string(),
+            content: "使用 async/await 编写<b>高性能</b>网络服务".to_string(),
+            category_id: 2,
+            ..Default::default()
+        };
+        let tags = vec![tag::Model {
+            id: 3,
+            tag_name: "Rust".to_string(),
+            color: None,
+        }];
+        let mut writer = search_index.lock_writer().unwrap();
+        writer
+            .add_document(search_index.build_document(&blog, "后端", &tags))
+            .unwrap();
+        search_index.commit(&mut writer).unwrap();
+        drop(writer);
+
+        let (total, list) = SearchService::search_blogs("高性能 网络", None, None, 1, 10).unwrap();
+        assert_eq!(total, 1);
+        let json = serde_json::to_string(&list).unwrap();
+        assert!(json.contains("<mark>高性能</mark>"));
+        assert!(!json.contains("<b>"));
+        let (total, list) = SearchService::search_blogs("rust", Some(2), Some(3), 1, 10).unwrap();
+        assert_eq!(total, 1);
+        assert!(serde_json::to_string(&list)
+            .unwrap()
+            .contains("<mark>Rust</mark>"));
+        let (total, _) = SearchService::search_blogs("rust", Some(9), None, 1, 10).unwrap();
+        assert_eq!(total, 0);
+        assert_eq!(
+            SearchService::search_blogs("(*", None, None, 1, 10)
+                .unwrap()
+                .0,
+            0
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use actix_jwt_session::{SessionStorage, Uuid};
+use chrono::{Duration, Local};
+
+use crate::common::{IpRegion, UserAgent};
+use crate::constant::RedisKeyConstant;
+use crate::error::DataBaseError;
+use crate::middleware::AppClaims;
+use crate::model::LoginSession;
+use crate::service::RedisService;
+
+pub struct SessionService;
+
+impl SessionService {
+    /**
+     * 登录成功后记录会话，ttl_seconds 为刷新token的有效期
+     * 会话按账号id保存，修改用户名后仍能找到该账号的会话
+     */
+    pub async fn save_session(
+        account_id: i64,
+        username: &str,
+        jwt_id: Uuid,
+        ip: &str,
+        user_agent: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), DataBaseError> {
+        let now = Local::now().naive_local();
+        let agent = UserAgent::parse_user_agent(user_agent).await;
+        let session = LoginSession {
+            jwt_id: jwt_id.to_string(),
+            username: username.to_string(),
+            ip: ip.to_string(),
+            ip_source: IpRegion::search_by_ip(ip).unwrap_or_default(),
+            os: agent.os.name,
+            browser: agent.browser.name,
+            user_agent: agent.user_agent,
+            issue_time: now,
+            refresh_time: None,
+            expire_time: now + Duration::seconds(ttl_seconds as i64),
+            current: false,
+        };
+        Self::put(account_id, &session, ttl_seconds).await
+    }
+
+    /**
+     * 刷新token后更新会话，jwt_id 变化时迁移到新的 jwt_id
+     */
+    pub async fn refresh_session(
+        account_id: i64,
+        old_jwt_id: Uuid,
+        new_jwt_id: Uuid,
+        ttl_seconds: u64,
+    ) -> Result<(), DataBaseError> {
+        let key = Self::key(account_id);
+        let Some(value) =
+            RedisService::get_hash_string(key.clone(), old_jwt_id.to_string()).await?
+        else {
+            return Ok(());
+        };
+        let mut session: LoginSession = serde_json::from_str(&value)?;
+        let now = Local::now().naive_local();
+        session.jwt_id = new_jwt_id.to_string();
+        session.refresh_time = Some(now);
+        session.expire_time = now + Duration::seconds(ttl_seconds as i64);
+        if old_jwt_id != new_jwt_id {
+            RedisService::delete_hash_field(key, old_jwt_id.to_string()).await?;
+        }
+        Self::put(account_id, &session, ttl_seconds).await
+    }
+
+    /**
+     * 用户的有效会话，按登录时间倒序，顺便清理已过期的会话
+     */
+    pub async fn find_sessions(
+        account_id: i64,
+        current: Uuid,
+    ) -> Result<Vec<LoginSession>, DataBaseError> {
+        let key = Self::key(account_id);
+        let now = Local::now().naive_local();
+        let mut sessions = vec![];
+        for (jwt_id, value) in RedisService::get_hash_string_map(key.clone()).await? {
+            match serde_json::from_str::<LoginSession>(&value) {
+                Ok(mut session) if session.expire_time > now => {
+                    session.current = session.jwt_id == current.to_string();
+                    sessions.push(session);
+                }
+                _ => RedisService::delete_hash_field(key.clone(), jwt_id).await?,
+            }
+        }
+        sessions.sort_by(|a, b| b.issue_time.cmp(&a.issue_time));
+        Ok(sessions)
+    }
+
+    /**
+     * 注销指定会话，jwt_id 必须属于该账号
+     */
+    pub async fn revoke_session(
+        account_id: i64,
+        jwt_id: Uuid,
+        store: &SessionStorage,
+    ) -> Result<(), DataBaseError> {
+        if RedisService::get_hash_string(Self::key(account_id), jwt_id.to_string())
+            .await?
+            .is_none()
+        {
+            return Err(DataBaseError::Validation("会话不存在".to_string()));
+        }
+        Self::remove_session(account_id, jwt_id, store).await
+    }
+
+    /**
+     * 删除 actix-jwt-session 中的token与会话记录，调用方需保证 jwt_id 属于该账号
+     */
+    pub async fn remove_session(
+        account_id: i64,
+        jwt_id: Uuid,
+        store: &SessionStorage,
+    ) -> Result<(), DataBaseError> {
+        if let Err(e) = store.erase::<AppClaims>(jwt_id).await {
+            //token已过期时只清理会话记录
+            log::warn!("删除会话{}的token失败:{}", jwt_id, e);
+        }
+        RedisService::delete_hash_field(Self::key(account_id), jwt_id.to_string()).await
+    }
+
+    /**
+     * 注销用户除 keep 以外的所有会话，返回注销的数量
+     */
+    pub async fn revoke_other_sessions(
+        account_id: i64,
+        keep: Uuid,
+        store: &SessionStorage,
+    ) -> Result<usize, DataBaseError> {
+        let mut count = 0;
+        for jwt_id in RedisService::get_hash_string_map(Self::key(account_id))
+            .await?
+            .into_keys()
+        {
+            let Ok(jwt_id) = Uuid::parse_str(&jwt_id) else {
+                continue;
+            };
+            if jwt_id != keep {
+                Self::remove_session(account_id, jwt_id, store).await?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /**
+     * 注销用户的所有会话(禁用账号或修改角色后)，返回注销的数量
+     */
+    pub async fn revoke_all_sessions(
+        account_id: i64,
+        store: &SessionStorage,
+    ) -> Result<usize, DataBaseError> {
+        Self::revoke_other_sessions(account_id, Uuid::nil(), store).await
+    }
+
+    async fn put(
+        account_id: i64,
+        session: &LoginSession,
+        ttl_seconds: u64,
+    ) -> Result<(), DataBaseError> {
+        RedisService::set_hash_string_with_expire(
+            Self::key(account_id),
+            session.jwt_id.clone(),
+            serde_json::to_string(session)?,
+            ttl_seconds,
+        )
+        .await
+    }
+
+    fn key(account_id: i64) -> String {
+        format!("{}:{}", RedisKeyConstant::LOGIN_SESSION, account_id)
+    }
+}
@@ -8,8 +8,9 @@ use crate::service::RedisService;
 use rbs::value;
 use rbs::value::map::ValueMap;
 use rbs::Value;
-use sea_orm::DatabaseConnection;
+use sea_orm::ActiveValue::{NotSet, Set};
 use sea_orm::EntityTrait;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, QueryFilter, TransactionTrait};
 use std::collections::HashMap;
 
 pub struct SiteSettingService;
@@ -151,4 +152,45 @@ impl SiteSettingService {
         map.insert("type3".to_string(), value!(site_type3));
         Ok(map)
     }
+
+    /**
+     * 更新站点设置：新增或修改 settings，删除 delete_ids
+     */
+    pub async fn update_site_settings(
+        settings: Vec<SiteSetting>,
+        delete_ids: Vec<i64>,
+        db: &DatabaseConnection,
+    ) -> Result<(), DataBaseError> {
+        db.transaction::<_, (), DataBaseError>(|conn| {
+            Box::pin(async move {
+                for setting in settings {
+                    let active = site_setting::ActiveModel {
+                        id: match setting.id {
+                            Some(id) => Set(id),
+                            None => NotSet,
+                        },
+                        name_en: Set(Some(setting.name_en)),
+                        name_zh: Set(Some(setting.name_zh)),
+                        value: Set(Some(setting.value)),
+                        r#type: Set(Some(setting.r#type)),
+                    };
+                    active.save(conn).await?;
+                }
+                if !delete_ids.is_empty() {
+                    site_setting::Entity::delete_many()
+                        .filter(site_setting::Column::Id.is_in(delete_ids))
+                        .exec(conn)
+                        .await?;
+                }
+                Ok(())
+            })
+        })
+        .await
+        .map_err(|e| match e {
+            sea_orm::TransactionError::Connection(e) => DataBaseError::MySQLError(e),
+            sea_orm::TransactionError::Transaction(e) => e,
+        })?;
+        RedisService::evict_cache(&[RedisKeyConstant::SITE_INFO_MAP]).await;
+        Ok(())
+    }
 }
@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+
+use chrono::{Local, NaiveDateTime, SecondsFormat, TimeZone};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+
+use crate::app::CONFIG;
+use crate::constant::RedisKeyConstant;
+use crate::entity::sea_orm_active_enums::BlogStatus;
+use crate::entity::{blog, blog_tag, category, tag};
+use crate::error::DataBaseError;
+use crate::service::{MomentService, RedisService};
+
+/// 单个sitemap文件的最大URL数量
+const SITEMAP_MAX_URLS: usize = 50_000;
+/// 不含文章的固定页面
+const STATIC_PAGES: [&str; 2] = ["friends", "about"];
+
+struct SitemapUrl {
+    loc: String,
+    lastmod: Option<NaiveDateTime>,
+}
+
+pub struct SitemapService;
+
+impl SitemapService {
+    /**
+     * 获取站点地图：page为None时对应 /sitemap.xml，URL数量超过上限时为索引文件；
+     * page为分片序号(从1开始)，分片不存在时返回None
+     */
+    pub async fn find_sitemap(
+        page: Option<usize>,
+        db: &DatabaseConnection,
+    ) -> Result<Option<String>, DataBaseError> {
+        let field = page.map_or("index".to_string(), |page| page.to_string());
+        if let Ok(Some(xml)) =
+            RedisService::get_hash_string(RedisKeyConstant::SITEMAP_XML.to_string(), field.clone())
+                .await
+        {
+            return Ok(Some(xml));
+        }
+        let urls = Self::find_urls(db).await?;
+        let xml = Self::render(&urls, page, &Self::front_address());
+        if let Some(xml) = &xml {
+            if let Err(e) = RedisService::set_hash_string(
+                RedisKeyConstant::SITEMAP_XML.to_string(),
+                field,
+                xml.clone(),
+            )
+            .await
+            {
+                log::error!("redis KEY:{} 缓存失败:{}", RedisKeyConstant::SITEMAP_XML, e);
+            }
+        }
+        Ok(xml)
+    }
+
+    /**
+     * robots.txt：配置的规则 + Sitemap 地址
+     */
+    pub fn robots() -> String {
+        let rules = CONFIG
+            .get_server_config()
+            .robots
+            .unwrap_or_else(|| "User-agent: *\nAllow: /".to_string());
+        format!(
+            "{}\n\nSitemap: {}/sitemap.xml\n",
+            rules.trim_end(),
+            Self::front_address()
+        )
+    }
+
+    /**
+     * 首页、文章、分类、标签、归档、动态、友链、关于我页面
+     */
+    async fn find_urls(db: &DatabaseConnection) -> Result<Vec<SitemapUrl>, DataBaseError> {
+        let front = Self::front_address();
+        let blogs: Vec<(i64, i64, NaiveDateTime)> = blog::Entity::find()
+            .select_only()
+            .columns([
+                blog::Column::Id,
+                blog::Column::CategoryId,
+                blog::Column::UpdateTime,
+            ])
+            .filter(blog::Column::Status.eq(BlogStatus::Published))
+            .order_by_desc(blog::Column::UpdateTime)
+            .into_tuple()
+            .all(db)
+            .await?;
+        let blog_lastmod: HashMap<i64, NaiveDateTime> =
+            blogs.iter().map(|(id, _, time)| (*id, *time)).collect();
+        let latest = blogs.first().map(|(_, _, time)| *time);
+
+        //分类、标签的最后修改时间取其中文章的最新更新时间
+        let mut category_lastmod: HashMap<i64, NaiveDateTime> = HashMap::new();
+        for (_, category_id, time) in &blogs {
+            category_lastmod.entry(*category_id).or_insert(*time);
+        }
+        let mut tag_lastmod: HashMap<i64, NaiveDateTime> = HashMap::new();
+        for item in blog_tag::Entity::find().all(db).await? {
+            if let Some(time) = blog_lastmod.get(&item.blog_id) {
+                let lastmod = tag_lastmod.entry(item.tag_id).or_insert(*time);
+                *lastmod = (*lastmod).max(*time);
+            }
+        }
+
+        let mut urls = vec![SitemapUrl {
+            loc: format!("{}/", front),
+            lastmod: latest,
+        }];
+        for (id, _, time) in &blogs {
+            urls.push(SitemapUrl {
+                loc: format!("{}/blog/{}", front, id),
+                lastmod: Some(*time),
+            });
+        }
+        for item in category::Entity::find().all(db).await? {
+            urls.push(SitemapUrl {
+                loc: format!(
+                    "{}/category/{}",
+                    front,
+                    urlencoding::encode(&item.category_name)
+                ),
+                lastmod: category_lastmod.get(&item.id).copied(),
+            });
+        }
+        for item in tag::Entity::find().all(db).await? {
+            urls.push(SitemapUrl {
+                loc: format!("{}/tag/{}", front, urlencoding::encode(&item.tag_name)),
+                lastmod: tag_lastmod.get(&item.id).copied(),
+            });
+        }
+        urls.push(SitemapUrl {
+            loc: format!("{}/archives", front),
+            lastmod: latest,
+        });
+        let moment_lastmod = MomentService::find_latest_public_moments(1, db)
+            .await?
+            .first()
+            .map(|moment| moment.create_time);
+        urls.push(SitemapUrl {
+            loc: format!("{}/moments", front),
+            lastmod: moment_lastmod,
+        });
+        for page in STATIC_PAGES {
+            urls.push(SitemapUrl {
+                loc: format!("{}/{}", front, page),
+                lastmod: None,
+            });
+        }
+        Ok(urls)
+    }
+
+    fn render(urls: &[SitemapUrl], page: Option<usize>, front: &str) -> Option<String> {
+        let chunks: Vec<&[SitemapUrl]> = urls.chunks(SITEMAP_MAX_URLS).collect();
+        match page {
+            None if chunks.len() <= 1 => Some(Self::render_urlset(urls)),
+            None => Some(Self::render_index(&chunks, front)),
+            Some(page) if chunks.len() > 1 && (1..=chunks.len()).contains(&page) => {
+                Some(Self::render_urlset(chunks[page - 1]))
+            }
+            Some(_) => None,
+        }
+    }
+
+    fn render_urlset(urls: &[SitemapUrl]) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+        );
+        for url in urls {
+            xml.push_str("<url><loc>");
+            xml.push_str(&Self::escape_xml(&url.loc));
+            xml.push_str("</loc>");
+            if let Some(lastmod) = url.lastmod {
+                xml.push_str(&format!(
+                    "<lastmod>{}</lastmod>",
+                    Self::format_time(lastmod)
+                ));
+            }
+            xml.push_str("</url>\n");
+        }
+        xml.push_str("</urlset>\n");
+        xml
+    }
+
+    fn render_index(chunks: &[&[SitemapUrl]], front: &str) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+        );
+        for (index, chunk) in chunks.iter().enumerate() {
+            xml.push_str(&format!(
+                "<sitemap><loc>{}</loc>",
+                Self::escape_xml(&format!("{}/sitemap-{}.xml", front, index + 1))
+            ));
+            if let Some(lastmod) = chunk.iter().filter_map(|url| url.lastmod).max() {
+                xml.push_str(&format!(
+                    "<lastmod>{}</lastmod>",
+                    Self::format_time(lastmod)
+                ));
+            }
+            xml.push_str("</sitemap>\n");
+        }
+        xml.push_str("</sitemapindex>\n");
+        xml
+    }
+
+    fn format_time(time: NaiveDateTime) -> String {
+        match Local.from_local_datetime(&time).earliest() {
+            Some(time) => time.to_rfc3339_opts(SecondsFormat::Secs, false),
+            None => time.format("%Y-%m-%d").to_string(),
+        }
+    }
+
+    fn escape_xml(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&apos;")
+    }
+
+    fn front_address() -> String {
+        CONFIG
+            .get_server_config()
+            .front_adderss
+            .trim_end_matches('/')
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SitemapService, SitemapUrl, SITEMAP_MAX_URLS};
+
+    fn urls(count: usize) -> Vec<SitemapUrl> {
+        (0..count)
+            .map(|id| SitemapUrl {
+                loc: format!("http://localhost/blog/{}?a=1&b=2", id),
+                lastmod: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_render_sitemap() {
+        let xml = SitemapService::render(&urls(2), None, "http://localhost").unwrap();
+        assert!(xml.contains("<urlset"));
+        assert!(xml.contains("?a=1&amp;b=2"));
+        assert!(SitemapService::render(&urls(2), Some(1), "http://localhost").is_none());
+
+        let urls = urls(SITEMAP_MAX_URLS + 1);
+        let index = SitemapService::render(&urls, None, "http://localhost").unwrap();
+        assert!(index.contains("<sitemapindex"));
+        assert!(index.contains("http://localhost/sitemap-2.xml"));
+        let last = SitemapService::render(&urls, Some(2), "http://localhost").unwrap();
+        assert_eq!(last.matches("<url>").count(), 1);
+        assert!(SitemapService::render(&urls, Some(3), "http://localhost").is_none());
+    }
+}
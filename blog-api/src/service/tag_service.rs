@@ -102,6 +102,7 @@ impl TagService {
             active.id = NotSet;
         }
         active.reset_all().save(db).await?;
+        RedisService::evict_cache(&RedisKeyConstant::TAG_CACHE_KEYS).await;
         Ok(())
     }
 
@@ -134,9 +135,14 @@ impl TagService {
             .count(db)
             .await?;
         match count > 0 {
-            true => return Err(DataBaseError::Custom("标签下有文章，不能删除".to_string())),
+            true => {
+                return Err(DataBaseError::Validation(
+                    "标签下有文章，不能删除".to_string(),
+                ))
+            }
             false => {
                 tag::Entity::delete_by_id(id).exec(db).await?;
+                RedisService::evict_cache(&RedisKeyConstant::TAG_CACHE_KEYS).await;
                 Ok(())
             }
         }
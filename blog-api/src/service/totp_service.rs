@@ -0,0 +1,299 @@
+use actix_web::web;
+use chrono::{Local, Utc};
+use rand::{rngs::OsRng, Rng};
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+
+use crate::common::Totp;
+use crate::constant::{LoginConstant, RedisKeyConstant};
+use crate::entity::user;
+use crate::error::DataBaseError;
+use crate::service::{RedisService, UserService};
+
+/// 恢复码字符集，去掉了容易混淆的 0/o、1/l/i
+const RECOVERY_CODE_CHARS: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+pub struct TotpService;
+
+impl TotpService {
+    /**
+     * 开始绑定：生成新密钥(尚未启用)，返回密钥与 otpauth URI
+     */
+    pub async fn setup(
+        account_id: i64,
+        db: &DatabaseConnection,
+    ) -> Result<(String, String), DataBaseError> {
+        let model = Self::find_user_by_id(account_id, db).await?;
+        if model.totp_enabled {
+            return Err(DataBaseError::Validation(
+                "两步验证已启用，请先关闭后重新绑定".to_string(),
+            ));
+        }
+        let secret = Totp::generate_secret();
+        let uri = Totp::otpauth_uri(LoginConstant::TOTP_ISSUER, &model.username, &secret);
+        user::ActiveModel {
+            id: Set(model.id),
+            totp_secret: Set(Some(secret.clone())),
+            totp_recovery_codes: Set(None),
+            update_time: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        }
+        .update(db)
+        .await?;
+        Ok((secret, uri))
+    }
+
+    /**
+     * 确认绑定：校验首个动态码后启用两步验证，返回恢复码(仅显示这一次)
+     */
+    pub async fn enable(
+        account_id: i64,
+        code: &str,
+        db: &DatabaseConnection,
+    ) -> Result<Vec<String>, DataBaseError> {
+        let model = Self::find_user_by_id(account_id, db).await?;
+        if model.totp_enabled {
+            return Err(DataBaseError::Validation("两步验证已启用".to_string()));
+        }
+        let secret = model
+            .totp_secret
+            .as_deref()
+            .ok_or_else(|| DataBaseError::Validation("请先获取两步验证密钥".to_string()))?;
+        if !Self::verify_code(model.id, secret, code).await {
+            return Err(DataBaseError::Validation("动态码错误".to_string()));
+        }
+        let (codes, hashes) = Self::generate_recovery_codes()?;
+        user::ActiveModel {
+            id: Set(model.id),
+            totp_enabled: Set(true),
+            totp_recovery_codes: Set(Some(hashes)),
+            update_time: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        }
+        .update(db)
+        .await?;
+        log::info!("用户:{}已启用两步验证", model.username);
+        Ok(codes)
+    }
+
+    /**
+     * 关闭两步验证，需要登录密码与动态码(或恢复码)
+     */
+    pub async fn disable(
+        account_id: i64,
+        password: &str,
+        code: &str,
+        db: &DatabaseConnection,
+    ) -> Result<(), DataBaseError> {
+        let model = Self::find_user_by_id(account_id, db).await?;
+        if !UserService::verify_password(password, &model.password) {
+            return Err(DataBaseError::Validation("密码错误".to_string()));
+        }
+        if !model.totp_enabled {
+            return Err(DataBaseError::Validation("两步验证未启用".to_string()));
+        }
+        if !Self::verify_second_factor(&model, code, db).await? {
+            return Err(DataBaseError::Validation("动态码错误".to_string()));
+        }
+        user::ActiveModel {
+            id: Set(model.id),
+            totp_secret: Set(None),
+            totp_enabled: Set(false),
+            totp_recovery_codes: Set(None),
+            update_time: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        }
+        .update(db)
+        .await?;
+        log::info!("用户:{}已关闭两步验证", model.username);
+        Ok(())
+    }
+
+    /**
+     * 重新生成恢复码，旧恢复码全部失效
+     */
+    pub async fn regenerate_recovery_codes(
+        account_id: i64,
+        code: &str,
+        db: &DatabaseConnection,
+    ) -> Result<Vec<String>, DataBaseError> {
+        let model = Self::find_user_by_id(account_id, db).await?;
+        let secret = match (model.totp_enabled, model.totp_secret.as_deref()) {
+            (true, Some(secret)) => secret,
+            _ => return Err(DataBaseError::Validation("两步验证未启用".to_string())),
+        };
+        if !Self::verify_code(model.id, secret, code).await {
+            return Err(DataBaseError::Validation("动态码错误".to_string()));
+        }
+        let (codes, hashes) = Self::generate_recovery_codes()?;
+        user::ActiveModel {
+            id: Set(model.id),
+            totp_recovery_codes: Set(Some(hashes)),
+            update_time: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        }
+        .update(db)
+        .await?;
+        Ok(codes)
+    }
+
+    /**
+     * 登录第二步：校验动态码，动态码不正确时尝试恢复码(使用后作废)
+     */
+    pub async fn verify_login(
+        username: &str,
+        code: &str,
+        db: &DatabaseConnection,
+    ) -> Result<bool, DataBaseError> {
+        let model = Self::find_user(username, db).await?;
+        if !model.totp_enabled {
+            return Ok(false);
+        }
+        Self::verify_second_factor(&model, code, db).await
+    }
+
+    /**
+     * 密码校验通过后生成预登录token，有效期内凭token与动态码完成登录
+     */
+    pub async fn create_pre_auth(username: &str) -> Result<String, DataBaseError> {
+        let token = actix_jwt_session::Uuid::new_v4().simple().to_string();
+        RedisService::set_string_with_expire(
+            format!("{}:{}", RedisKeyConstant::LOGIN_PRE_AUTH, token),
+            username.to_string(),
+            LoginConstant::PRE_AUTH_SECONDS,
+        )
+        .await?;
+        Ok(token)
+    }
+
+    /**
+     * 预登录token对应的用户名，token不存在或已过期时返回None
+     */
+    pub async fn find_pre_auth(token: &str) -> Result<Option<String>, DataBaseError> {
+        RedisService::get_string(format!("{}:{}", RedisKeyConstant::LOGIN_PRE_AUTH, token)).await
+    }
+
+    /**
+     * 登录完成后删除预登录token
+     */
+    pub async fn remove_pre_auth(token: &str) -> Result<(), DataBaseError> {
+        RedisService::delete_key(format!("{}:{}", RedisKeyConstant::LOGIN_PRE_AUTH, token)).await
+    }
+
+    async fn verify_second_factor(
+        model: &user::Model,
+        code: &str,
+        db: &DatabaseConnection,
+    ) -> Result<bool, DataBaseError> {
+        if let Some(secret) = model.totp_secret.as_deref() {
+            if Self::verify_code(model.id, secret, code).await {
+                return Ok(true);
+            }
+        }
+        //恢复码：匹配后从列表中移除
+        let code = code.trim().to_lowercase();
+        let hashes: Vec<String> = model
+            .totp_recovery_codes
+            .as_deref()
+            .unwrap_or("")
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+        //逐个校验Argon2哈希比较耗时，在线程池中执行
+        let candidates = hashes.clone();
+        let index = web::block(move || {
+            candidates
+                .iter()
+                .position(|hash| UserService::verify_password(&code, hash))
+        })
+        .await
+        .map_err(|e| DataBaseError::Custom(format!("校验恢复码失败:{}", e)))?;
+        let Some(index) = index else {
+            return Ok(false);
+        };
+        let remain: Vec<&str> = hashes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != index)
+            .map(|(_, hash)| hash.as_str())
+            .collect();
+        user::ActiveModel {
+            id: Set(model.id),
+            totp_recovery_codes: Set(Some(remain.join("\n"))),
+            ..Default::default()
+        }
+        .update(db)
+        .await?;
+        log::warn!(
+            "用户:{}使用恢复码登录，剩余{}个恢复码",
+            model.username,
+            remain.len()
+        );
+        Ok(true)
+    }
+
+    /**
+     * 校验动态码，同一时间步的动态码只能使用一次；Redis异常时不做重复校验
+     */
+    async fn verify_code(account_id: i64, secret: &str, code: &str) -> bool {
+        let now = Local::now().timestamp().max(0) as u64;
+        let Some(step) = Totp::verify(secret, code, now) else {
+            return false;
+        };
+        let key = format!(
+            "{}:{}:{}",
+            RedisKeyConstant::TOTP_USED_STEP,
+            account_id,
+            step
+        );
+        match RedisService::set_if_absent(key, Totp::PERIOD * (2 * Totp::SKEW + 1)).await {
+            Ok(first_use) => first_use,
+            Err(e) => {
+                log::error!("记录动态码使用状态失败:{}", e);
+                true
+            }
+        }
+    }
+
+    /**
+     * 生成恢复码，返回(明文恢复码, 每行一个的哈希)
+     */
+    fn generate_recovery_codes() -> Result<(Vec<String>, String), DataBaseError> {
+        let codes: Vec<String> = (0..LoginConstant::RECOVERY_CODE_COUNT)
+            .map(|_| {
+                let chars: String = (0..10)
+                    .map(|_| {
+                        RECOVERY_CODE_CHARS[OsRng.gen_range(0..RECOVERY_CODE_CHARS.len())] as char
+                    })
+                    .collect();
+                format!("{}-{}", &chars[..5], &chars[5..])
+            })
+            .collect();
+        let hashes = codes
+            .iter()
+            .map(|code| UserService::hash_password(code))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((codes, hashes.join("\n")))
+    }
+
+    async fn find_user_by_id(
+        account_id: i64,
+        db: &DatabaseConnection,
+    ) -> Result<user::Model, DataBaseError> {
+        user::Entity::find_by_id(account_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DataBaseError::Validation("用户不存在".to_string()))
+    }
+
+    async fn find_user(
+        username: &str,
+        db: &DatabaseConnection,
+    ) -> Result<user::Model, DataBaseError> {
+        user::Entity::find()
+            .filter(user::Column::Username.eq(username))
+            .one(db)
+            .await?
+            .ok_or_else(|| DataBaseError::Validation("用户不存在".to_string()))
+    }
+}
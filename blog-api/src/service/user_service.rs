@@ -1,9 +1,20 @@
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QueryTrait, Set,
+};
 
+use crate::constant::RoleConstant;
 use crate::entity::user;
 use crate::error::DataBaseError;
 use crate::model::User;
 
+/// Argon2 哈希串前缀，用于区分历史遗留的明文密码
+const ARGON2_PREFIX: &str = "$argon2";
+
 pub struct UserService;
 
 impl UserService {
@@ -21,6 +32,243 @@ impl UserService {
         if let Some(user) = user {
             return Ok(User::from(user));
         }
-        Err(DataBaseError::Custom("没有检索到该用户".to_string()))
+        Err(DataBaseError::Validation("没有检索到该用户".to_string()))
+    }
+
+    /**
+     * 使用 Argon2id 对密码进行哈希(随机盐)
+     */
+    pub fn hash_password(password: &str) -> Result<String, DataBaseError> {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| DataBaseError::Custom(format!("密码哈希失败:{}", e)))
+    }
+
+    /**
+     * 校验密码，兼容数据库中尚未升级的明文密码
+     */
+    pub fn verify_password(password: &str, stored: &str) -> bool {
+        if !Self::is_hashed(stored) {
+            return password == stored;
+        }
+        match PasswordHash::new(stored) {
+            Ok(hash) => Argon2::default()
+                .verify_password(password.as_bytes(), &hash)
+                .is_ok(),
+            Err(e) => {
+                log::error!("密码哈希格式错误:{}", e);
+                false
+            }
+        }
+    }
+
+    /**
+     * 判断存储的密码是否已经是 Argon2 哈希
+     */
+    pub fn is_hashed(stored: &str) -> bool {
+        stored.starts_with(ARGON2_PREFIX)
+    }
+
+    /**
+     * 登录校验，校验通过且库中仍为明文时就地升级为哈希
+     */
+    pub async fn check_login_password(
+        user: &User,
+        password: &str,
+        db: &DatabaseConnection,
+    ) -> Result<bool, DataBaseError> {
+        let stored = user.get_password();
+        if !Self::verify_password(password, &stored) {
+            return Ok(false);
+        }
+        if !Self::is_hashed(&stored) {
+            Self::update_password(user.get_id(), password, db).await?;
+            log::info!("用户:{}的明文密码已升级为哈希存储", user.get_username());
+        }
+        Ok(true)
+    }
+
+    /**
+     * 修改密码前校验旧密码，返回新密码的哈希，由调用方与其它字段一起写入
+     */
+    pub fn check_password_change(
+        user: &User,
+        old_password: &str,
+        new_password: &str,
+    ) -> Result<String, DataBaseError> {
+        if new_password.trim().is_empty() {
+            return Err(DataBaseError::Validation("新密码不能为空".to_string()));
+        }
+        if !Self::verify_password(old_password, &user.get_password()) {
+            return Err(DataBaseError::Validation("原密码错误".to_string()));
+        }
+        Self::hash_password(new_password)
+    }
+
+    /**
+     * 后台账号列表，密码与两步验证信息不返回
+     */
+    pub async fn find_users(
+        page_num: u64,
+        page_size: u64,
+        username: Option<String>,
+        db: &DatabaseConnection,
+    ) -> Result<(u64, Vec<User>), DataBaseError> {
+        let paginator = user::Entity::find()
+            .apply_if(username, |query, value| {
+                query.filter(user::Column::Username.contains(value))
+            })
+            .order_by_asc(user::Column::Id)
+            .paginate(db, page_size.max(1));
+        let total = paginator.num_items().await?;
+        let users = paginator
+            .fetch_page(page_num.max(1) - 1)
+            .await?
+            .into_iter()
+            .map(|model| {
+                let mut user = User::from(model);
+                user.set_password("".to_string());
+                user
+            })
+            .collect();
+        Ok((total, users))
+    }
+
+    /**
+     * 创建后台账号，返回账号ID
+     */
+    pub async fn create_user(
+        username: &str,
+        password: &str,
+        nickname: &str,
+        email: &str,
+        role: &str,
+        db: &DatabaseConnection,
+    ) -> Result<i64, DataBaseError> {
+        if username.trim().is_empty() || password.trim().is_empty() {
+            return Err(DataBaseError::Validation(
+                "用户名和密码不能为空".to_string(),
+            ));
+        }
+        if !RoleConstant::is_valid(role) {
+            return Err(DataBaseError::Validation("角色不存在".to_string()));
+        }
+        let exist = user::Entity::find()
+            .filter(user::Column::Username.eq(username))
+            .one(db)
+            .await?;
+        if exist.is_some() {
+            return Err(DataBaseError::Validation("用户名已被使用".to_string()));
+        }
+        let now = Utc::now().naive_utc();
+        let model = user::ActiveModel {
+            username: Set(username.to_string()),
+            password: Set(Self::hash_password(password)?),
+            nickname: Set(nickname.to_string()),
+            avatar: Set(String::new()),
+            email: Set(email.to_string()),
+            create_time: Set(now),
+            update_time: Set(now),
+            role: Set(role.to_string()),
+            totp_secret: Set(None),
+            totp_enabled: Set(false),
+            totp_recovery_codes: Set(None),
+            status: Set(true),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok(model.id)
+    }
+
+    /**
+     * 修改账号角色，返回用户名
+     */
+    pub async fn update_role(
+        id: i64,
+        role: &str,
+        db: &DatabaseConnection,
+    ) -> Result<String, DataBaseError> {
+        if !RoleConstant::is_valid(role) {
+            return Err(DataBaseError::Validation("角色不存在".to_string()));
+        }
+        let model = Self::find_model(id, db).await?;
+        user::ActiveModel {
+            id: Set(id),
+            role: Set(role.to_string()),
+            update_time: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        }
+        .update(db)
+        .await?;
+        Ok(model.username)
+    }
+
+    /**
+     * 启用或禁用账号，返回用户名
+     */
+    pub async fn update_status(
+        id: i64,
+        status: bool,
+        db: &DatabaseConnection,
+    ) -> Result<String, DataBaseError> {
+        let model = Self::find_model(id, db).await?;
+        user::ActiveModel {
+            id: Set(id),
+            status: Set(status),
+            update_time: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        }
+        .update(db)
+        .await?;
+        Ok(model.username)
+    }
+
+    async fn find_model(id: i64, db: &DatabaseConnection) -> Result<user::Model, DataBaseError> {
+        user::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DataBaseError::Validation("没有检索到该用户".to_string()))
+    }
+
+    /**
+     * 哈希后写入新密码
+     */
+    async fn update_password(
+        id: i64,
+        password: &str,
+        db: &DatabaseConnection,
+    ) -> Result<(), DataBaseError> {
+        let hash = Self::hash_password(password)?;
+        user::ActiveModel {
+            id: Set(id),
+            password: Set(hash),
+            update_time: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        }
+        .update(db)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UserService;
+
+    #[test]
+    fn test_hash_and_verify() {
+        let hash = UserService::hash_password("123456").unwrap();
+        assert!(UserService::is_hashed(&hash));
+        assert!(UserService::verify_password("123456", &hash));
+        assert!(!UserService::verify_password("654321", &hash));
+    }
+
+    #[test]
+    fn test_verify_plaintext() {
+        assert!(UserService::verify_password("123456", "123456"));
+        assert!(!UserService::verify_password("12345", "123456"));
     }
 }
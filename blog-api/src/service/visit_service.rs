@@ -1,16 +1,33 @@
 use std::collections::HashMap;
 
 use actix_web::web::{Data, Query};
-use sea_orm::{ActiveModelTrait, ActiveValue::Set};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait,
+    FromQueryResult, QueryFilter, QueryOrder, QuerySelect, Select,
+};
 
 use crate::{
     app::AppState,
     common::{IpRegion, UserAgentInfo},
     constant::{VisitBehavior, VisitBehaviorType},
-    entity::visit_log,
+    entity::{city_visitor, visit_log, visit_record, visitor},
+    error::DataBaseError,
     service::BlogService,
 };
 
+/**
+ * 按访客标识码聚合的访问统计
+ */
+#[derive(Debug, FromQueryResult)]
+struct VisitorStat {
+    uuid: Option<String>,
+    pv: i64,
+    first_time: Option<NaiveDateTime>,
+    last_time: Option<NaiveDateTime>,
+}
+
 pub struct VisitService;
 
 impl VisitService {
@@ -154,4 +171,243 @@ impl VisitService {
             log::error!("保存访问日志失败: AppState is None");
         }
     }
+
+    /**
+     * 统计某一天的访问数据：记录当天PV、UV，更新访客的PV和最后访问时间，累加城市新增访客数
+     * 访客PV按访问日志重新计算，重复执行同一天不会重复累加
+     */
+    pub async fn sync_visit_info(
+        date: NaiveDate,
+        db: &DatabaseConnection,
+    ) -> Result<(), DataBaseError> {
+        let start = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+        let end = start + Duration::days(1);
+        //1.当天按访客聚合
+        let day_stats = Self::find_visitor_stats()
+            .filter(visit_log::Column::CreateTime.gte(start))
+            .filter(visit_log::Column::CreateTime.lt(end))
+            .into_model::<VisitorStat>()
+            .all(db)
+            .await?;
+        let pv: i64 = day_stats.iter().map(|stat| stat.pv).sum();
+        let uv = day_stats.len();
+        //2.记录当天PV、UV
+        Self::save_visit_record(
+            date.format("%Y-%m-%d").to_string(),
+            pv as i32,
+            uv as i32,
+            db,
+        )
+        .await?;
+        //3.更新访客信息
+        let uuids: Vec<String> = day_stats.into_iter().filter_map(|stat| stat.uuid).collect();
+        if uuids.is_empty() {
+            log::info!("{} 没有访问记录", date);
+            return Ok(());
+        }
+        let total_stats = Self::find_visitor_stats()
+            .filter(visit_log::Column::Uuid.is_in(uuids))
+            .into_model::<VisitorStat>()
+            .all(db)
+            .await?;
+        let mut new_visitor_count = 0;
+        for stat in total_stats {
+            if Self::save_visitor(stat, db).await? {
+                new_visitor_count += 1;
+            }
+        }
+        log::info!(
+            "{} 访问统计完成 PV:{}, UV:{}, 新增访客:{}",
+            date,
+            pv,
+            uv,
+            new_visitor_count
+        );
+        Ok(())
+    }
+
+    /**
+     * 今日PV、UV(实时)
+     */
+    pub async fn find_today_pv_uv(db: &DatabaseConnection) -> (i64, i64) {
+        let start = chrono::Local::now()
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .unwrap_or_default();
+        match Self::find_visitor_stats()
+            .filter(visit_log::Column::CreateTime.gte(start))
+            .into_model::<VisitorStat>()
+            .all(db)
+            .await
+        {
+            Ok(stats) => (stats.iter().map(|stat| stat.pv).sum(), stats.len() as i64),
+            Err(e) => {
+                log::error!("查询今日访问量失败:{}", e);
+                (0, 0)
+            }
+        }
+    }
+
+    /**
+     * 按访客标识码分组统计访问次数、首次与最后访问时间
+     */
+    fn find_visitor_stats() -> Select<visit_log::Entity> {
+        visit_log::Entity::find()
+            .select_only()
+            .column(visit_log::Column::Uuid)
+            .column_as(Expr::col(visit_log::Column::Id).count(), "pv")
+            .column_as(Expr::col(visit_log::Column::CreateTime).min(), "first_time")
+            .column_as(Expr::col(visit_log::Column::CreateTime).max(), "last_time")
+            .filter(visit_log::Column::Uuid.is_not_null())
+            .group_by(visit_log::Column::Uuid)
+    }
+
+    /**
+     * 保存某一天的访问记录，按完整日期查找，重复执行或补录历史日期时更新
+     */
+    async fn save_visit_record(
+        date: String,
+        pv: i32,
+        uv: i32,
+        db: &DatabaseConnection,
+    ) -> Result<(), DataBaseError> {
+        let record = visit_record::Entity::find()
+            .filter(visit_record::Column::Date.eq(date.as_str()))
+            .one(db)
+            .await?;
+        match record {
+            Some(record) => {
+                let mut active: visit_record::ActiveModel = record.into();
+                active.pv = Set(pv);
+                active.uv = Set(uv);
+                active.update(db).await?;
+            }
+            None => {
+                visit_record::ActiveModel {
+                    pv: Set(pv),
+                    uv: Set(uv),
+                    date: Set(date),
+                    ..Default::default()
+                }
+                .insert(db)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * 新增或更新访客，返回是否为新访客
+     */
+    async fn save_visitor(
+        stat: VisitorStat,
+        db: &DatabaseConnection,
+    ) -> Result<bool, DataBaseError> {
+        let uuid = stat.uuid.unwrap_or_default();
+        //最近一次访问的日志，用于更新IP、系统、浏览器信息
+        let last_log = match visit_log::Entity::find()
+            .filter(visit_log::Column::Uuid.eq(&uuid))
+            .order_by_desc(visit_log::Column::CreateTime)
+            .one(db)
+            .await?
+        {
+            Some(last_log) => last_log,
+            None => return Ok(false),
+        };
+        let last_time = stat.last_time.unwrap_or(last_log.create_time);
+        let exist = visitor::Entity::find()
+            .filter(visitor::Column::Uuid.eq(&uuid))
+            .one(db)
+            .await?;
+        match exist {
+            Some(model) => {
+                let mut active: visitor::ActiveModel = model.into();
+                active.pv = Set(Some(stat.pv as i32));
+                active.last_time = Set(last_time);
+                active.ip = Set(last_log.ip);
+                active.ip_source = Set(last_log.ip_source);
+                active.os = Set(last_log.os);
+                active.browser = Set(last_log.browser);
+                active.user_agent = Set(last_log.user_agent);
+                active.update(db).await?;
+                Ok(false)
+            }
+            None => {
+                if let Some(city) = last_log.ip_source.as_deref().and_then(Self::get_city) {
+                    Self::increase_city_visitor(city, db).await?;
+                }
+                visitor::ActiveModel {
+                    uuid: Set(uuid),
+                    ip: Set(last_log.ip),
+                    ip_source: Set(last_log.ip_source),
+                    os: Set(last_log.os),
+                    browser: Set(last_log.browser),
+                    create_time: Set(stat.first_time.unwrap_or(last_time)),
+                    last_time: Set(last_time),
+                    pv: Set(Some(stat.pv as i32)),
+                    user_agent: Set(last_log.user_agent),
+                    ..Default::default()
+                }
+                .insert(db)
+                .await?;
+                Ok(true)
+            }
+        }
+    }
+
+    /**
+     * 城市新增访客数+1
+     */
+    async fn increase_city_visitor(
+        city: String,
+        db: &DatabaseConnection,
+    ) -> Result<(), DataBaseError> {
+        match city_visitor::Entity::find_by_id(city.clone())
+            .one(db)
+            .await?
+        {
+            Some(model) => {
+                let uv = model.uv;
+                let mut active: city_visitor::ActiveModel = model.into();
+                active.uv = Set(uv + 1);
+                active.update(db).await?;
+            }
+            None => {
+                city_visitor::ActiveModel {
+                    city: Set(city),
+                    uv: Set(1),
+                }
+                .insert(db)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * 从IP来源(国家|区域|省份|城市|运营商)中获取城市
+     */
+    fn get_city(ip_source: &str) -> Option<String> {
+        ip_source
+            .split('|')
+            .nth(3)
+            .map(str::trim)
+            .filter(|city| !city.is_empty() && *city != "0")
+            .map(String::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VisitService;
+
+    #[test]
+    fn test_get_city() {
+        assert_eq!(
+            VisitService::get_city("中国|0|浙江省|杭州市|电信"),
+            Some("杭州市".to_string())
+        );
+        assert_eq!(VisitService::get_city("美国|0|0|0|0"), None);
+        assert_eq!(VisitService::get_city(""), None);
+    }
 }